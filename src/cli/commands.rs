@@ -24,14 +24,21 @@ use std::vec;
 
 use indexmap::IndexMap;
 use sqlparser::ast::{
-    Assignment, BinaryOperator, ColumnDef, ColumnOption, DataType, Expr, Function, LimitClause,
-    ObjectName, OrderBy, Select, SelectItem, SetExpr, Statement, TableConstraint, TableFactor,
-    TableObject, TableWithJoins, Use, Value, ValueWithSpan,
+    AlterTableOperation, Assignment, BinaryOperator, CharacterLength, ColumnDef, ColumnOption,
+    DataType, Distinct, DuplicateTreatment, EnumMember, ExactNumberInfo, Expr, Function,
+    FunctionArgExpr, FunctionArguments, GroupByExpr, Ident, LimitClause, ObjectName, OrderBy,
+    OrderByKind, Query, RenameTableNameKind, Select, SelectItem, Set, SetExpr, Statement,
+    TableConstraint, TableFactor, TableObject, TableWithJoins, Use, Value, ValueWithSpan,
 };
 
-use crate::cli::messages::{highlight_argument, system_message};
+use crate::cli::filter::{FilterCompiler, column_not_found, parse_expr};
+use crate::cli::messages::{
+    database_list_message, did_you_mean, highlight_argument, inform, system_message,
+};
+use crate::cli::plan::{SelectPlan, TableAccess};
+use crate::errors::EngineError;
 use crate::functions::{aggregators, scalars};
-use crate::persistence::{Database, Row, TableReader};
+use crate::persistence::{self, ColumnInformation, Database, Row, Schema, Table, TableReader};
 use crate::sessions::session::Session;
 
 /// The executor class that runs the statements.
@@ -61,19 +68,30 @@ pub struct SqlExecutor {
 ///
 /// The [`SqlResult`] is a simple struct that stores the displayable
 /// [`TableReader`] and [`Row`] objects inside it and returns them to the main
-/// terminal thread after completion.
-///
-/// # Improvements
-/// - Add separate displayers for CLI to show rows and tables via the readers or
-/// use the Display trait more effectively (food for thought).
+/// terminal thread after completion, where [`SqlResult::display`] renders them.
 pub struct SqlResult {
     pub table: Option<TableReader>,
     pub n_rows_processed: Option<usize>,
+    /// The single row a statement affected, e.g. the row of a one-row INSERT.
+    pub row: Option<Row>,
+}
+
+impl SqlResult {
+    pub fn display(&self) -> String {
+        //! Render the result for the terminal: the rows of a query as an ASCII
+        //! table, else the single affected row, else the number of affected rows.
+
+        match (&self.table, &self.row) {
+            (Some(table), _) => table.to_string(),
+            (None, Some(row)) => row.to_string(),
+            (None, None) => format!("{} row(s) affected", self.n_rows_processed.unwrap_or(0)),
+        }
+    }
 }
 
 impl Display for SqlResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.table.as_ref().unwrap())
+        writeln!(f, "{}", self.display())
     }
 }
 
@@ -105,10 +123,16 @@ pub enum SelectColumn {
 /// Works for cases like:
 /// - COUNT(*)       - now
 /// - COUNT(name)    - now
+/// - ADD(age, 2)    - now, a literal value
+/// - MIN(name COLLATE nocase) - now, a column with a collation
+/// - COUNT(DISTINCT name) - now, a leading distinct marker
 /// - COUNT(age * 2) - in future
 pub enum FunctionArg {
     Wildcard,
+    Distinct,
     Column(String),
+    Literal(String),
+    Collated(String, String),
 }
 
 /// A type specifier for the type of [`SelectColumn::Function`].
@@ -124,9 +148,157 @@ pub enum FunctionType {
     Aggregator,
 }
 
+/// A [`super::filter::RowFilter`] without the thread-safety bounds, the way tables take it.
+type TableFilter = Box<dyn Fn(&Row) -> bool>;
+
+fn _query_calls_volatile(query: &Query) -> bool {
+    //! Whether the `query` calls a volatile scalar anywhere, see
+    //! [`crate::functions::scalars::Scalar::is_volatile`].
+    //!
+    //! Only the clauses of a plain `SELECT` are looked at, any other query
+    //! counts as volatile, so its result is never reused.
+
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return true;
+    };
+
+    let projection = select.projection.iter().any(|item| match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+            _expr_calls_volatile(expr)
+        }
+        SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => false,
+    });
+    let group_by = match &select.group_by {
+        GroupByExpr::Expressions(exprs, _) => exprs.iter().any(_expr_calls_volatile),
+        GroupByExpr::All(_) => false,
+    };
+    let order_by = query
+        .order_by
+        .as_ref()
+        .is_some_and(|order_by| match &order_by.kind {
+            OrderByKind::Expressions(exprs) => exprs
+                .iter()
+                .any(|order_expr| _expr_calls_volatile(&order_expr.expr)),
+            OrderByKind::All(_) => false,
+        });
+
+    projection
+        || group_by
+        || order_by
+        || select.selection.as_ref().is_some_and(_expr_calls_volatile)
+        || select.having.as_ref().is_some_and(_expr_calls_volatile)
+}
+
+fn _expr_calls_volatile(expr: &Expr) -> bool {
+    //! Whether the `expr` calls a volatile scalar anywhere. Expressions the
+    //! executor does not know count as volatile.
+
+    match expr {
+        Expr::Identifier(_) | Expr::CompoundIdentifier(_) | Expr::Value(_) => false,
+        Expr::Function(func) => {
+            let volatile = func
+                .name
+                .0
+                .last()
+                .and_then(|part| part.as_ident())
+                .is_some_and(|ident| scalars::is_volatile(&ident.value));
+
+            volatile
+                || match &func.args {
+                    FunctionArguments::None => false,
+                    FunctionArguments::Subquery(query) => _query_calls_volatile(query),
+                    FunctionArguments::List(list) => list.args.iter().any(|arg| match arg {
+                        sqlparser::ast::FunctionArg::Unnamed(arg)
+                        | sqlparser::ast::FunctionArg::Named { arg, .. }
+                        | sqlparser::ast::FunctionArg::ExprNamed { arg, .. } => match arg {
+                            FunctionArgExpr::Expr(expr) => _expr_calls_volatile(expr),
+                            FunctionArgExpr::QualifiedWildcard(_) | FunctionArgExpr::Wildcard => {
+                                false
+                            }
+                        },
+                    }),
+                }
+        }
+        Expr::BinaryOp { left, right, .. }
+        | Expr::IsDistinctFrom(left, right)
+        | Expr::IsNotDistinctFrom(left, right) => {
+            _expr_calls_volatile(left) || _expr_calls_volatile(right)
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Collate { expr, .. } => _expr_calls_volatile(expr),
+        Expr::Between {
+            expr, low, high, ..
+        } => _expr_calls_volatile(expr) || _expr_calls_volatile(low) || _expr_calls_volatile(high),
+        Expr::InList { expr, list, .. } => {
+            _expr_calls_volatile(expr) || list.iter().any(_expr_calls_volatile)
+        }
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            _expr_calls_volatile(expr) || _expr_calls_volatile(pattern)
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            _expr_calls_volatile(expr) || _query_calls_volatile(subquery)
+        }
+        Expr::Subquery(query)
+        | Expr::Exists {
+            subquery: query, ..
+        } => _query_calls_volatile(query),
+        _ => true,
+    }
+}
+
+fn _extract_column_checks(column_definition: &ColumnDef) -> Vec<(String, String)> {
+    //! The CHECK constraints of a column, as `(column, expression)` pairs.
+    //!
+    //! They are kept out of the column definition strings, whose tokens are split
+    //! on spaces, so an expression may hold any literal.
+
+    column_definition
+        .options
+        .iter()
+        .filter_map(|option| match &option.option {
+            ColumnOption::Check(constraint) => Some((
+                column_definition.name.value.clone(),
+                constraint.expr.to_string(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn _table_not_found(database: &Database, table_name: &str) -> EngineError {
+    //! The error for a missing table, hinting at the closest existing one.
+
+    let table_names = database.get_table_names();
+    EngineError::TableNotFound(system_message(
+        "system",
+        format!(
+            "Table {} does not exist!{}",
+            highlight_argument(table_name),
+            did_you_mean(table_name, table_names.iter().map(String::as_str))
+        ),
+    ))
+}
+
 enum SqlExecutorSelectMode {
     Column,
     Aggregate,
+    Group,
+}
+
+fn _function_select_mode(function: &SelectColumn) -> SqlExecutorSelectMode {
+    //! The mode a projection starting with `function` is selected in: aggregators
+    //! fold the table into one row, scalars are applied to the rows of a column select.
+
+    match function {
+        SelectColumn::Function {
+            function_type: FunctionType::Aggregator,
+            ..
+        } => SqlExecutorSelectMode::Aggregate,
+        _ => SqlExecutorSelectMode::Column,
+    }
 }
 
 impl Display for SelectColumn {
@@ -147,9 +319,13 @@ impl Display for SelectColumn {
                 name, args, alias, ..
             } => {
                 let mut arg_names = Vec::new();
+                let mut distinct = "";
 
                 for arg in args.iter() {
-                    arg_names.push(format!("{}", arg));
+                    match arg {
+                        FunctionArg::Distinct => distinct = "DISTINCT ",
+                        _ => arg_names.push(format!("{}", arg)),
+                    }
                 }
 
                 let alias_name = {
@@ -160,7 +336,14 @@ impl Display for SelectColumn {
                     }
                 };
 
-                write!(f, "{}({}){}", name, arg_names.join(", "), alias_name)
+                write!(
+                    f,
+                    "{}({}{}){}",
+                    name,
+                    distinct,
+                    arg_names.join(", "),
+                    alias_name
+                )
             }
         }
     }
@@ -170,7 +353,10 @@ impl Display for FunctionArg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Column(name) => write!(f, "{}", name.clone()),
+            Self::Literal(value) => write!(f, "'{}'", value),
             Self::Wildcard => write!(f, "*"),
+            Self::Distinct => write!(f, "DISTINCT"),
+            Self::Collated(name, collation) => write!(f, "{} COLLATE {}", name, collation),
         }
     }
 }
@@ -189,9 +375,21 @@ impl SqlExecutor {
         //! a [`FunctionArg`] object.
 
         match arg {
+            sqlparser::ast::FunctionArgExpr::Expr(Expr::Identifier(ident)) => {
+                Some(FunctionArg::Column(ident.value.clone()))
+            }
+            sqlparser::ast::FunctionArgExpr::Expr(Expr::Collate { expr, collation }) => {
+                match expr.as_ref() {
+                    Expr::Identifier(ident) => Some(FunctionArg::Collated(
+                        ident.value.clone(),
+                        self._parse_object_name(collation),
+                    )),
+                    _ => None,
+                }
+            }
             sqlparser::ast::FunctionArgExpr::Expr(expr) => {
-                let expr_string = self._parse_expr(expr);
-                Some(FunctionArg::Column(expr_string.unwrap()))
+                let expr_string = parse_expr(expr);
+                Some(FunctionArg::Literal(expr_string.unwrap()))
             }
             sqlparser::ast::FunctionArgExpr::Wildcard => Some(FunctionArg::Wildcard),
             _ => None,
@@ -216,20 +414,41 @@ impl SqlExecutor {
             _fni.value.clone()
         };
 
-        let func_args = match &func.args {
-            sqlparser::ast::FunctionArguments::List(list) => list
-                .args
-                .iter()
-                .filter_map(|item| match item {
-                    sqlparser::ast::FunctionArg::Unnamed(arg) => {
-                        self._extract_function_argument(arg)
-                    }
-                    _ => None,
-                })
-                .collect(),
+        let (func_args, distinct) = match &func.args {
+            sqlparser::ast::FunctionArguments::List(list) => (
+                list.args
+                    .iter()
+                    .filter_map(|item| match item {
+                        sqlparser::ast::FunctionArg::Unnamed(arg) => {
+                            self._extract_function_argument(arg)
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<FunctionArg>>(),
+                matches!(list.duplicate_treatment, Some(DuplicateTreatment::Distinct)),
+            ),
             _ => return Err("Invalid type of function arguments. Check your query.".to_string()),
         };
 
+        // DISTINCT travels as a leading marker argument, only COUNT understands it
+        let func_args = if distinct {
+            if !func_name.eq_ignore_ascii_case("COUNT") {
+                return Err(system_message(
+                    "exctr",
+                    format!(
+                        "Invalid {}; DISTINCT is only supported inside COUNT.",
+                        highlight_argument(&func_name)
+                    ),
+                ));
+            }
+
+            std::iter::once(FunctionArg::Distinct)
+                .chain(func_args)
+                .collect()
+        } else {
+            func_args
+        };
+
         let func_type = if aggregators::is_allowed(&func_name) {
             FunctionType::Aggregator
         } else if scalars::is_allowed(&func_name) {
@@ -255,21 +474,30 @@ impl SqlExecutor {
     fn _extract_column_names(
         &self,
         select: &Select,
+        grouped: bool,
     ) -> Result<(Vec<SelectColumn>, SqlExecutorSelectMode), String> {
+        //! Parse the projection of a SELECT into its [`SelectColumn`]s.
+        //!
+        //! Columns and aggregators cannot be mixed, unless the query is `grouped`, in which
+        //! case the projection is validated against the GROUP BY list by the caller.
+
         let mut column_names = Vec::new();
         let mut select_mode: Option<SqlExecutorSelectMode> = None;
 
         for item in &select.projection {
             match item {
                 SelectItem::ExprWithAlias { expr, alias } => {
-                    // TODO: SELECT col1 AS alias, ... FROM
+                    // SELECT col1 AS alias, ... FROM
 
+                    let expr = &self._unqualify_column(expr, select)?;
                     match expr {
                         sqlparser::ast::Expr::Identifier(ident) => {
                             // Insert a [`SelectColumn::Column`]
                             let column_name = ident.value.clone();
 
-                            if let Some(mode) = &select_mode {
+                            if let Some(mode) = &select_mode
+                                && !grouped
+                            {
                                 match mode {
                                     SqlExecutorSelectMode::Aggregate => {
                                         return Err(system_message(
@@ -298,7 +526,9 @@ impl SqlExecutor {
                             let function =
                                 self._extract_function(func, Some(alias.value.clone()))?;
 
-                            if let Some(mode) = &select_mode {
+                            if let Some(mode) = &select_mode
+                                && !grouped
+                            {
                                 match mode {
                                     SqlExecutorSelectMode::Column => match &function {
                                         SelectColumn::Function {
@@ -319,15 +549,31 @@ impl SqlExecutor {
                                         },
                                         _ => {}
                                     },
+                                    SqlExecutorSelectMode::Aggregate => match &function {
+                                        SelectColumn::Function {
+                                            name,
+                                            function_type: FunctionType::Scalar,
+                                            ..
+                                        } => {
+                                            return Err(system_message(
+                                                "exctr",
+                                                format!(
+                                                    "Invalid {}; scalars not allowed with aggregators.",
+                                                    highlight_argument(name)
+                                                ),
+                                            ));
+                                        }
+                                        _ => {}
+                                    },
                                     _ => {}
                                 }
                             }
 
-                            column_names.push(function);
-
                             if select_mode.is_none() {
-                                select_mode = Some(SqlExecutorSelectMode::Aggregate);
+                                select_mode = Some(_function_select_mode(&function));
                             }
+
+                            column_names.push(function);
                         }
                         _ => {
                             return Err(system_message(
@@ -343,12 +589,15 @@ impl SqlExecutor {
                     // Could be made better using the _parse_expr after
                     // matching identifier
 
+                    let expr = &self._unqualify_column(expr, select)?;
                     match expr {
                         sqlparser::ast::Expr::Identifier(ident) => {
                             // Insert a [`SelectColumn::Column`]
                             let column_name = ident.value.clone();
 
-                            if let Some(mode) = &select_mode {
+                            if let Some(mode) = &select_mode
+                                && !grouped
+                            {
                                 match mode {
                                     SqlExecutorSelectMode::Aggregate => {
                                         return Err(system_message(
@@ -376,7 +625,9 @@ impl SqlExecutor {
                             // Insert a [`SelectColumn::Function`]
                             let function = self._extract_function(func, None)?;
 
-                            if let Some(mode) = &select_mode {
+                            if let Some(mode) = &select_mode
+                                && !grouped
+                            {
                                 match mode {
                                     SqlExecutorSelectMode::Column => match &function {
                                         SelectColumn::Function {
@@ -397,15 +648,31 @@ impl SqlExecutor {
                                         },
                                         _ => {}
                                     },
+                                    SqlExecutorSelectMode::Aggregate => match &function {
+                                        SelectColumn::Function {
+                                            name,
+                                            function_type: FunctionType::Scalar,
+                                            ..
+                                        } => {
+                                            return Err(system_message(
+                                                "exctr",
+                                                format!(
+                                                    "Invalid {}; scalars not allowed with aggregators.",
+                                                    highlight_argument(name)
+                                                ),
+                                            ));
+                                        }
+                                        _ => {}
+                                    },
                                     _ => {}
                                 }
                             }
 
-                            column_names.push(function);
-
                             if select_mode.is_none() {
-                                select_mode = Some(SqlExecutorSelectMode::Aggregate);
+                                select_mode = Some(_function_select_mode(&function));
                             }
+
+                            column_names.push(function);
                         }
                         _ => {
                             return Err(system_message(
@@ -440,6 +707,243 @@ impl SqlExecutor {
         ))
     }
 
+    fn _unqualify_column(&self, expr: &Expr, select: &Select) -> Result<Expr, String> {
+        //! Turn a qualified `table.column` into a plain `column` identifier, once the
+        //! qualifier is checked against the table in FROM, either its name or its alias.
+        //!
+        //! Any other expression is given back as it is.
+        //!
+        //! # Issues
+        //! - There are no joins yet, so the only table a column can belong to is the
+        //!   single one in FROM.
+
+        let Expr::CompoundIdentifier(idents) = expr else {
+            return Ok(expr.clone());
+        };
+
+        let [qualifier, column] = idents.as_slice() else {
+            return Err(system_message(
+                "exctr",
+                format!(
+                    "Invalid column {}; use column or table.column.",
+                    highlight_argument(&expr.to_string())
+                ),
+            ));
+        };
+
+        let table_with_joins = select.from.first().ok_or(system_message(
+            "exctr",
+            "There is no table name after FROM keyword.".to_string(),
+        ))?;
+        let table_name = self._extract_table_name(table_with_joins)?;
+        let table_alias = match &table_with_joins.relation {
+            TableFactor::Table {
+                alias: Some(alias), ..
+            } => Some(alias.name.value.clone()),
+            _ => None,
+        };
+
+        if qualifier.value == table_name || Some(&qualifier.value) == table_alias.as_ref() {
+            Ok(Expr::Identifier(column.clone()))
+        } else {
+            Err(system_message(
+                "exctr",
+                format!(
+                    "Invalid {}; there is no table {} in FROM.",
+                    highlight_argument(&expr.to_string()),
+                    highlight_argument(&qualifier.value)
+                ),
+            ))
+        }
+    }
+
+    fn _extract_group_by(&self, select: &Select) -> Result<Vec<String>, String> {
+        //! Get the names of the GROUP BY columns, if any.
+        //!
+        //! Only plain column identifiers are allowed for grouping for now.
+
+        match &select.group_by {
+            GroupByExpr::Expressions(exprs, _) => exprs
+                .iter()
+                .map(|expr| match expr {
+                    Expr::Identifier(ident) => Ok(ident.value.clone()),
+                    _ => Err(system_message(
+                        "exctr",
+                        format!("Invalid GROUP BY expression '{}'!", expr),
+                    )),
+                })
+                .collect(),
+            GroupByExpr::All(_) => Err(system_message(
+                "exctr",
+                "GROUP BY ALL is not supported by the engine yet!".to_string(),
+            )),
+        }
+    }
+
+    fn _validate_group_projection(
+        &self,
+        column_names: &[SelectColumn],
+        group_columns: &[String],
+    ) -> Result<(), String> {
+        //! In a grouped query, every plain column must be one of the GROUP BY columns,
+        //! everything else must be an aggregator.
+
+        for column in column_names.iter() {
+            match column {
+                SelectColumn::Column { name, .. } if !group_columns.contains(name) => {
+                    return Err(system_message(
+                        "exctr",
+                        format!(
+                            "Invalid {}; column is not in GROUP BY.",
+                            highlight_argument(name)
+                        ),
+                    ));
+                }
+                SelectColumn::Function {
+                    name,
+                    function_type: FunctionType::Scalar,
+                    ..
+                } => {
+                    return Err(system_message(
+                        "exctr",
+                        format!(
+                            "Invalid {}; scalars not allowed with GROUP BY.",
+                            highlight_argument(name)
+                        ),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn _extract_having(
+        &self,
+        having: &Expr,
+        group_columns: &[String],
+        grouped_columns: &mut Vec<SelectColumn>,
+    ) -> Result<Expr, String> {
+        //! Prepare a HAVING predicate to be run over the grouped rows.
+        //!
+        //! Every aggregator call and group column inside the predicate is appended to the
+        //! `grouped_columns` as a hidden column, and is replaced inside the predicate by
+        //! an identifier pointing to that hidden column. Any other column is an error,
+        //! since it has no single value inside a group.
+        //!
+        //! Returns the rewritten predicate.
+
+        let hide = |column: SelectColumn, grouped_columns: &mut Vec<SelectColumn>| {
+            let hidden_name = format!("__having_{}", grouped_columns.len());
+            let column = match column {
+                SelectColumn::Column { name, .. } => SelectColumn::Column {
+                    name,
+                    alias: Some(hidden_name.clone()),
+                },
+                SelectColumn::Function {
+                    name,
+                    args,
+                    function_type,
+                    ..
+                } => SelectColumn::Function {
+                    name,
+                    args,
+                    function_type,
+                    alias: Some(hidden_name.clone()),
+                },
+            };
+
+            grouped_columns.push(column);
+            Expr::Identifier(Ident::new(hidden_name))
+        };
+
+        match having {
+            Expr::Identifier(ident) => {
+                if !group_columns.contains(&ident.value) {
+                    return Err(system_message(
+                        "exctr",
+                        format!(
+                            "Invalid {}; HAVING only allows GROUP BY columns and aggregators.",
+                            highlight_argument(&ident.value)
+                        ),
+                    ));
+                }
+
+                let column = SelectColumn::Column {
+                    name: ident.value.clone(),
+                    alias: None,
+                };
+                Ok(hide(column, grouped_columns))
+            }
+            Expr::Function(func) => {
+                let function = self._extract_function(func, None)?;
+
+                if let SelectColumn::Function {
+                    name,
+                    function_type: FunctionType::Scalar,
+                    ..
+                } = &function
+                {
+                    return Err(system_message(
+                        "exctr",
+                        format!(
+                            "Invalid {}; scalars not allowed in HAVING.",
+                            highlight_argument(name)
+                        ),
+                    ));
+                }
+
+                Ok(hide(function, grouped_columns))
+            }
+            Expr::BinaryOp { left, op, right } => Ok(Expr::BinaryOp {
+                left: Box::new(self._extract_having(left, group_columns, grouped_columns)?),
+                op: op.clone(),
+                right: Box::new(self._extract_having(right, group_columns, grouped_columns)?),
+            }),
+            Expr::Nested(inner) => Ok(Expr::Nested(Box::new(self._extract_having(
+                inner,
+                group_columns,
+                grouped_columns,
+            )?))),
+            Expr::Value(_) => Ok(having.clone()),
+            _ => Err(system_message(
+                "exctr",
+                format!("Invalid HAVING expression '{}'!", having),
+            )),
+        }
+    }
+
+    fn _apply_having(
+        &self,
+        grouped_result: TableReader,
+        having: &Expr,
+        grouped_columns: &[SelectColumn],
+    ) -> Result<TableReader, String> {
+        //! Filter the grouped rows with a predicate from [`SqlExecutor::_extract_having`].
+        //!
+        //! Aggregated values are compared as numbers.
+
+        let having_schema_vec: Vec<(String, ColumnInformation)> = {
+            let schema = grouped_result.schema.read().unwrap();
+            schema
+                .get_vec()
+                .iter()
+                .zip(grouped_columns.iter())
+                .map(|((col_name, col_info), column)| match column {
+                    SelectColumn::Function { .. } => (
+                        col_name.clone(),
+                        ColumnInformation::from(persistence::DataType::Number, None, true),
+                    ),
+                    SelectColumn::Column { .. } => (col_name.clone(), col_info.clone()),
+                })
+                .collect()
+        };
+
+        let filter = FilterCompiler::new(&having_schema_vec).compile(having)?;
+        grouped_result.filter(filter)
+    }
+
     fn _extract_table_name(&self, table_with_joins: &TableWithJoins) -> Result<String, String> {
         match &table_with_joins.relation {
             TableFactor::Table { name, .. } => Ok(name
@@ -478,13 +982,64 @@ impl SqlExecutor {
 
         match column_definition.data_type {
             DataType::Int(_) => col_def.push("num".to_string()),
+            DataType::Varchar(Some(CharacterLength::IntegerLength { length, .. })) => {
+                col_def.push(format!("txt({})", length))
+            }
             DataType::Varchar(_) => col_def.push("txt".to_string()),
+            DataType::Date => col_def.push("date".to_string()),
+            DataType::JSON => col_def.push("json".to_string()),
+            DataType::Uuid => col_def.push("uuid".to_string()),
+            DataType::Bool | DataType::Boolean => col_def.push("bool".to_string()),
+            DataType::Decimal(info) | DataType::Numeric(info) | DataType::Dec(info) => {
+                // The MySQL defaults, DECIMAL alone is DECIMAL(10,0)
+                let (precision, scale) = match info {
+                    ExactNumberInfo::None => (10, 0),
+                    ExactNumberInfo::Precision(precision) => (precision, 0),
+                    ExactNumberInfo::PrecisionAndScale(precision, scale) => (precision, scale),
+                };
+                col_def.push(format!("dec({},{})", precision, scale))
+            }
+            DataType::Enum(members, _) => {
+                let values: Vec<String> = members
+                    .into_iter()
+                    .map(|member| match member {
+                        EnumMember::Name(value) | EnumMember::NamedValue(value, _) => value,
+                    })
+                    .collect();
+
+                // Values travel inside a single space-delimited, comma-separated token
+                if let Some(value) = values
+                    .iter()
+                    .find(|value| value.contains(|c: char| c == ',' || c.is_whitespace()))
+                {
+                    return Err(system_message(
+                        "exctr",
+                        format!(
+                            "Invalid ENUM value {} for column {}; values can not hold spaces or commas.",
+                            highlight_argument(&format!("'{}'", value)),
+                            col_name
+                        ),
+                    ));
+                }
+                col_def.push(format!("enum({})", values.join(",")))
+            }
             _ => return Err(format!("Invalid type for column {}", col_name)),
         }
 
         for option in column_definition.options {
             match option.option {
                 ColumnOption::PrimaryKey(_) => col_def.push("pk".to_string()),
+                ColumnOption::Unique(_) => col_def.push("unique".to_string()),
+                ColumnOption::Null => col_def.push("null".to_string()),
+                // CHECK constraints are passed apart, see `_extract_column_checks`
+                ColumnOption::Check(_) => {}
+                ColumnOption::DialectSpecific(tokens)
+                    if tokens
+                        .iter()
+                        .any(|token| token.to_string().eq_ignore_ascii_case("AUTO_INCREMENT")) =>
+                {
+                    col_def.push("auto".to_string())
+                }
                 _ => return Err(format!("Invalid option for column {}", col_name)),
             }
         }
@@ -496,20 +1051,23 @@ impl SqlExecutor {
         &self,
         column_names: Vec<String>,
         constraint_definition: TableConstraint,
-    ) -> Result<(String, Vec<String>), String> {
+    ) -> Result<Vec<(String, Vec<String>)>, String> {
         //! Foreign keys do not appear inside the `option` field in a column definition, like
-        //! primary key does, so it needs to be parsed as a constraint.
+        //! primary key does, so it needs to be parsed as a constraint. The same goes for a
+        //! composite `PRIMARY KEY (col, ...)` declared after the columns.
         //!
         //! First, check that the column map already contains the definition for this column, and
         //! if there is none, then error out.
-
-        let mut column_constraints = vec![];
+        //!
+        //! Returns the column names alongwith the tokens to append to their definitions.
 
         match constraint_definition {
             TableConstraint::ForeignKey(fk) => {
+                let mut column_constraints = vec![];
+
                 let col_name = fk
                     .columns
-                    .get(0)
+                    .first()
                     .expect("Expected a column name to this foreign key.")
                     .value
                     .clone();
@@ -522,7 +1080,7 @@ impl SqlExecutor {
 
                 let ref_table_name = match fk.foreign_table {
                     ObjectName(obj) => obj
-                        .get(0)
+                        .first()
                         .expect("Expected a table name to the foreign key constraint")
                         .as_ident()
                         .unwrap()
@@ -531,80 +1089,41 @@ impl SqlExecutor {
                 };
                 let ref_col_name = fk
                     .referred_columns
-                    .get(0)
+                    .first()
                     .expect("Expected a column name to this foreign key.")
                     .value
                     .clone();
 
-                let fk = vec![ref_table_name, ref_col_name];
+                let fk = [ref_table_name, ref_col_name];
                 column_constraints.push(fk.join("."));
 
-                Ok((col_name, column_constraints))
-            }
-            _ => {
-                return Err(format!(
-                    "Invalid option for column. Check your statement again."
-                ));
+                Ok(vec![(col_name, column_constraints)])
             }
-        }
-    }
+            TableConstraint::PrimaryKey(pk) => {
+                let mut key_columns = vec![];
 
-    fn _parse_expr(&self, expr: &Expr) -> Result<String, String> {
-        match expr {
-            Expr::Value(value) => self._parse_value(&value),
-            Expr::Identifier(ident) => Ok(ident.value.clone()),
-            Expr::UnaryOp { op, expr } => {
-                let value = self._parse_expr(expr)?;
-                let prefix = match op {
-                    sqlparser::ast::UnaryOperator::Minus => "-",
-                    _ => {
-                        return Err(system_message(
-                            "system",
-                            "Unsupported unary operator.".to_string(),
-                        ));
-                    }
-                };
+                for column in pk.columns.iter() {
+                    let col_name = parse_expr(&column.column.expr)?;
 
-                Ok(format!("{}{}", prefix, value))
-            }
-            _ => {
-                return Err(system_message(
-                    "system",
-                    "Unsupported value. Check your query.".to_string(),
-                ));
-            }
-        }
-    }
+                    if !column_names.contains(&col_name) {
+                        return Err(format!("The column {} was not defined.", &col_name));
+                    }
 
-    fn _parse_value(&self, value: &ValueWithSpan) -> Result<String, String> {
-        //! Match the [`Value`] object properly to its subtype and return the
-        //! engine-specific (value, datatype) mapping.
-        //!
-        //! This function is a utility to allow data insertion format to align
-        //! with the one accepted by the persistence API
+                    key_columns.push((col_name, vec!["pk".to_string()]));
+                }
 
-        let value = match &value.value {
-            Value::Number(value, _) => value,
-            Value::SingleQuotedString(value) => value,
-            Value::DoubleQuotedString(value) => value,
-            _ => {
-                return Err(system_message(
-                    "system",
-                    format!(
-                        "Unsupported value: {}",
-                        highlight_argument(&value.to_string())
-                    ),
-                ));
+                Ok(key_columns)
             }
-        };
-
-        Ok(value.into())
+            _ => Err("Invalid option for column. Check your statement again.".to_string()),
+        }
     }
 
-    fn _extract_row(&self, values: Vec<Expr>) -> Result<Vec<String>, String> {
-        //! Extract a row form a [`Vec<Expr>`] to create a [`Vec<String>`]
+    fn _extract_row(&self, values: Vec<Expr>) -> Result<Vec<Option<String>>, String> {
+        //! Extract a row form a [`Vec<Expr>`] to create a [`Vec<Option<String>>`]
         //! which is compatible with the persistence api.
         //!
+        //! A `NULL` literal becomes [`None`], so it stays apart from an empty `''` text.
+        //!
         //! Internally, uses an extraction function to extract all supported
         //! expression types into a String that can be stored inside the engine
         //!
@@ -613,83 +1132,116 @@ impl SqlExecutor {
         //! to call recursively itself, untul a [`ValueWithSpan`] is obtained, that
         //! can then be parsed using the [`self._parse_value_to_engine`] function.
 
-        values.into_iter().map(|e| self._parse_expr(&e)).collect()
+        values
+            .into_iter()
+            .map(|e| match e {
+                Expr::Value(ValueWithSpan {
+                    value: Value::Null, ..
+                }) => Ok(None),
+                e => parse_expr(&e).map(Some),
+            })
+            .collect()
     }
 
-    fn _parse_selection(
-        &self,
-        selection: &Expr,
-        table_schema_vec: &Vec<String>,
-    ) -> Result<Box<dyn Fn(&Row) -> bool>, String> {
-        //! Parse the [`Expr::BinaryOp`] variant to a filter.
-        //!
-        //! Returns a closure `Fn(&Row) -> bool` that takes a row to check
-        //! if it is a fit over the filter. I intend it to be used inside a filter
-        //! function on a tabler reader as well.
-        //!
-        //! Filter flow: the filter function can NOT directly access the table and
-        //! the row index, therefore, we rely directly on the database to do this for
-        //! us. In that case, we will have to pass the left and right values to the
-        //! database, restructure them to map them to the table and run the final
-        //! filter to get the resultant rows.
+    fn _index_range(&self, selection: &Expr) -> Option<(String, i64, i64)> {
+        //! Find a `col BETWEEN low AND high` or `col = value` over integers that
+        //! every row matching the `selection` must also satisfy, so an ordered
+        //! index can serve it.
 
         match selection {
-            Expr::BinaryOp { left, op, right } => match op {
-                BinaryOperator::Or => {
-                    let left_filter = self._parse_selection(left, table_schema_vec)?;
-                    let right_filter = self._parse_selection(right, table_schema_vec)?;
-
-                    Ok(Box::new(move |row| left_filter(row) || right_filter(row)))
-                }
-                BinaryOperator::And => {
-                    let left_filter = self._parse_selection(left, table_schema_vec)?;
-                    let right_filter = self._parse_selection(right, table_schema_vec)?;
-
-                    Ok(Box::new(move |row| left_filter(row) && right_filter(row)))
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } => match left.as_ref() {
+                Expr::Identifier(ident) => {
+                    let value = parse_expr(right).ok()?.parse::<i64>().ok()?;
+                    Some((ident.value.clone(), value, value))
                 }
-                BinaryOperator::Eq => {
-                    let (col_index, value) =
-                        self._parse_operands(left.as_ref(), right.as_ref(), table_schema_vec)?;
-
-                    Ok(Box::new(move |row| {
-                        row.0
-                            .get(col_index)
-                            .and_then(|v| v.as_ref())
-                            .map_or(false, |v| v == &value)
-                    }))
-                }
-                BinaryOperator::NotEq => {
-                    let (col_index, value) =
-                        self._parse_operands(left.as_ref(), right.as_ref(), table_schema_vec)?;
-
-                    Ok(Box::new(move |row| {
-                        row.0
-                            .get(col_index)
-                            .and_then(|v| v.as_ref())
-                            .map_or(false, |v| v != &value)
-                    }))
+                _ => None,
+            },
+            Expr::Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            } => match expr.as_ref() {
+                Expr::Identifier(ident) => {
+                    let low = parse_expr(low).ok()?.parse::<i64>().ok()?;
+                    let high = parse_expr(high).ok()?.parse::<i64>().ok()?;
+                    Some((ident.value.clone(), low, high))
                 }
-                _ => Err(format!("Invalid query filter. Check your query.")),
+                _ => None,
             },
-            _ => Err(format!("Invalid column selection. Check your query.")),
+            Expr::Nested(inner) => self._index_range(inner),
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+            } => self._index_range(left).or_else(|| self._index_range(right)),
+            _ => None,
         }
     }
 
-    fn _parse_operands(
-        &self,
-        left: &Expr,
-        right: &Expr,
-        table_schema_vec: &Vec<String>,
-    ) -> Result<(usize, String), String> {
-        let col_name = self._parse_expr(left)?;
-        let value = self._parse_expr(right)?;
-
-        let col_index = table_schema_vec
-            .iter()
-            .position(|col| col == &col_name)
-            .ok_or_else(|| format!("Column {} does not exist!", highlight_argument(&col_name)))?;
-
-        Ok((col_index, value))
+    fn _table_access(&self, table: &Table, selection: Option<&Expr>) -> TableAccess {
+        //! Decide how to read the rows of `table` for a query with a WHERE `selection`.
+
+        match selection.and_then(|selection| self._index_range(selection)) {
+            Some((column, low, high)) if table.has_ordered_index(&column) => {
+                TableAccess::IndexRange { column, low, high }
+            }
+            _ => TableAccess::FullScan,
+        }
+    }
+
+    fn _plan_select(&self, query: &Query) -> Result<SelectPlan, String> {
+        //! Build the [`SelectPlan`] of a SELECT query, without running it.
+
+        let SetExpr::Select(select) = query.body.as_ref() else {
+            return Err(system_message(
+                "exctr",
+                "Only SELECT queries can be explained.".to_string(),
+            ));
+        };
+
+        let group_by = self._extract_group_by(select)?;
+        let (column_names, _) = self._extract_column_names(select, !group_by.is_empty())?;
+
+        let table_with_joins = select.from.first().ok_or(system_message(
+            "exctr",
+            "There is no table name after FROM keyword.".to_string(),
+        ))?;
+        let table_name = self._extract_table_name(table_with_joins)?;
+
+        let db_arc = self._get_db_from_session()?;
+        let database = db_arc.read().unwrap();
+        let table = database
+            .get_table(&table_name)
+            .ok_or_else(|| _table_not_found(&database, &table_name))?;
+        let access = self._table_access(&table.read().unwrap(), select.selection.as_ref());
+
+        Ok(SelectPlan {
+            table_name,
+            access,
+            filter: select
+                .selection
+                .as_ref()
+                .map(|selection| selection.to_string()),
+            group_by,
+            having: select.having.as_ref().map(|having| having.to_string()),
+            projection: column_names
+                .iter()
+                .map(|column| column.to_string())
+                .collect(),
+            order_by: query.order_by.as_ref().map(|order_by| {
+                let order_by = order_by.to_string();
+                order_by.trim().trim_start_matches("ORDER BY ").to_string()
+            }),
+            limit: query.limit_clause.as_ref().map(|limit| {
+                let limit = limit.to_string();
+                limit.trim().trim_start_matches("LIMIT ").to_string()
+            }),
+        })
     }
 
     fn _parse_assignment(&self, assignment: Assignment) -> Result<(String, String), String> {
@@ -703,7 +1255,7 @@ impl SqlExecutor {
             }
             _ => return Err(format!("Invalid column name. Check your query.")),
         };
-        let value = self._parse_expr(&assignment.value)?;
+        let value = parse_expr(&assignment.value)?;
 
         Ok((col_name, value))
     }
@@ -718,39 +1270,38 @@ impl SqlExecutor {
                 // each of the first and then the third among each group of second
                 let mut sort_index = vec![];
 
+                let schema_vec = {
+                    let reader = query_result.table.as_ref().unwrap();
+                    reader.schema.read().unwrap().get_vec().clone()
+                };
+
                 for order in expressions.iter() {
-                    if order.options.asc.is_none() {
-                        return Ok(query_result);
-                    } else {
-                        if let Some(asc) = order.options.asc {
-                            let identifier = self._parse_expr(&order.expr)?;
+                    // Only plain columns can be sorted on for now
+                    let Expr::Identifier(ident) = &order.expr else {
+                        return Err(system_message(
+                            "exctr",
+                            format!(
+                                "Can not order by {}; only columns are supported.",
+                                highlight_argument(&order.expr.to_string())
+                            ),
+                        ));
+                    };
 
-                            // get the table object ref and then derive index of that column from
-                            let schema = {
-                                let _tr = query_result.table.as_ref().unwrap();
-                                _tr.schema.read().unwrap()
-                            };
-                            let col_index = schema
-                                .get_vec()
-                                .iter()
-                                .position(|(col_name, _)| &identifier == col_name);
+                    let col_index = schema_vec
+                        .iter()
+                        .position(|(col_name, _)| col_name == &ident.value)
+                        .ok_or_else(|| column_not_found(&ident.value, &schema_vec))?;
 
-                            if let Some(col_index) = col_index {
-                                sort_index.push((col_index, asc));
-                            }
-                        }
-                    }
+                    // Ascending unless DESC is given
+                    sort_index.push((col_index, order.options.asc.unwrap_or(true)));
                 }
 
-                println!(
-                    "{}",
-                    system_message(
-                        "sorter",
-                        format!(
-                            "Sorting data by order: {}",
-                            highlight_argument(format!("{:?}", sort_index).as_str())
-                        )
-                    )
+                inform(
+                    "sorter",
+                    format!(
+                        "Sorting data by order: {}",
+                        highlight_argument(format!("{:?}", sort_index).as_str())
+                    ),
                 );
 
                 let table_reader = query_result.table.unwrap();
@@ -759,6 +1310,7 @@ impl SqlExecutor {
                 Ok(SqlResult {
                     table: Some(table_reader.order_by(sort_index)),
                     n_rows_processed: Some(table_reader_rows),
+                    row: None,
                 })
             }
             _ => {
@@ -770,6 +1322,28 @@ impl SqlExecutor {
         }
     }
 
+    fn _distinct(&self, query_result: SqlResult, distinct: &Distinct) -> Result<SqlResult, String> {
+        //! Drop the repeated rows of the results of the query.
+        //!
+        //! Returns a new [`SqlResult`] object.
+
+        if let Distinct::On(_) = distinct {
+            return Err(system_message(
+                "exctr",
+                "DISTINCT ON is not supported yet; use DISTINCT.".to_string(),
+            ));
+        }
+
+        let table_reader = query_result.table.unwrap().distinct();
+        let n_rows = table_reader.count_rows();
+
+        Ok(SqlResult {
+            table: Some(table_reader),
+            n_rows_processed: Some(n_rows),
+            row: None,
+        })
+    }
+
     fn _limit_offset(
         &self,
         query_result: SqlResult,
@@ -779,61 +1353,54 @@ impl SqlExecutor {
         //!
         //! Returns a new [`SqlResult`] object.
 
-        let mut row_limit: Option<usize> = None;
-        let mut row_offset: Option<usize> = None;
-
-        match limit_clause {
-            LimitClause::LimitOffset { limit, offset, .. } => {
-                if let Some(limit_expr) = limit {
-                    row_limit = Some(
-                        self._parse_expr(limit_expr)?
-                            .parse()
-                            .expect("String values not allowed as limits."),
-                    );
-                }
+        // A count of rows, where negative numbers and text are errors
+        let row_count = |expr: &Expr| -> Result<usize, String> {
+            let value = parse_expr(expr)?;
+            value.parse::<usize>().map_err(|_| {
+                system_message(
+                    "exctr",
+                    format!(
+                        "Invalid {}; LIMIT and OFFSET take a number of rows.",
+                        highlight_argument(&value)
+                    ),
+                )
+            })
+        };
 
-                if let Some(offset_expr) = offset {
-                    row_offset = Some(
-                        self._parse_expr(&offset_expr.value)?
-                            .parse()
-                            .expect("String values not allowed as limits."),
-                    );
-                }
-            }
+        let (row_limit, row_offset) = match limit_clause {
+            LimitClause::LimitOffset { limit, offset, .. } => (
+                limit.as_ref().map(row_count).transpose()?,
+                offset
+                    .as_ref()
+                    .map(|offset| row_count(&offset.value))
+                    .transpose()?,
+            ),
             LimitClause::OffsetCommaLimit { offset, limit } => {
-                row_offset = Some(
-                    self._parse_expr(offset)?
-                        .parse()
-                        .expect("String values not allowed as limits."),
-                );
-
-                row_limit = Some(
-                    self._parse_expr(limit)?
-                        .parse()
-                        .expect("String values not allowed as limits."),
-                );
+                (Some(row_count(limit)?), Some(row_count(offset)?))
             }
-        }
+        };
 
-        println!(
-            "{}",
-            system_message(
-                "limoft",
-                format!(
-                    "Selecting {} rows from row {} onwards.",
-                    highlight_argument(format!("{:?}", row_limit).as_str()),
-                    highlight_argument(format!("{:?}", row_offset).as_str())
-                )
-            )
+        inform(
+            "limoft",
+            format!(
+                "Selecting {} rows from row {} onwards.",
+                highlight_argument(format!("{:?}", row_limit).as_str()),
+                highlight_argument(format!("{:?}", row_offset).as_str())
+            ),
         );
 
         let old_table_reader = query_result.table.unwrap();
-        let new_table_reader = old_table_reader.offset(row_offset)?.limit(row_limit)?;
+        let new_table_reader = old_table_reader
+            .lazy()
+            .offset(row_offset)?
+            .limit(row_limit)?
+            .collect();
         let new_table_reader_rows = new_table_reader.count_rows();
 
         Ok(SqlResult {
             table: Some(new_table_reader),
             n_rows_processed: Some(new_table_reader_rows),
+            row: None,
         })
     }
 
@@ -847,6 +1414,55 @@ impl SqlExecutor {
         }
     }
 
+    fn _cache_source(&self, query: &Query) -> Option<(String, u64)> {
+        //! The key and the table version to cache the result of the `query`
+        //! under, see [`crate::sessions::cache`].
+        //!
+        //! Gives [`None`] when the result is not to be cached: the cache is off,
+        //! the query does not read a single table, or it calls a volatile scalar.
+
+        if self.session.read().unwrap().result_cache().capacity() == 0 {
+            return None;
+        }
+
+        let SetExpr::Select(select) = query.body.as_ref() else {
+            return None;
+        };
+        let [table_with_joins] = select.from.as_slice() else {
+            return None;
+        };
+        if !table_with_joins.joins.is_empty() {
+            return None;
+        }
+
+        if _query_calls_volatile(query) {
+            return None;
+        }
+        let sql = query.to_string();
+
+        // The version is read before the query runs, so a change while it runs
+        // leaves a result that is already out of date, never one that looks fresh.
+        let table_name = self._extract_table_name(table_with_joins).ok()?;
+        let database = self._get_db_from_session().ok()?;
+        let table = database.read().unwrap().get_table(&table_name)?;
+        let version = table.read().unwrap().version();
+        Some((sql, version))
+    }
+
+    fn _run_query(&self, query: &Query) -> Result<TableReader, String> {
+        //! Run a nested `SELECT`, e.g. the source of a `CREATE TABLE ... AS SELECT`,
+        //! in the same session.
+        //!
+        //! Returns the resulting rows.
+
+        let executor = SqlExecutor::new(Statement::Query(Box::new(query.clone())), &self.session);
+
+        executor.execute()?.table.ok_or(system_message(
+            "exctr",
+            "The query did not return any rows.".to_string(),
+        ))
+    }
+
     fn _parse_object_name(&self, obj_name: &ObjectName) -> String {
         //! Parse the annoying name object and obtain its string value.
 
@@ -868,37 +1484,70 @@ impl SqlExecutor {
         }
     }
 
-    pub fn execute(&self) -> Result<SqlResult, String> {
+    pub fn execute(&self) -> Result<SqlResult, EngineError> {
         //! Run the assigned command and display results if any are to be displayed.
         //!
+        //! Fails with an [`EngineError`] whose kind tells a missing table, a bad
+        //! value or an unparsable statement apart, see [`EngineError::code`].
+        //!
         //! Currently, an arc has to be acquired first, in every branch, and then the
         //! database is read or modified.
 
         match &self.statement {
             Statement::Query(query) => {
+                let cache_source = self._cache_source(query);
+                if let Some((sql, version)) = cache_source.as_ref() {
+                    let cached = {
+                        let mut session = self.session.write().unwrap();
+                        session.result_cache_mut().get(sql, *version)
+                    };
+                    if let Some(table) = cached {
+                        inform(
+                            "cache",
+                            "Reusing the cached result of the query.".to_string(),
+                        );
+                        let n_rows = table.count_rows();
+                        return Ok(SqlResult {
+                            table: Some(table),
+                            n_rows_processed: Some(n_rows),
+                            row: None,
+                        });
+                    }
+                }
+
                 let mut query_result = match query.body.as_ref() {
                     SetExpr::Select(select) => {
-                        let (column_names, select_mode) = self._extract_column_names(select)?;
+                        let group_columns = self._extract_group_by(select)?;
+                        let (column_names, mut select_mode) =
+                            self._extract_column_names(select, !group_columns.is_empty())?;
+
+                        if !group_columns.is_empty() {
+                            self._validate_group_projection(&column_names, &group_columns)?;
+                            select_mode = SqlExecutorSelectMode::Group;
+                        } else if select.having.is_some() {
+                            return Err(system_message(
+                                "exctr",
+                                "HAVING is only allowed with GROUP BY.".to_string(),
+                            )
+                            .into());
+                        }
                         let table_with_joins = select.from.first().ok_or(system_message(
                             "exctr",
                             "There is no table name after FROM keyword.".to_string(),
                         ))?;
                         let table_name = self._extract_table_name(table_with_joins)?;
 
-                        println!(
-                            "{}",
-                            system_message(
-                                "exctr",
-                                format!(
-                                    "Selecting {} in table {}.",
-                                    column_names
-                                        .iter()
-                                        .map(|sel_col| format!("{}", sel_col))
-                                        .collect::<Vec<String>>()
-                                        .join(", "),
-                                    table_name
-                                ),
-                            )
+                        inform(
+                            "exctr",
+                            format!(
+                                "Selecting {} in table {}.",
+                                column_names
+                                    .iter()
+                                    .map(|sel_col| format!("{}", sel_col))
+                                    .collect::<Vec<String>>()
+                                    .join(", "),
+                                table_name
+                            ),
                         );
 
                         // database.get_table()
@@ -921,10 +1570,65 @@ impl SqlExecutor {
                                     Ok(SqlResult {
                                         table: Some(aggregate_result),
                                         n_rows_processed: None,
+                                        row: None,
+                                    })
+                                }
+                                SqlExecutorSelectMode::Group => {
+                                    let filter: Option<TableFilter> =
+                                        match select.selection.as_ref() {
+                                            Some(selection) => Some(
+                                                FilterCompiler::new(
+                                                    table.schema.read().unwrap().get_vec(),
+                                                )
+                                                .compile(selection)?,
+                                            ),
+                                            None => None,
+                                        };
+
+                                    let n_columns = column_names.len();
+                                    let mut grouped_columns = column_names;
+                                    let having = match select.having.as_ref() {
+                                        Some(having) => Some(self._extract_having(
+                                            having,
+                                            &group_columns,
+                                            &mut grouped_columns,
+                                        )?),
+                                        None => None,
+                                    };
+
+                                    let mut grouped_result = table.perform_group_aggregate(
+                                        filter.as_deref(),
+                                        &group_columns,
+                                        &grouped_columns,
+                                    )?;
+
+                                    if let Some(having) = having {
+                                        grouped_result = self
+                                            ._apply_having(
+                                                grouped_result,
+                                                &having,
+                                                &grouped_columns,
+                                            )?
+                                            .truncate(n_columns);
+                                    }
+
+                                    Ok(SqlResult {
+                                        table: Some(grouped_result),
+                                        n_rows_processed: Some(table._rows()),
+                                        row: None,
                                     })
                                 }
                                 SqlExecutorSelectMode::Column => {
-                                    let reader = table.reader();
+                                    // A range over an indexed column narrows the rows before the
+                                    // WHERE filter runs; the filter still checks every predicate.
+                                    let reader = match self
+                                        ._table_access(&table, select.selection.as_ref())
+                                    {
+                                        TableAccess::IndexRange { column, low, high } => table
+                                            .range_reader(&column, low, high)
+                                            .unwrap_or_else(|| table.reader()),
+                                        TableAccess::FullScan => table.reader(),
+                                    };
                                     let mut result_table;
 
                                     let cols: Vec<String> = column_names
@@ -938,6 +1642,19 @@ impl SqlExecutor {
                                         })
                                         .collect();
 
+                                    // Aliases are applied last, WHERE and scalars still refer to
+                                    // the selected columns by their own names.
+                                    let aliases: Vec<Option<String>> = column_names
+                                        .iter()
+                                        .filter_map(|col| {
+                                            if let SelectColumn::Column { alias, .. } = col {
+                                                Some(alias.clone())
+                                            } else {
+                                                None
+                                            }
+                                        })
+                                        .collect();
+
                                     let sclrs: Vec<SelectColumn> = column_names
                                         .into_iter()
                                         .filter_map(|col| {
@@ -952,54 +1669,64 @@ impl SqlExecutor {
                                     // This check could be made better with:
                                     // - A vec based wrapper
                                     // - A wildcard check method or enum variant
-                                    if cols.contains(&"*".to_string()) {
-                                        result_table = table.reader();
-                                    } else {
-                                        // TODO: Update this call to include alias, so reader can display readable
-                                        // column names.
-                                        result_table = reader.select(cols)?;
+                                    let wildcard = cols.contains(&"*".to_string());
+                                    // Scalars on their own read the columns of the whole row,
+                                    // which are dropped again once the scalars are appended.
+                                    let scalars_only = cols.is_empty();
+                                    let mut chain = reader.lazy();
+                                    if !wildcard && !scalars_only {
+                                        chain = chain.select(cols)?;
                                     }
 
                                     if let Some(selection) = select.selection.as_ref() {
-                                        let table_schema_vec: Vec<String> = {
-                                            let schema = result_table.schema.read().unwrap();
-                                            schema
-                                                .get_vec()
-                                                .iter()
-                                                .map(|(col, _)| col)
-                                                .cloned()
-                                                .collect()
-                                        };
+                                        let table_schema_vec = chain.schema().get_vec().clone();
 
-                                        let filter =
-                                            self._parse_selection(selection, &table_schema_vec)?;
-                                        result_table = result_table.filter(filter).unwrap();
+                                        let filter = FilterCompiler::new(&table_schema_vec)
+                                            .compile(selection)?;
+                                        chain = chain.filter(filter)?;
                                     }
+                                    result_table = chain.collect();
+                                    let n_read_columns = result_table.schema.read().unwrap().len();
 
                                     if sclrs.len() > 0 {
-                                        println!("Performing {} scalars", sclrs.len());
+                                        inform(
+                                            "exctr",
+                                            format!("Performing {} scalars", sclrs.len()),
+                                        );
                                         result_table = result_table.perform_function(&sclrs)?;
                                     }
+                                    if scalars_only {
+                                        result_table = result_table.skip(n_read_columns);
+                                    }
+
+                                    if !wildcard {
+                                        result_table = result_table.alias(&aliases)?;
+                                    }
 
                                     Ok(SqlResult {
                                         table: Some(result_table),
                                         n_rows_processed: Some(table._rows()),
+                                        row: None,
                                     })
                                 }
                             }
                         } else {
-                            Err(system_message(
-                                "system",
-                                format!("Table '{}' does not exist!", &table_name),
-                            ))
+                            Err(_table_not_found(&database, &table_name))
                         }
                     }
                     _ => Err(system_message(
                         "exctr",
                         "This type of query is not handled by the engine yet!".to_string(),
-                    )),
+                    )
+                    .into()),
                 }?;
 
+                if let SetExpr::Select(select) = query.body.as_ref()
+                    && let Some(distinct) = select.distinct.as_ref()
+                {
+                    query_result = self._distinct(query_result, distinct)?;
+                }
+
                 if let Some(order_by) = query.order_by.as_ref() {
                     query_result = self._order_by(query_result, order_by)?;
                 }
@@ -1008,6 +1735,13 @@ impl SqlExecutor {
                     query_result = self._limit_offset(query_result, limit_clause)?;
                 }
 
+                if let Some((sql, version)) = cache_source
+                    && let Some(table) = query_result.table.as_ref()
+                {
+                    let mut session = self.session.write().unwrap();
+                    session.result_cache_mut().insert(sql, version, table);
+                }
+
                 Ok(query_result)
             }
             Statement::Insert(insert) => {
@@ -1018,43 +1752,64 @@ impl SqlExecutor {
 
                 let table_name = match insert.table.clone() {
                     TableObject::TableName(obj) => obj.0[0].as_ident().unwrap().value.clone(),
-                    _ => return Err("Invalid table name. Please check your query.".to_string()),
+                    _ => {
+                        return Err(EngineError::Other(
+                            "Invalid table name. Please check your query.".to_string(),
+                        ));
+                    }
                 };
 
-                let db_arc = self._get_db_from_session()?;
-                let mut database = db_arc.write().unwrap();
-                if database.contains_table(&table_name) {
-                    let query_body = insert.source.clone().expect(&system_message(
-                        "system",
-                        "No values to insert.".to_string(),
-                    ));
+                let query_body = insert.source.clone().expect(&system_message(
+                    "system",
+                    "No values to insert.".to_string(),
+                ));
 
-                    let query_rows = match query_body.body.as_ref() {
-                        SetExpr::Values(values) => values.rows.clone(),
-                        _ => {
-                            return Err("Invalid values list. Please check your query.".to_string());
+                // An INSERT ... SELECT runs its query before the database is
+                // locked for writing, the query needs to read it.
+                let mut rows = vec![];
+                match query_body.body.as_ref() {
+                    SetExpr::Values(values) => {
+                        for row in values.rows.clone() {
+                            // extract a row and add to a list of rows
+                            // use the insert many to insert the rows together
+                            rows.push(self._extract_row(row)?);
                         }
-                    };
+                    }
+                    SetExpr::Select(_) => {
+                        let reader = self._run_query(&query_body)?;
+                        rows.extend(reader.borrow_rows().iter().map(Row::cells));
+                    }
+                    _ => {
+                        return Err(EngineError::Other(
+                            "Invalid values list. Please check your query.".to_string(),
+                        ));
+                    }
+                }
 
-                    let mut rows = vec![];
+                let db_arc = self._get_db_from_session()?;
+                let mut database = db_arc.write().unwrap();
+                if database.contains_table(&table_name) {
+                    // A single inserted row is handed back so it can be shown.
+                    if rows.len() == 1 {
+                        let row = database.insert_values_into_table(&table_name, rows.remove(0))?;
 
-                    for row in query_rows {
-                        // extract a row and add to a list of rows
-                        // use the insert many to insert the rows together
-                        rows.push(self._extract_row(row)?);
+                        return Ok(SqlResult {
+                            table: None,
+                            n_rows_processed: Some(1),
+                            row: Some(row),
+                        });
                     }
 
-                    let inserted_row_count = database.insert_many_into_table(&table_name, rows)?;
+                    let inserted_row_count =
+                        database.insert_many_values_into_table(&table_name, rows)?;
 
                     Ok(SqlResult {
                         table: None,
                         n_rows_processed: Some(inserted_row_count),
+                        row: None,
                     })
                 } else {
-                    return Err(system_message(
-                        "system",
-                        format!("Table {} does not exist.", highlight_argument(&table_name)),
-                    ));
+                    Err(_table_not_found(&database, &table_name))
                 }
             }
             Statement::ShowTables { .. } => {
@@ -1074,6 +1829,7 @@ impl SqlExecutor {
                 return Ok(SqlResult {
                     table: None,
                     n_rows_processed: Some(0),
+                    row: None,
                 });
             }
             Statement::CreateTable(create_table) => {
@@ -1085,13 +1841,37 @@ impl SqlExecutor {
                     .collect::<Vec<_>>()
                     .join(".");
 
+                if let Some(query) = create_table.query.as_ref() {
+                    if !create_table.columns.is_empty() {
+                        return Err(system_message(
+                            "exctr",
+                            "Column definitions are not supported with AS SELECT.".to_string(),
+                        )
+                        .into());
+                    }
+
+                    let reader = self._run_query(query)?;
+                    let db_arc = self._get_db_from_session()?;
+                    let mut database = db_arc.write().unwrap();
+                    let copied_row_count =
+                        database.create_table_from_reader(table_name, &reader)?;
+
+                    return Ok(SqlResult {
+                        table: None,
+                        n_rows_processed: Some(copied_row_count),
+                        row: None,
+                    });
+                }
+
                 let mut col_def_map = IndexMap::new();
+                let mut checks = vec![];
 
                 for column_definition in create_table.columns.iter() {
                     let col_name = column_definition.name.value.clone();
-                    let col_def = self._extract_column_definition(column_definition.clone());
+                    let col_def = self._extract_column_definition(column_definition.clone())?;
 
-                    col_def_map.insert(col_name, col_def.unwrap());
+                    checks.extend(_extract_column_checks(column_definition));
+                    col_def_map.insert(col_name, col_def);
                 }
 
                 // for every constraint, if any, process the constraint
@@ -1100,18 +1880,15 @@ impl SqlExecutor {
                 // col name to append to, and the vec to append
 
                 for constraint in create_table.constraints.iter() {
-                    let (column_name, column_constraint) = self._extract_constraint_definition(
-                        col_def_map
-                            .keys()
-                            .cloned()
-                            .into_iter()
-                            .map(|key| key)
-                            .collect(),
+                    let column_constraints = self._extract_constraint_definition(
+                        col_def_map.keys().cloned().collect(),
                         constraint.clone(),
                     )?;
 
-                    let prev_constraint = col_def_map.get_mut(&column_name).unwrap();
-                    prev_constraint.extend(column_constraint);
+                    for (column_name, column_constraint) in column_constraints {
+                        let prev_constraint = col_def_map.get_mut(&column_name).unwrap();
+                        prev_constraint.extend(column_constraint);
+                    }
                 }
 
                 let db_arc = self._get_db_from_session()?;
@@ -1122,11 +1899,12 @@ impl SqlExecutor {
                     .map(|def| def.join(" "))
                     .collect();
 
-                database.create_table(table_name, column_definitions)?;
+                database.create_table_with_checks(table_name, column_definitions, checks)?;
 
                 Ok(SqlResult {
                     table: None,
                     n_rows_processed: Some(0),
+                    row: None,
                 })
             }
             Statement::Delete(delete) => {
@@ -1138,7 +1916,7 @@ impl SqlExecutor {
                         ))?;
                         self._extract_table_name(table_with_joins)?
                     }
-                    _ => return Err("Invalid DELETE statement.".to_string()),
+                    _ => return Err(EngineError::Other("Invalid DELETE statement.".to_string())),
                 };
 
                 let db_arc = self._get_db_from_session()?;
@@ -1154,12 +1932,12 @@ impl SqlExecutor {
                         let _tl = database.get_table(&table_name).unwrap();
                         let _t = _tl.read().unwrap();
                         let _s = _t.schema.read().unwrap();
-                        _s.get_vec().iter().map(|(col, _)| col.clone()).collect()
+                        _s.get_vec().clone()
                     };
 
-                    let mut filter = None;
+                    let mut filter: Option<TableFilter> = None;
                     if let Some(selection) = delete.selection.as_ref() {
-                        filter = self._parse_selection(selection, &table_schema_vec).ok();
+                        filter = Some(FilterCompiler::new(&table_schema_vec).compile(selection)?);
                     }
 
                     let deleted_row_count =
@@ -1168,12 +1946,10 @@ impl SqlExecutor {
                     Ok(SqlResult {
                         table: None,
                         n_rows_processed: Some(deleted_row_count),
+                        row: None,
                     })
                 } else {
-                    Err(system_message(
-                        "system",
-                        format!("Table {} does not exist!", highlight_argument(&table_name)),
-                    ))
+                    Err(_table_not_found(&database, &table_name))
                 }
             }
             Statement::Update(update) => {
@@ -1190,16 +1966,16 @@ impl SqlExecutor {
                     // causing the database.delete... methods to wait on read lock to
                     // finish and then start a write lock, which would cause the engine to
                     // hang indefinitely. The database api might need a few additions
-                    let table_schema_vec: Vec<String> = {
+                    let table_schema_vec = {
                         let _tl = database.get_table(&table_name).unwrap();
                         let _t = _tl.read().unwrap();
                         let _s = _t.schema.read().unwrap();
-                        _s.get_vec().iter().map(|(col, _)| col.clone()).collect()
+                        _s.get_vec().clone()
                     };
 
-                    let mut filter = None;
+                    let mut filter: Option<TableFilter> = None;
                     if let Some(selection) = update.selection.clone() {
-                        filter = self._parse_selection(&selection, &table_schema_vec).ok();
+                        filter = Some(FilterCompiler::new(&table_schema_vec).compile(&selection)?);
                     }
 
                     let mut updates = HashMap::new();
@@ -1215,12 +1991,10 @@ impl SqlExecutor {
                     Ok(SqlResult {
                         table: None,
                         n_rows_processed: Some(updated_row_count),
+                        row: None,
                     })
                 } else {
-                    Err(system_message(
-                        "system",
-                        format!("Table {} does not exist!", highlight_argument(&table_name)),
-                    ))
+                    Err(_table_not_found(&database, &table_name))
                 }
             }
             Statement::CreateDatabase {
@@ -1236,6 +2010,43 @@ impl SqlExecutor {
                 Ok(SqlResult {
                     table: None,
                     n_rows_processed: Some(0),
+                    row: None,
+                })
+            }
+            Statement::StartTransaction { .. } => {
+                let mut session = self.session.write().unwrap();
+                session.begin_transaction()?;
+
+                Ok(SqlResult {
+                    table: None,
+                    n_rows_processed: Some(0),
+                    row: None,
+                })
+            }
+            Statement::Commit { .. } => {
+                let mut session = self.session.write().unwrap();
+                session.commit_transaction()?;
+
+                Ok(SqlResult {
+                    table: None,
+                    n_rows_processed: Some(0),
+                    row: None,
+                })
+            }
+            Statement::Rollback { savepoint, .. } => {
+                if savepoint.is_some() {
+                    return Err(EngineError::Other(
+                        "invalid rollback: savepoints are not supported".to_string(),
+                    ));
+                }
+
+                let mut session = self.session.write().unwrap();
+                session.rollback_transaction()?;
+
+                Ok(SqlResult {
+                    table: None,
+                    n_rows_processed: Some(0),
+                    row: None,
                 })
             }
             Statement::Use(use_stmt) => {
@@ -1248,7 +2059,8 @@ impl SqlExecutor {
                                 "No other case than {} is handled yet.",
                                 highlight_argument("USE <db_name>")
                             ),
-                        ));
+                        )
+                        .into());
                     }
                 };
 
@@ -1258,6 +2070,7 @@ impl SqlExecutor {
                 Ok(SqlResult {
                     table: None,
                     n_rows_processed: Some(0),
+                    row: None,
                 })
             }
             Statement::ShowDatabases { .. } => {
@@ -1265,22 +2078,19 @@ impl SqlExecutor {
                 // TERSE, HISTORY, LIMIT, STARTS WITH etc...
                 let session = self.session.read().unwrap();
                 let database_names = session.get_available_databases();
+                let active = session
+                    .get_active_database()
+                    .map(|db| db.read().unwrap().name());
 
-                if database_names.is_empty() {
-                    println!("There are no databases in the registry yet.");
-                } else {
-                    println!(
-                        "There are {} databases in the registry.",
-                        database_names.len()
-                    );
-                    for (index, table_name) in database_names.iter().enumerate() {
-                        println!("{:5}. {:10}", index + 1, table_name);
-                    }
-                }
+                println!(
+                    "{}",
+                    database_list_message(&database_names, active.as_deref())
+                );
 
                 Ok(SqlResult {
                     table: None,
                     n_rows_processed: Some(0),
+                    row: None,
                 })
             }
             Statement::Drop {
@@ -1308,30 +2118,45 @@ impl SqlExecutor {
                                         "No other case than {} is handled yet.",
                                         highlight_argument("USE <db_name>")
                                     ),
-                                ));
+                                )
+                                .into());
                             }
                         }
 
                         Ok(SqlResult {
                             table: None,
                             n_rows_processed: Some(0),
+                            row: None,
                         })
                     }
                     sqlparser::ast::ObjectType::Table => {
                         // Removes the table from the registry.
 
+                        // Every table is checked first, so a failing name drops nothing.
                         let db_arc = self._get_db_from_session()?;
                         let mut database = db_arc.write().unwrap();
-                        let mut dropped_row_count = 0;
+                        let mut table_names = vec![];
 
                         for name_obj in names.iter() {
                             let name = self._parse_object_name(name_obj);
-                            dropped_row_count += database.drop_table(&name)?;
+
+                            if database.contains_table(&name) {
+                                table_names.push(name);
+                            } else if !*if_exists {
+                                return Err(_table_not_found(&database, &name));
+                            }
                         }
 
+                        let dropped_row_count = database
+                            .drop_tables(&table_names)?
+                            .iter()
+                            .map(|table| table.read().unwrap()._rows())
+                            .sum();
+
                         Ok(SqlResult {
                             table: None,
                             n_rows_processed: Some(dropped_row_count),
+                            row: None,
                         })
                     }
                     _ => {
@@ -1341,9 +2166,166 @@ impl SqlExecutor {
                                 "No other case than {} is handled yet.",
                                 highlight_argument("DROP DATABASE <db_name>")
                             ),
-                        ));
+                        )
+                        .into());
+                    }
+                }
+            }
+            Statement::Set(Set::SingleAssignment {
+                variable, values, ..
+            }) => {
+                // SET key = value, changes a setting of the session's config.
+                let key = self._parse_object_name(variable);
+                let value = match values.as_slice() {
+                    [value] => parse_expr(value)?,
+                    _ => {
+                        return Err(system_message(
+                            "exctr",
+                            format!("Invalid SET {}; it takes a single value.", key),
+                        )
+                        .into());
+                    }
+                };
+
+                let mut session = self.session.write().unwrap();
+                session.set_config(&key, &value)?;
+
+                Ok(SqlResult {
+                    table: None,
+                    n_rows_processed: Some(0),
+                    row: None,
+                })
+            }
+            Statement::Explain {
+                statement, analyze, ..
+            } => {
+                // EXPLAIN SELECT ..., one row per step of the plan. The query is not run.
+                let query = match statement.as_ref() {
+                    Statement::Query(query) if !analyze => query,
+                    _ => {
+                        return Err(system_message(
+                            "exctr",
+                            "Only EXPLAIN of a SELECT is supported.".to_string(),
+                        )
+                        .into());
+                    }
+                };
+
+                let plan = self._plan_select(query)?;
+                let schema = Schema::new(vec![("plan".to_string(), ColumnInformation::default())]);
+                let rows: Vec<Row> = plan
+                    .steps()
+                    .into_iter()
+                    .map(|step| Row(vec![persistence::Value::Text(step)]))
+                    .collect();
+                let n_rows = rows.len();
+
+                Ok(SqlResult {
+                    table: Some(TableReader {
+                        schema: Arc::new(RwLock::new(schema)),
+                        rows: Arc::new(RwLock::new(rows)),
+                        all_rows: None,
+                    }),
+                    n_rows_processed: Some(n_rows),
+                    row: None,
+                })
+            }
+            Statement::CreateIndex(create_index) => {
+                // CREATE INDEX name ON table (col), an ordered index over a single numeric
+                // column. The index name is not kept, the column identifies the index.
+                let table_name = self._parse_object_name(&create_index.table_name);
+                let col_name = match create_index.columns.as_slice() {
+                    [column] => parse_expr(&column.column.expr)?,
+                    _ => {
+                        return Err(system_message(
+                            "exctr",
+                            "Indexes over more than one column are not supported.".to_string(),
+                        )
+                        .into());
                     }
+                };
+
+                if create_index.unique {
+                    return Err(system_message(
+                        "exctr",
+                        "Unique indexes are created with the UNIQUE column constraint.".to_string(),
+                    )
+                    .into());
                 }
+
+                let db_arc = self._get_db_from_session()?;
+                let database = db_arc.read().unwrap();
+                let table = database
+                    .get_table(&table_name)
+                    .ok_or_else(|| _table_not_found(&database, &table_name))?;
+                table.write().unwrap().create_ordered_index(&col_name)?;
+
+                Ok(SqlResult {
+                    table: None,
+                    n_rows_processed: Some(0),
+                    row: None,
+                })
+            }
+            Statement::RenameTable(renames) => {
+                // RENAME TABLE old TO new, ...
+                let db_arc = self._get_db_from_session()?;
+                let mut database = db_arc.write().unwrap();
+
+                for rename in renames.iter() {
+                    let old_name = self._parse_object_name(&rename.old_name);
+                    let new_name = self._parse_object_name(&rename.new_name);
+                    database.rename_table(&old_name, &new_name)?;
+                }
+
+                Ok(SqlResult {
+                    table: None,
+                    n_rows_processed: Some(0),
+                    row: None,
+                })
+            }
+            Statement::AlterTable(alter_table) => {
+                // Only the renaming operations are handled for now.
+                let db_arc = self._get_db_from_session()?;
+                let mut database = db_arc.write().unwrap();
+                let mut table_name = self._parse_object_name(&alter_table.name);
+
+                for operation in alter_table.operations.iter() {
+                    match operation {
+                        AlterTableOperation::RenameColumn {
+                            old_column_name,
+                            new_column_name,
+                        } => {
+                            database.rename_column(
+                                &table_name,
+                                &old_column_name.value,
+                                &new_column_name.value,
+                            )?;
+                        }
+                        AlterTableOperation::RenameTable {
+                            table_name: RenameTableNameKind::To(new_name),
+                        }
+                        | AlterTableOperation::RenameTable {
+                            table_name: RenameTableNameKind::As(new_name),
+                        } => {
+                            let new_name = self._parse_object_name(new_name);
+                            database.rename_table(&table_name, &new_name)?;
+                            table_name = new_name;
+                        }
+                        _ => {
+                            return Err(system_message(
+                                "exctr",
+                                format!("Unsupported ALTER TABLE operation '{}'.", operation),
+                            )
+                            .into());
+                        }
+                    }
+                }
+
+                Ok(SqlResult {
+                    table: None,
+                    n_rows_processed: Some(0),
+                    row: None,
+                })
             }
             Statement::Truncate(truncate) => {
                 // Removes all rows of a table regardless of foreign key checks.
@@ -1360,12 +2342,14 @@ impl SqlExecutor {
                 Ok(SqlResult {
                     table: None,
                     n_rows_processed: Some(truncated_row_count),
+                    row: None,
                 })
             }
             _ => Err(system_message(
                 "exctr",
                 "This statement is not handled by the engine yet!".to_string(),
-            )),
+            )
+            .into()),
         }
     }
 }
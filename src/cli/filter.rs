@@ -0,0 +1,673 @@
+//! Compiling SQL expressions into row predicates, against a schema alone.
+//!
+//! A [`FilterCompiler`] only needs the columns of the rows it filters, so the
+//! executor uses it for WHERE and HAVING, and tables use it for their CHECK
+//! constraints, see [`compile_check`], without any session or database.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use sqlparser::ast::{BinaryOperator, Expr, UnaryOperator, Value, ValueWithSpan};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+
+use crate::cli::messages::{did_you_mean, highlight_argument, system_message};
+use crate::errors::EngineError;
+use crate::persistence::{self, ColumnInformation, Row};
+
+/// A row predicate compiled from a WHERE clause.
+pub type RowFilter = Box<dyn Fn(&Row) -> bool + Send + Sync>;
+
+/// A numeric expression compiled from SQL, evaluated against a single row.
+type RowArithmetic = Box<dyn Fn(&Row) -> Option<f64> + Send + Sync>;
+
+pub(crate) fn compare_cell(
+    datatype: &persistence::DataType,
+    cell: &str,
+    value: &str,
+) -> Option<Ordering> {
+    //! Compare a cell to a literal the way its column orders values.
+    //!
+    //! Numbers compare numerically, and [`None`] is returned if either side is not
+    //! one. Text and dates compare lexically, which orders ISO dates correctly.
+
+    match datatype {
+        datatype if datatype.is_numeric() => {
+            let cell = cell.parse::<f64>().ok()?;
+            let value = value.parse::<f64>().ok()?;
+            cell.partial_cmp(&value)
+        }
+        _ => Some(cell.cmp(value)),
+    }
+}
+
+pub(crate) fn column_not_found(
+    col_name: &str,
+    table_schema_vec: &[(String, ColumnInformation)],
+) -> EngineError {
+    //! The error for a missing column, hinting at the closest existing one.
+
+    EngineError::ColumnNotFound(format!(
+        "Column {} does not exist!{}",
+        highlight_argument(col_name),
+        did_you_mean(
+            col_name,
+            table_schema_vec.iter().map(|(col, _)| col.as_str())
+        )
+    ))
+}
+
+/// A side of a NULL-safe `<=>` comparison.
+enum NullSafeOperand {
+    /// The cell of the column at this index.
+    Column(usize),
+    /// A literal value, [`None`] for NULL.
+    Literal(Option<String>),
+}
+
+impl NullSafeOperand {
+    fn value<'a>(&'a self, row: &'a Row) -> Option<Cow<'a, str>> {
+        match self {
+            NullSafeOperand::Column(col_index) => row.get_str(*col_index),
+            NullSafeOperand::Literal(value) => value.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+/// A single piece of a compiled LIKE pattern.
+enum LikeToken {
+    /// `%`, any sequence of characters, including none.
+    Any,
+    /// `_`, exactly one character.
+    One,
+    /// Any other character, or an escaped wildcard, matched literally.
+    Char(char),
+}
+
+fn _compile_like(pattern: &str, escape: char) -> Result<Vec<LikeToken>, String> {
+    //! Turn a LIKE pattern into its tokens. The `escape` character makes the
+    //! character after it match literally, e.g. `\%` matches a percent sign.
+
+    let mut tokens = vec![];
+    let mut chars = pattern.chars();
+
+    while let Some(ch) = chars.next() {
+        let token = match ch {
+            _ if ch == escape => match chars.next() {
+                Some(escaped) => LikeToken::Char(escaped),
+                None => {
+                    return Err(format!(
+                        "Invalid pattern '{}'; it ends with the escape character.",
+                        pattern
+                    ));
+                }
+            },
+            '%' => LikeToken::Any,
+            '_' => LikeToken::One,
+            _ => LikeToken::Char(ch),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn _like_matches(tokens: &[LikeToken], text: &str) -> bool {
+    //! Check the whole `text` against a compiled LIKE pattern.
+    //!
+    //! Backtracks to the last `%` on a mismatch, so it runs in linear space.
+
+    let text: Vec<char> = text.chars().collect();
+    let (mut t, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match tokens.get(p) {
+            Some(LikeToken::Any) => {
+                backtrack = Some((p, t));
+                p += 1;
+                continue;
+            }
+            Some(LikeToken::One) => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            Some(LikeToken::Char(ch)) if *ch == text[t] => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((any_p, any_t)) => {
+                p = any_p + 1;
+                t = any_t + 1;
+                backtrack = Some((any_p, any_t + 1));
+            }
+            None => return false,
+        }
+    }
+
+    tokens[p..]
+        .iter()
+        .all(|token| matches!(token, LikeToken::Any))
+}
+
+/// Compiles expressions over the columns of a `schema` into [`RowFilter`]s.
+pub(crate) struct FilterCompiler<'a> {
+    schema: &'a [(String, ColumnInformation)],
+}
+
+pub fn compile_check(
+    expression: &str,
+    schema: &[(String, ColumnInformation)],
+) -> Result<RowFilter, String> {
+    //! Compile a CHECK constraint `expression` over the columns of `schema`
+    //! into a row predicate.
+    //!
+    //! The expression is compiled exactly like a WHERE clause, so constraints
+    //! support the same operators as filters do.
+
+    let expr = Parser::new(&MySqlDialect {})
+        .try_with_sql(expression)
+        .and_then(|mut parser| {
+            let expr = parser.parse_expr()?;
+            parser.expect_token(&Token::EOF)?;
+            Ok(expr)
+        })
+        .map_err(|e| format!("invalid check {}: {}", expression, e))?;
+
+    FilterCompiler::new(schema).compile(&expr)
+}
+
+pub(crate) fn parse_expr(expr: &Expr) -> Result<String, String> {
+    //! Get a column name, or a literal the way the engine stores it, out of
+    //! the `expr`.
+
+    match expr {
+        Expr::Value(value) => _parse_value(&value),
+        Expr::Identifier(ident) => Ok(ident.value.clone()),
+        Expr::UnaryOp { op, expr } => {
+            let value = parse_expr(expr)?;
+            let prefix = match op {
+                sqlparser::ast::UnaryOperator::Minus => "-",
+                _ => {
+                    return Err(system_message(
+                        "system",
+                        "Unsupported unary operator.".to_string(),
+                    ));
+                }
+            };
+
+            Ok(format!("{}{}", prefix, value))
+        }
+        _ => {
+            return Err(system_message(
+                "system",
+                "Unsupported value. Check your query.".to_string(),
+            ));
+        }
+    }
+}
+
+fn _parse_value(value: &ValueWithSpan) -> Result<String, String> {
+    //! Match the [`Value`] object properly to its subtype and return the
+    //! engine-specific (value, datatype) mapping.
+    //!
+    //! This function is a utility to allow data insertion format to align
+    //! with the one accepted by the persistence API
+
+    let value = match &value.value {
+        Value::Number(value, _) => value,
+        Value::SingleQuotedString(value) => value,
+        Value::DoubleQuotedString(value) => value,
+        Value::Boolean(value) => return Ok(value.to_string()),
+        _ => {
+            return Err(system_message(
+                "system",
+                format!(
+                    "Unsupported value: {}",
+                    highlight_argument(&value.to_string())
+                ),
+            ));
+        }
+    };
+
+    Ok(value.into())
+}
+
+impl<'a> FilterCompiler<'a> {
+    pub(crate) fn new(schema: &'a [(String, ColumnInformation)]) -> FilterCompiler<'a> {
+        FilterCompiler { schema }
+    }
+
+    pub(crate) fn compile(&self, expr: &Expr) -> Result<RowFilter, String> {
+        //! Compile the `expr` of a WHERE clause into a filter over the rows of
+        //! the schema.
+
+        self._parse_selection(expr)
+    }
+
+    fn _parse_selection(&self, selection: &Expr) -> Result<RowFilter, String> {
+        //! Parse the [`Expr::BinaryOp`] variant to a filter.
+        //!
+        //! Returns a closure `Fn(&Row) -> bool` that takes a row to check
+        //! if it is a fit over the filter. I intend it to be used inside a filter
+        //! function on a tabler reader as well.
+        //!
+        //! Filter flow: the filter function can NOT directly access the table and
+        //! the row index, therefore, we rely directly on the database to do this for
+        //! us. In that case, we will have to pass the left and right values to the
+        //! database, restructure them to map them to the table and run the final
+        //! filter to get the resultant rows.
+
+        match selection {
+            Expr::BinaryOp { left, op, right } => match op {
+                BinaryOperator::Or => {
+                    let left_filter = self._parse_selection(left)?;
+                    let right_filter = self._parse_selection(right)?;
+
+                    Ok(Box::new(move |row| left_filter(row) || right_filter(row)))
+                }
+                BinaryOperator::And => {
+                    let left_filter = self._parse_selection(left)?;
+                    let right_filter = self._parse_selection(right)?;
+
+                    Ok(Box::new(move |row| left_filter(row) && right_filter(row)))
+                }
+                // Nothing is equal, or unequal, to NULL; `<=>` is the NULL-safe equality.
+                BinaryOperator::Eq | BinaryOperator::NotEq
+                    if matches!(
+                        right.as_ref(),
+                        Expr::Value(ValueWithSpan {
+                            value: Value::Null,
+                            ..
+                        })
+                    ) =>
+                {
+                    Ok(Box::new(|_| false))
+                }
+                BinaryOperator::Eq | BinaryOperator::NotEq
+                    if !matches!(left.as_ref(), Expr::Identifier(_)) =>
+                {
+                    self._parse_comparison(left, op, right)
+                }
+                BinaryOperator::Gt
+                | BinaryOperator::Lt
+                | BinaryOperator::GtEq
+                | BinaryOperator::LtEq => self._parse_comparison(left, op, right),
+                BinaryOperator::Eq => {
+                    let (col_index, value) = self._parse_operands(left.as_ref(), right.as_ref())?;
+
+                    Ok(Box::new(move |row| {
+                        row.get_str(col_index)
+                            .map_or(false, |v| v == value.as_str())
+                    }))
+                }
+                BinaryOperator::NotEq => {
+                    let (col_index, value) = self._parse_operands(left.as_ref(), right.as_ref())?;
+
+                    Ok(Box::new(move |row| {
+                        row.get_str(col_index)
+                            .map_or(false, |v| v != value.as_str())
+                    }))
+                }
+                BinaryOperator::Spaceship => {
+                    let left = self._parse_null_safe_operand(left)?;
+                    let right = self._parse_null_safe_operand(right)?;
+
+                    Ok(Box::new(move |row| left.value(row) == right.value(row)))
+                }
+                _ => Err(format!("Invalid query filter. Check your query.")),
+            },
+            Expr::Like {
+                negated,
+                any: false,
+                expr,
+                pattern,
+                escape_char,
+            } => self._parse_like(expr, pattern, escape_char, *negated, false),
+            Expr::ILike {
+                negated,
+                any: false,
+                expr,
+                pattern,
+                escape_char,
+            } => self._parse_like(expr, pattern, escape_char, *negated, true),
+            Expr::Nested(inner) => self._parse_selection(inner),
+            Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => {
+                // Filters are two-valued, so a row whose predicate failed on a NULL
+                // cell matches the negated predicate.
+                let filter = self._parse_selection(expr)?;
+                Ok(Box::new(move |row| !filter(row)))
+            }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => self._parse_between(expr, low, high, *negated),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => self._parse_in_list(expr, list, *negated),
+            Expr::IsNull(expr) => {
+                let col_index = self._parse_null_check(expr)?;
+                Ok(Box::new(move |row| {
+                    row.is_null(col_index)
+                }))
+            }
+            Expr::IsNotNull(expr) => {
+                let col_index = self._parse_null_check(expr)?;
+                Ok(Box::new(move |row| {
+                    row.get_str(col_index).is_some()
+                }))
+            }
+            _ => Err(format!("Invalid column selection. Check your query.")),
+        }
+    }
+
+    fn _parse_comparable(&self, expr: &Expr, value: &Expr) -> Result<String, String> {
+        //! Parse a literal compared against the column `expr` by `BETWEEN` or `IN`.
+        //!
+        //! Literals for numeric columns must be numbers themselves.
+
+        let literal = parse_expr(value)?;
+        let col_name = parse_expr(expr)?;
+
+        let is_number = self
+            .schema
+            .iter()
+            .any(|(col, col_info)| col == &col_name && col_info.datatype().is_numeric());
+        if is_number && literal.parse::<f64>().is_err() {
+            return Err(system_message(
+                "exctr",
+                format!(
+                    "Invalid {}; numeric columns can only be compared to numbers.",
+                    highlight_argument(&literal)
+                ),
+            ));
+        }
+
+        Ok(literal)
+    }
+
+    fn _parse_between(
+        &self,
+        expr: &Expr,
+        low: &Expr,
+        high: &Expr,
+        negated: bool,
+    ) -> Result<RowFilter, String> {
+        //! Parse `col [NOT] BETWEEN low AND high` into a filter.
+        //!
+        //! Both bounds are inclusive. Numeric columns compare as numbers, any other
+        //! column compares lexically. A NULL cell matches neither BETWEEN nor NOT
+        //! BETWEEN.
+
+        let (col_index, _) = self._parse_operands(expr, low)?;
+        let low = self._parse_comparable(expr, low)?;
+        let high = self._parse_comparable(expr, high)?;
+        let datatype = self.schema[col_index].1.datatype().clone();
+
+        Ok(Box::new(move |row| match row.get_str(col_index) {
+            Some(value) => {
+                let above = compare_cell(&datatype, &value, &low).is_some_and(|o| o.is_ge());
+                let below = compare_cell(&datatype, &value, &high).is_some_and(|o| o.is_le());
+                (above && below) != negated
+            }
+            _ => false,
+        }))
+    }
+
+    fn _parse_in_list(
+        &self,
+        expr: &Expr,
+        list: &[Expr],
+        negated: bool,
+    ) -> Result<RowFilter, String> {
+        //! Parse `col [NOT] IN (v1, v2, ...)` into a filter.
+        //!
+        //! Numeric columns match by number, so `20` and `20.0` are the same value.
+        //! A NULL cell matches neither IN nor NOT IN.
+
+        let Some(first) = list.first() else {
+            return Err(system_message(
+                "exctr",
+                "Invalid IN; the list of values is empty.".to_string(),
+            ));
+        };
+
+        let (col_index, _) = self._parse_operands(expr, first)?;
+        let values = list
+            .iter()
+            .map(|value| self._parse_comparable(expr, value))
+            .collect::<Result<Vec<String>, String>>()?;
+        let datatype = self.schema[col_index].1.datatype().clone();
+
+        Ok(Box::new(move |row| match row.get_str(col_index) {
+            Some(cell) => {
+                let found = values
+                    .iter()
+                    .any(|value| compare_cell(&datatype, &cell, value).is_some_and(|o| o.is_eq()));
+                found != negated
+            }
+            _ => false,
+        }))
+    }
+
+    fn _parse_like(
+        &self,
+        expr: &Expr,
+        pattern: &Expr,
+        escape_char: &Option<Value>,
+        negated: bool,
+        case_insensitive: bool,
+    ) -> Result<RowFilter, String> {
+        //! Parse `col [NOT] LIKE 'pattern' [ESCAPE 'c']` into a filter.
+        //!
+        //! `%` matches any sequence of characters and `_` a single one. The escape
+        //! character, a backslash unless given, makes the next character literal.
+        //! LIKE is case-sensitive, ILIKE is not. A NULL cell matches neither LIKE
+        //! nor NOT LIKE.
+
+        let (col_index, pattern) = self._parse_operands(expr, pattern)?;
+
+        let escape = match escape_char {
+            None => '\\',
+            Some(Value::SingleQuotedString(escape)) if escape.chars().count() == 1 => {
+                escape.chars().next().unwrap()
+            }
+            Some(escape) => {
+                return Err(system_message(
+                    "exctr",
+                    format!(
+                        "Invalid escape {}; it must be a single character.",
+                        highlight_argument(&escape.to_string())
+                    ),
+                ));
+            }
+        };
+
+        let pattern = match case_insensitive {
+            true => pattern.to_lowercase(),
+            false => pattern,
+        };
+        let tokens = _compile_like(&pattern, escape)?;
+
+        Ok(Box::new(move |row| match row.get_str(col_index) {
+            Some(value) if case_insensitive => {
+                _like_matches(&tokens, &value.to_lowercase()) != negated
+            }
+            Some(value) => _like_matches(&tokens, &value) != negated,
+            _ => false,
+        }))
+    }
+
+    fn _parse_null_check(&self, expr: &Expr) -> Result<usize, String> {
+        //! Get the index of the column tested by `IS NULL` or `IS NOT NULL`.
+        //!
+        //! Only a plain column can be tested, since that is where the NULL cell lives.
+
+        match expr {
+            Expr::Identifier(ident) => self
+                .schema
+                .iter()
+                .position(|(col, _)| col == &ident.value)
+                .ok_or_else(|| column_not_found(&ident.value, self.schema).into()),
+            _ => Err(system_message(
+                "exctr",
+                format!(
+                    "Invalid {}; only columns can be checked for NULL.",
+                    highlight_argument(&expr.to_string())
+                ),
+            )),
+        }
+    }
+
+    fn _parse_null_safe_operand(&self, expr: &Expr) -> Result<NullSafeOperand, String> {
+        //! Parse a side of `<=>`, the NULL-safe equality: two NULLs are equal,
+        //! and a NULL never equals a value.
+        //!
+        //! A side is either a column, compared by its cell, or a literal,
+        //! `NULL` included.
+
+        match expr {
+            Expr::Identifier(ident) => self
+                .schema
+                .iter()
+                .position(|(col, _)| col == &ident.value)
+                .map(NullSafeOperand::Column)
+                .ok_or_else(|| column_not_found(&ident.value, self.schema).into()),
+            Expr::Value(ValueWithSpan {
+                value: Value::Null, ..
+            }) => Ok(NullSafeOperand::Literal(None)),
+            expr => Ok(NullSafeOperand::Literal(Some(parse_expr(expr)?))),
+        }
+    }
+
+    fn _parse_operands(&self, left: &Expr, right: &Expr) -> Result<(usize, String), String> {
+        let col_name = parse_expr(left)?;
+        let value = parse_expr(right)?;
+
+        let col_index = self
+            .schema
+            .iter()
+            .position(|(col, _)| col == &col_name)
+            .ok_or_else(|| column_not_found(&col_name, self.schema))?;
+
+        Ok((col_index, value))
+    }
+
+    fn _parse_comparison(
+        &self,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+    ) -> Result<RowFilter, String> {
+        //! Parse a numeric comparison, where either side may be an arithmetic
+        //! expression over columns and literals.
+        //!
+        //! Both sides are evaluated per row before comparing. A row with a NULL
+        //! operand never matches.
+
+        let left_value = self._parse_arithmetic(left)?;
+        let right_value = self._parse_arithmetic(right)?;
+
+        let compare: fn(f64, f64) -> bool = match op {
+            BinaryOperator::Eq => |l, r| l == r,
+            BinaryOperator::NotEq => |l, r| l != r,
+            BinaryOperator::Gt => |l, r| l > r,
+            BinaryOperator::Lt => |l, r| l < r,
+            BinaryOperator::GtEq => |l, r| l >= r,
+            BinaryOperator::LtEq => |l, r| l <= r,
+            _ => return Err("Invalid comparison operator. Check your query.".to_string()),
+        };
+
+        Ok(Box::new(move |row| {
+            match (left_value(row), right_value(row)) {
+                (Some(l), Some(r)) => compare(l, r),
+                _ => false,
+            }
+        }))
+    }
+
+    fn _parse_arithmetic(&self, expr: &Expr) -> Result<RowArithmetic, String> {
+        //! Parse an arithmetic expression into a closure evaluating it on a row.
+        //!
+        //! Only numeric columns and literals are allowed as operands. The closure
+        //! returns [`None`] when a cell is NULL or the result is not a number,
+        //! e.g. after a division by zero.
+
+        match expr {
+            Expr::Identifier(ident) => {
+                let col_index = self
+                    .schema
+                    .iter()
+                    .position(|(col, _)| col == &ident.value)
+                    .ok_or_else(|| column_not_found(&ident.value, self.schema))?;
+
+                let (_, col_info) = &self.schema[col_index];
+                if !col_info.datatype().is_numeric() {
+                    return Err(system_message(
+                        "exctr",
+                        format!(
+                            "Invalid {}; arithmetic is only allowed on numeric columns.",
+                            highlight_argument(&ident.value)
+                        ),
+                    ));
+                }
+
+                Ok(Box::new(move |row| {
+                    row.get_str(col_index)?.parse::<f64>().ok()
+                }))
+            }
+            Expr::Nested(inner) => self._parse_arithmetic(inner),
+            Expr::BinaryOp { left, op, right } => {
+                let left_value = self._parse_arithmetic(left)?;
+                let right_value = self._parse_arithmetic(right)?;
+
+                let operate: fn(f64, f64) -> f64 = match op {
+                    BinaryOperator::Plus => |l, r| l + r,
+                    BinaryOperator::Minus => |l, r| l - r,
+                    BinaryOperator::Multiply => |l, r| l * r,
+                    BinaryOperator::Divide => |l, r| l / r,
+                    BinaryOperator::Modulo => |l, r| l % r,
+                    _ => {
+                        return Err(system_message(
+                            "exctr",
+                            format!("Unsupported arithmetic operator '{}'.", op),
+                        ));
+                    }
+                };
+
+                Ok(Box::new(move |row| {
+                    let value = operate(left_value(row)?, right_value(row)?);
+                    value.is_finite().then_some(value)
+                }))
+            }
+            _ => {
+                let literal = parse_expr(expr)?;
+                let value = literal.parse::<f64>().map_err(|_| {
+                    system_message(
+                        "exctr",
+                        format!(
+                            "Invalid {}; arithmetic is only allowed on numbers.",
+                            highlight_argument(&literal)
+                        ),
+                    )
+                })?;
+
+                Ok(Box::new(move |_| Some(value)))
+            }
+        }
+    }
+}
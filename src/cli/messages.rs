@@ -5,11 +5,19 @@
 //! - Highlight Text Hard (make the text ferrum red and bold)
 //! - System message formatting functions that produce the same
 //! format messages.
+//! - Did-you-mean hints for mistyped names
+//! - A quiet switch that hides the informational messages
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use colored::Colorize;
 
 use crate::cli::colors::FERRUM_RED;
 
+/// Whether informational messages are hidden, for the whole process.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
 pub fn highlight_argument(argument: &str) -> String {
     //! Highlight a piece of text in the ferrum red
     //! color to make it obvious.
@@ -31,3 +39,122 @@ pub fn system_message(source_name: &str, message: String) -> String {
     let message = format!("[{}] {}", source_formatted, message);
     message
 }
+
+pub fn set_quiet(quiet: bool) {
+    //! Hide or show the messages written with [`inform`] from now on.
+
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+pub fn inform(source_name: &str, message: String) {
+    //! Print an informational [`system_message`], unless the client is quiet.
+    //!
+    //! Warnings, errors and results should be printed directly instead, so
+    //! they still show in quiet mode.
+
+    if !is_quiet() {
+        println!("{}", system_message(source_name, message));
+    }
+}
+
+fn _edit_distance(left: &str, right: &str) -> usize {
+    //! The Levenshtein distance between two texts: the fewest single character
+    //! insertions, deletions and substitutions turning one into the other.
+
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[right.len()]
+}
+
+pub fn closest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    //! Find the candidate closest to a mistyped `name`, ignoring case.
+    //!
+    //! Only candidates at most half the length of `name` edits away count, so
+    //! [`None`] is returned when nothing is close.
+
+    let name = name.to_lowercase();
+    let limit = (name.chars().count() / 2).max(1);
+
+    candidates
+        .into_iter()
+        .map(|candidate| (_edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    //! A ` Did you mean '...'?` hint to put after a message about a mistyped
+    //! `name`, or an empty string when no candidate is close.
+
+    match closest_name(name, candidates) {
+        Some(closest) => format!(" Did you mean '{}'?", highlight_argument(closest)),
+        None => String::new(),
+    }
+}
+
+pub fn format_duration(elapsed: Duration) -> String {
+    //! Format a duration with a unit fitting its size, e.g. `820µs`, `1.82ms`
+    //! or `2.31s`.
+
+    let micros = elapsed.as_micros();
+    if micros < 1_000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", elapsed.as_secs_f64() * 1_000.0)
+    } else {
+        format!("{:.2}s", elapsed.as_secs_f64())
+    }
+}
+
+pub fn processed_message(n_rows: usize, elapsed: Option<Duration>) -> String {
+    //! The message shown after a statement runs, with its execution time when
+    //! timing is on.
+
+    match elapsed {
+        Some(elapsed) => format!("{} row(s) in {}", n_rows, format_duration(elapsed)),
+        None => format!("{} row(s) processed!", n_rows),
+    }
+}
+
+pub fn database_list_message(database_names: &[String], active: Option<&str>) -> String {
+    //! The listing shown for `SHOW DATABASES`, one database per line.
+    //!
+    //! The `active` database is marked with a `*` and highlighted.
+
+    if database_names.is_empty() {
+        return "There are no databases in the registry yet.".to_string();
+    }
+
+    let mut lines = vec![format!(
+        "There are {} databases in the registry.",
+        database_names.len()
+    )];
+
+    for (index, name) in database_names.iter().enumerate() {
+        if Some(name.as_str()) == active {
+            lines.push(format!("* {:3}. {}", index + 1, highlight_argument(name)));
+        } else {
+            lines.push(format!("  {:3}. {}", index + 1, name));
+        }
+    }
+
+    lines.join("\n")
+}
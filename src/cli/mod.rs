@@ -1,7 +1,9 @@
 use std::{
+    fs,
     io::{self, Write},
     path::Path,
     sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 
 use colored::Colorize;
@@ -10,86 +12,275 @@ use sqlparser::dialect::MySqlDialect;
 use crate::{
     cli::{
         colors::FERRUM_RED,
-        commands::{SqlExecutor, SqlResult},
-        messages::{highlight_argument, system_message},
-        parsers::SqlParser,
+        messages::{highlight_argument, inform, set_quiet, system_message},
+        parsers::{OutputFormat, SqlParser, Timing},
     },
     config::EngineConfig,
     persistence::DatabaseRegistry,
-    serialization::{deserializers::load_registry, registry_exists, serializers::save_registry},
-    sessions::session::Session,
+    serialization::{
+        data_dir::{load_data_dir, save_data_dir},
+        deserializers::load_registry,
+        registry_exists,
+        serializers::save_registry,
+    },
+    sessions::{event_log::Event, session::Session},
 };
 
 mod colors;
 mod commands;
+mod filter;
 mod messages;
 pub mod parsers;
+mod plan;
 mod splash_screen;
 
 pub use commands::FunctionArg;
+pub use commands::FunctionType;
 pub use commands::SelectColumn;
+pub use commands::{SqlExecutor, SqlResult};
+pub use filter::compile_check;
+pub use messages::{
+    closest_name, database_list_message, format_duration, is_quiet, processed_message,
+};
 
 const DEFAULT_LAST_COMMAND_DELIMITER: &str = "!";
 
-const FERRUM_ENGINE_COMMANDS_LIST: [(&str, &str); 4] = [
+const LOGIN_ATTEMPTS: usize = 3;
+
+/// Without a registry file or data directory the users would be lost on exit,
+/// and anyone could add the first one again on the next start.
+const AUTH_NOT_PERSISTED: &str =
+    "Authentication needs a registry file or a data directory to keep the users in.";
+
+/// A line typed into the REPL, either one of its control words or SQL.
+#[derive(Debug, PartialEq)]
+pub enum ReplInput<'a> {
+    History,
+    Help,
+    Dump,
+    Reset,
+    Exit,
+    Corrode,
+    Sql(&'a str),
+}
+
+impl<'a> ReplInput<'a> {
+    pub fn parse(line: &'a str) -> ReplInput<'a> {
+        //! Read a line of input. Control words match regardless of case and the
+        //! whitespace around them, any other line is passed on as SQL, as typed.
+
+        let line = line.trim();
+        match line.to_lowercase().as_str() {
+            "history" => ReplInput::History,
+            "help" => ReplInput::Help,
+            "\\dump" => ReplInput::Dump,
+            "\\reset" => ReplInput::Reset,
+            "exit" => ReplInput::Exit,
+            "corrode" => ReplInput::Corrode,
+            _ => ReplInput::Sql(line),
+        }
+    }
+}
+
+const FERRUM_ENGINE_COMMANDS_LIST: [(&str, &str); 6] = [
     ("!", "execute the last command, add more to go further back"),
     ("help", "list all available commands"),
     ("history", "list command history for this session"),
+    (
+        "\\dump",
+        "print the active database as replayable sql statements",
+    ),
+    (
+        "\\reset",
+        "clear the active database, history and variables of this session",
+    ),
     (
         "corrode",
         "iron corrodes and so does this session when you exit",
     ),
 ];
 
-pub fn run_client(config: &EngineConfig) {
-    splash_screen::splash_screen();
+pub fn run_client(
+    config: &EngineConfig,
+    format: OutputFormat,
+    timing: Timing,
+    auth: bool,
+    log: Option<&Path>,
+    data_dir: Option<&Path>,
+    quiet: bool,
+) {
+    set_quiet(quiet);
+    if !quiet {
+        splash_screen::splash_screen();
+    }
 
-    let _reg: DatabaseRegistry;
-    let _reg_path = Path::new(config.registry_file_path.as_str());
+    if auth && !is_persisted(config, data_dir) {
+        println!("{}", system_message("auth", AUTH_NOT_PERSISTED.to_string()));
+        return;
+    }
 
-    if registry_exists(_reg_path) {
-        _reg = load_registry(_reg_path).unwrap();
-        println!(
-            "{}",
-            system_message(
-                "info",
-                format!("A restored database registry was created at the session level.")
-            )
+    let _reg = open_registry(config, data_dir);
+    let _reg_path = config.registry_file_path.as_deref().map(Path::new);
+    let registry = Arc::new(RwLock::new(_reg));
+    let mut session = Session::client(&registry);
+    let timing = match timing {
+        Timing::On => "on",
+        Timing::Off => "off",
+    };
+    session.set_config("timing", timing).unwrap();
+    if let Some(log) = log {
+        session.set_event_log(log);
+    }
+
+    let session = Arc::new(RwLock::new(session));
+    if auth && !login(&session) {
+        return;
+    }
+
+    {
+        let session = session.read().unwrap();
+        let user = session.current_user().map(|user| user.to_string());
+        log_event(&session, Event::Connection { user });
+    }
+
+    {
+        let registry = Arc::clone(&registry);
+        let path = _reg_path.map(Path::to_path_buf);
+        let data_dir = data_dir.map(Path::to_path_buf);
+        let handler = ctrlc::set_handler(move || {
+            println!();
+            if let Err(error) = shutdown(&registry, path.as_deref(), data_dir.as_deref()) {
+                println!("{}", system_message("error", error));
+            }
+            std::process::exit(0);
+        });
+        if let Err(error) = handler {
+            println!(
+                "{}",
+                system_message("warn", format!("Failed to handle interrupts: {}", error))
+            );
+        }
+    }
+
+    start_repl(session, format);
+
+    if let Err(error) = shutdown(&registry, _reg_path, data_dir) {
+        println!("{}", system_message("error", error));
+    }
+}
+
+fn is_persisted(config: &EngineConfig, data_dir: Option<&Path>) -> bool {
+    //! Whether the registry is loaded from and saved to a registry file or a
+    //! data directory, rather than living in memory only.
+
+    config.registry_file_path.is_some() || data_dir.is_some()
+}
+
+fn open_registry(config: &EngineConfig, data_dir: Option<&Path>) -> DatabaseRegistry {
+    //! Load the registry file of the `config` and the databases of the
+    //! `data_dir`, or start an empty registry if there are none.
+
+    let mut _reg: DatabaseRegistry;
+    let _reg_path = config.registry_file_path.as_deref().map(Path::new);
+
+    if let Some(path) = _reg_path
+        && registry_exists(path)
+    {
+        _reg = load_registry(path).unwrap();
+        inform(
+            "info",
+            "A restored database registry was created at the session level.".to_string(),
         );
     } else {
-        println!(
-            "{}",
-            system_message(
+        match _reg_path {
+            Some(path) => inform(
                 "info",
                 format!(
                     "Failed to find registry at '{}'.",
-                    highlight_argument(_reg_path.to_str().unwrap())
+                    highlight_argument(path.to_str().unwrap())
+                ),
+            ),
+            None if data_dir.is_none() => println!(
+                "{}",
+                system_message(
+                    "warn",
+                    "No registry file is configured, data is in-memory only.".to_string()
                 )
-            )
-        );
+            ),
+            None => {}
+        }
         _reg = DatabaseRegistry::new();
+        inform(
+            "info",
+            "A default database registry was created at the session level.".to_string(),
+        );
+    }
+
+    if let Some(dir) = data_dir {
+        match load_data_dir(&mut _reg, dir) {
+            Ok(report) => {
+                for warning in report.skipped {
+                    println!("{}", system_message("warn", warning));
+                }
+                inform(
+                    "info",
+                    format!(
+                        "Loaded {} database(s) from '{}'.",
+                        report.loaded.len(),
+                        highlight_argument(dir.to_str().unwrap())
+                    ),
+                );
+            }
+            Err(error) => println!("{}", system_message("warn", error)),
+        }
+    }
+
+    _reg
+}
+
+pub fn shutdown(
+    registry: &Arc<RwLock<DatabaseRegistry>>,
+    path: Option<&Path>,
+    data_dir: Option<&Path>,
+) -> Result<(), String> {
+    //! Flush every database of the `registry` to the registry file at `path`
+    //! and to the `data_dir`, before the session exits on `corrode` or an
+    //! interrupt.
+    //!
+    //! Without either, nothing is written, and a warning that the data was
+    //! in-memory only is shown instead.
+
+    if path.is_none() && data_dir.is_none() {
         println!(
             "{}",
             system_message(
-                "info",
-                format!("A default database registry was created at the session level.")
+                "warn",
+                "No registry file is configured, data was in-memory only.".to_string()
             )
         );
+        return Ok(());
     }
 
-    let registry = Arc::new(RwLock::new(_reg));
-    let session = Arc::new(RwLock::new(Session::client(&registry)));
+    inform("info", "persisting...".to_string());
+    let registry = registry.read().unwrap();
 
-    start_repl(session);
+    if let Some(path) = path {
+        inform(
+            "info",
+            format!("Saving registry to '{}'.", path.to_str().unwrap()),
+        );
+        save_registry(&registry, path)?;
+    }
 
-    println!(
-        "{}",
-        system_message(
+    if let Some(dir) = data_dir {
+        inform(
             "info",
-            format!("Saving registry to '{}'.", _reg_path.to_str().unwrap())
-        )
-    );
-    save_registry(&registry.read().unwrap().clone(), _reg_path).unwrap();
+            format!("Saving databases to '{}'.", dir.to_str().unwrap()),
+        );
+        save_data_dir(&registry, dir)?;
+    }
+
+    Ok(())
 }
 
 pub fn run_server() {
@@ -115,39 +306,356 @@ pub fn show_help() {
     }
 }
 
-fn start_repl(client_session: Arc<RwLock<Session>>) {
+fn log_event(session: &Session, event: Event) {
+    //! Log the `event`, warning instead of failing when the log can't be written.
+
+    if let Err(error) = session.log_event(event) {
+        println!("{}", system_message("log", error));
+    }
+}
+
+fn read_input(label: &str) -> String {
+    print!("{:10}: ", label);
+    io::stdout().flush().unwrap();
+
+    let mut buffer = String::new();
+    io::stdin().read_line(&mut buffer).unwrap();
+    buffer.trim().to_string()
+}
+
+fn login(client_session: &Arc<RwLock<Session>>) -> bool {
+    //! Ask for a username and password until they are right, at most
+    //! [`LOGIN_ATTEMPTS`] times. Fails right away if no users exist yet, they
+    //! are added with [`create_user`].
+    //!
+    //! The password is read like any other input, so it shows on the terminal.
+
+    let mut session = client_session.write().unwrap();
+
+    if !session.has_users() {
+        println!(
+            "{}",
+            system_message(
+                "auth",
+                format!(
+                    "No users exist yet, add one with {}.",
+                    highlight_argument("--create-user")
+                )
+            )
+        );
+        return false;
+    }
+
+    for _ in 0..LOGIN_ATTEMPTS {
+        let username = read_input("username");
+        let password = read_input("password");
+
+        match session.authenticate(&username, &password) {
+            Ok(()) => {
+                println!(
+                    "{}",
+                    system_message(
+                        "auth",
+                        format!("Logged in as '{}'.", highlight_argument(&username))
+                    )
+                );
+                return true;
+            }
+            Err(error) => println!("{}", system_message("auth", error)),
+        }
+    }
+
+    false
+}
+
+pub fn create_user(config: &EngineConfig, data_dir: Option<&Path>) -> bool {
+    //! Add a user for the `--auth` login and save it, instead of starting the
+    //! REPL. Only the first user is added without logging in.
+    //!
+    //! Returns whether the user was added and saved.
+
+    if !is_persisted(config, data_dir) {
+        println!("{}", system_message("auth", AUTH_NOT_PERSISTED.to_string()));
+        return false;
+    }
+
+    let registry = Arc::new(RwLock::new(open_registry(config, data_dir)));
+    let session = Arc::new(RwLock::new(Session::client(&registry)));
+
+    if session.read().unwrap().has_users() && !login(&session) {
+        return false;
+    }
+
+    println!(
+        "{}",
+        system_message("auth", "Enter the new user.".to_string())
+    );
+    let username = read_input("username");
+    let password = read_input("password");
+    if let Err(error) = session.write().unwrap().create_user(&username, &password) {
+        println!("{}", system_message("auth", error));
+        return false;
+    }
+
+    let path = config.registry_file_path.as_deref().map(Path::new);
+    if let Err(error) = shutdown(&registry, path, data_dir) {
+        println!("{}", system_message("error", error));
+        return false;
+    }
+
     println!(
         "{}",
         system_message(
-            "system",
-            format!(
-                "Use '{}' to quit and '{}' to know all commands available.",
-                highlight_argument("corrode"),
-                highlight_argument("help"),
-            ),
+            "auth",
+            format!("Added user '{}'.", highlight_argument(&username))
         )
     );
+    true
+}
 
-    {
-        let session = client_session.read().unwrap();
-        let session_start_time = session.start_time_string();
-        println!(
-            "{}",
+pub fn execute_statements<F>(
+    sql: &str,
+    session: &Arc<RwLock<Session>>,
+    mut on_result: F,
+) -> Result<usize, String>
+where
+    F: FnMut(SqlResult, Duration),
+{
+    //! Run every `;` separated statement of `sql` in order, handing each result
+    //! and its execution time to `on_result`.
+    //!
+    //! Stops at the first statement that fails, and names it in the error. The
+    //! statements before it keep their effects.
+    //!
+    //! Returns the number of statements that were run.
+
+    let parser = SqlParser::new(Box::new(MySqlDialect {}));
+    let statements = parser.parse_sql(sql)?;
+    let n_statements = statements.len();
+
+    inform(
+        "ferrum",
+        format!("{} statement(s) were parsed successfully!", n_statements),
+    );
+
+    for (position, statement) in statements.into_iter().enumerate() {
+        let statement_text = statement.to_string();
+        let executor = SqlExecutor::new(statement, session);
+        let started = Instant::now();
+
+        let result = executor.execute();
+        let event = match &result {
+            Ok(result) => Event::Statement {
+                statement: statement_text,
+                rows: result.n_rows_processed.unwrap_or(0),
+            },
+            Err(error) => Event::Error {
+                statement: statement_text,
+                error: error.to_string(),
+            },
+        };
+        log_event(&session.read().unwrap(), event);
+
+        let result = result.map_err(|error| {
             system_message(
-                "system",
+                "ferrum",
                 format!(
-                    "New session initiated at '{}'.",
-                    highlight_argument(&session_start_time)
+                    "Statement {} of {} failed: {}",
+                    highlight_argument(&(position + 1).to_string()),
+                    n_statements,
+                    error
                 ),
             )
+        })?;
+        on_result(result, started.elapsed());
+    }
+
+    Ok(n_statements)
+}
+
+pub fn dry_run_statements(
+    sql: &str,
+    session: &Arc<RwLock<Session>>,
+) -> Result<Vec<Result<(), String>>, String> {
+    //! Check every `;` separated statement of `sql` without changing any data.
+    //!
+    //! The statements run in order in a detached copy of the session, see
+    //! [`Session::detached`], so they resolve tables and columns and validate
+    //! values exactly like a real run would, and see the effects of the ones
+    //! before them. Unlike [`execute_statements`], a failing statement does not
+    //! stop the others.
+    //!
+    //! Returns the outcome of every statement, in order.
+
+    let parser = SqlParser::new(Box::new(MySqlDialect {}));
+    let statements = parser.parse_sql(sql)?;
+    let detached = Arc::new(RwLock::new(session.read().unwrap().detached()));
+
+    Ok(statements
+        .into_iter()
+        .map(|statement| {
+            SqlExecutor::new(statement, &detached)
+                .execute()
+                .map(|_| ())
+                .map_err(String::from)
+        })
+        .collect())
+}
+
+pub fn run_script(
+    config: &EngineConfig,
+    script: &Path,
+    dry_run: bool,
+    format: OutputFormat,
+    timing: Timing,
+    data_dir: Option<&Path>,
+    quiet: bool,
+) -> bool {
+    //! Run the statements of the `script` file instead of starting the REPL,
+    //! then save the data like `corrode` does.
+    //!
+    //! With `dry_run`, the statements are only checked, see
+    //! [`dry_run_statements`], and nothing is saved.
+    //!
+    //! Returns whether every statement ran, or would run.
+
+    set_quiet(quiet);
+
+    let sql = match fs::read_to_string(script) {
+        Ok(sql) => sql,
+        Err(error) => {
+            println!(
+                "{}",
+                system_message(
+                    "error",
+                    format!(
+                        "Failed to read script '{}': {}",
+                        highlight_argument(script.to_str().unwrap()),
+                        error
+                    )
+                )
+            );
+            return false;
+        }
+    };
+
+    let registry = Arc::new(RwLock::new(open_registry(config, data_dir)));
+    let session = Arc::new(RwLock::new(Session::client(&registry)));
+
+    if dry_run {
+        return report_dry_run(&sql, &session);
+    }
+
+    let timing = matches!(timing, Timing::On);
+    let execution = execute_statements(&sql, &session, |result, elapsed| {
+        print_result(&result, timing.then_some(elapsed), format)
+    });
+    if let Err(error) = &execution {
+        println!("{}", error);
+    }
+
+    let path = config.registry_file_path.as_deref().map(Path::new);
+    if let Err(error) = shutdown(&registry, path, data_dir) {
+        println!("{}", system_message("error", error));
+        return false;
+    }
+
+    execution.is_ok()
+}
+
+fn report_dry_run(sql: &str, session: &Arc<RwLock<Session>>) -> bool {
+    //! Print every statement of `sql` that would fail, and how many would run.
+
+    let outcomes = match dry_run_statements(sql, session) {
+        Ok(outcomes) => outcomes,
+        Err(error) => {
+            println!("{}", system_message("dryrun", error));
+            return false;
+        }
+    };
+
+    let n_statements = outcomes.len();
+    let mut n_failures = 0;
+    for (position, outcome) in outcomes.into_iter().enumerate() {
+        if let Err(error) = outcome {
+            n_failures += 1;
+            println!(
+                "{}",
+                system_message(
+                    "dryrun",
+                    format!(
+                        "Statement {} of {} would fail: {}",
+                        highlight_argument(&(position + 1).to_string()),
+                        n_statements,
+                        error
+                    )
+                )
+            );
+        }
+    }
+
+    println!(
+        "{}",
+        system_message(
+            "dryrun",
+            format!(
+                "{} of {} statement(s) would run, no data was changed.",
+                n_statements - n_failures,
+                n_statements
+            )
+        )
+    );
+    n_failures == 0
+}
+
+fn print_result(result: &SqlResult, elapsed: Option<Duration>, format: OutputFormat) {
+    //! Print the result of a statement in the `format`, after how many rows it
+    //! processed.
+
+    inform(
+        "ferrum",
+        processed_message(result.n_rows_processed.unwrap_or(0), elapsed),
+    );
+
+    match (format, &result.table) {
+        (OutputFormat::Json, Some(table)) => println!("{}", table.to_json()),
+        _ => println!("{}", result.display()),
+    }
+}
+
+fn start_repl(client_session: Arc<RwLock<Session>>, format: OutputFormat) {
+    inform(
+        "system",
+        format!(
+            "Use '{}' to quit and '{}' to know all commands available.",
+            highlight_argument("corrode"),
+            highlight_argument("help"),
+        ),
+    );
+
+    {
+        let session = client_session.read().unwrap();
+        let session_start_time = session.start_time_string();
+        inform(
+            "system",
+            format!(
+                "New session initiated at '{}'.",
+                highlight_argument(&session_start_time)
+            ),
         );
     }
 
     loop {
-        let mut query_result: Option<SqlResult> = None;
-
         println!();
-        print!("{:6} > ", "ferrum".color(FERRUM_RED).bold());
+        // Read every iteration, so a SET takes effect on the next prompt.
+        let (prompt, timing) = {
+            let session = client_session.read().unwrap();
+            let config = session.config();
+            colored::control::set_override(config.color);
+            (config.prompt.clone(), config.timing)
+        };
+
+        print!("{:6} > ", prompt.color(FERRUM_RED).bold());
         io::stdout().flush().unwrap();
 
         let mut buffer = String::new();
@@ -180,57 +688,37 @@ fn start_repl(client_session: Arc<RwLock<Session>>) {
             session.add_to_command_history(buffer.clone().trim());
         }
 
-        match buffer.trim() {
-            "history" => {
+        match ReplInput::parse(&buffer) {
+            ReplInput::History => {
                 let session = client_session.read().unwrap();
                 session.show_command_history(None);
             }
-            "help" => show_help(),
-            "exit" => println!("did you mean '{}'?", "corrode".color(FERRUM_RED)),
-            "corrode" => break,
-            sql => {
-                let dialect = Box::new(MySqlDialect {});
-                let parser = SqlParser::new(dialect);
-
-                match parser.parse_single_sql(sql) {
-                    Ok(statement) => {
-                        println!(
-                            "{}",
-                            system_message(
-                                "ferrum",
-                                "The statement was parsed successfully!".to_string(),
-                            )
-                        );
-
-                        let executor = SqlExecutor::new(statement, &client_session);
-                        match executor.execute() {
-                            Ok(result) => {
-                                println!(
-                                    "{}",
-                                    system_message(
-                                        "ferrum",
-                                        format!(
-                                            "{} row(s) processed!",
-                                            result.n_rows_processed.unwrap_or(0)
-                                        )
-                                    )
-                                );
-
-                                query_result = Some(result);
-                            }
-                            Err(error) => println!("{}", error),
-                        }
-                    }
-                    Err(error) => {
-                        println!("{}", error);
-                    }
-                };
+            ReplInput::Help => show_help(),
+            ReplInput::Dump => {
+                let session = client_session.read().unwrap();
+                match session.get_active_database() {
+                    Some(database) => println!("{}", database.read().unwrap().to_sql_dump()),
+                    None => println!(
+                        "{}",
+                        system_message("system", "No database currently selected.".to_string())
+                    ),
+                }
             }
-        }
+            ReplInput::Reset => {
+                let mut session = client_session.write().unwrap();
+                session.reset();
+                inform("system", "The session was reset.".to_string());
+            }
+            ReplInput::Exit => println!("did you mean '{}'?", "corrode".color(FERRUM_RED)),
+            ReplInput::Corrode => break,
+            ReplInput::Sql(sql) => {
+                let execution = execute_statements(sql, &client_session, |result, elapsed| {
+                    print_result(&result, timing.then_some(elapsed), format)
+                });
 
-        if let Some(result) = query_result.take() {
-            if let Some(table) = result.table {
-                println!("{}", table)
+                if let Err(error) = execution {
+                    println!("{}", error);
+                }
             }
         }
     }
@@ -3,10 +3,23 @@
 //! When the functionality becomes extensive, they will
 //! each have their own files.
 
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
 use clap::{Parser, ValueEnum, arg, command};
-use sqlparser::{ast::Statement, dialect::Dialect, parser};
+use sqlparser::{
+    ast::Statement,
+    dialect::Dialect,
+    parser,
+    tokenizer::{Token, Tokenizer},
+};
 
 use crate::cli::messages::{highlight_argument, system_message};
+use crate::cli::{SqlExecutor, SqlResult};
+use crate::errors::EngineError;
+use crate::sessions::session::Session;
 
 #[derive(Parser)]
 #[command(name = "ferrum")]
@@ -15,6 +28,60 @@ pub struct CliParser {
     // Either operate in the client or server mode.
     #[arg(required = true)]
     pub mode: Option<CliMode>,
+
+    // How query results are printed in the client.
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: OutputFormat,
+
+    // Whether the client shows how long each statement took.
+    #[arg(long, value_enum, default_value = "on")]
+    pub timing: Timing,
+
+    // Ask for a username and password before the client starts. Needs a
+    // registry file or a data directory to keep the users in.
+    #[arg(long, conflicts_with = "file")]
+    pub auth: bool,
+
+    // Add a user for `--auth` and exit. Once a user exists, one of them has to
+    // log in before another one is added.
+    #[arg(long, conflicts_with_all = ["file", "auth"])]
+    pub create_user: bool,
+
+    // Append the statements, errors and connections of the session to this file.
+    #[arg(long, conflicts_with = "file")]
+    pub log: Option<PathBuf>,
+
+    // Load the databases saved in this directory on start, and save them there on exit.
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+
+    // Skip the splash screen and informational messages, printing only results,
+    // warnings and errors.
+    #[arg(long)]
+    pub quiet: bool,
+
+    // Run the statements of this file instead of starting the REPL.
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+
+    // Only check the statements of the file against a copy of the data, and
+    // report the ones that would fail, without changing anything.
+    #[arg(long, requires = "file")]
+    pub dry_run: bool,
+
+    // Serve at most this many connections at once in server mode. Hidden
+    // until the server mode exists, see `run_server`.
+    #[arg(
+        long,
+        hide = true,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub max_connections: Option<usize>,
+
+    // Queue the connections over the limit for up to this many seconds,
+    // instead of refusing them right away.
+    #[arg(long, hide = true, requires = "max_connections")]
+    pub queue_timeout: Option<u64>,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -26,6 +93,24 @@ pub enum CliMode {
     Client,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    // Print results as a boxed table.
+    Table,
+
+    // Print results as a JSON array of row objects.
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Timing {
+    // Show the execution time after every statement.
+    On,
+
+    // Only show the number of processed rows.
+    Off,
+}
+
 /// An SQL parser that performs the parsing and execution of the SQL
 /// statements.
 ///
@@ -40,24 +125,24 @@ impl SqlParser {
         SqlParser { dialect: dialect }
     }
 
-    pub fn parse_sql(&self, statement: &str) -> Result<Vec<Statement>, String> {
+    pub fn parse_sql(&self, statement: &str) -> Result<Vec<Statement>, EngineError> {
         //! Parse one or more SQL queries at once.
         //!
         //! Returns an AST of statements.
 
         let ast = parser::Parser::parse_sql(self.dialect.as_ref(), statement);
         ast.map_err(|e| {
-            system_message(
+            EngineError::ParseError(system_message(
                 "parser",
                 format!(
                     "Error parsing query: {}",
                     highlight_argument(e.to_string().as_str())
                 ),
-            )
+            ))
         })
     }
 
-    pub fn parse_single_sql(&self, statement: &str) -> Result<Statement, String> {
+    pub fn parse_single_sql(&self, statement: &str) -> Result<Statement, EngineError> {
         //! Parse only one SQL query at once.
         //!
         //! Returns an AST of the statement.
@@ -65,6 +150,126 @@ impl SqlParser {
         let mut statements = self.parse_sql(statement)?;
 
         if statements.len() > 1 {
+            Err(EngineError::ParseError(system_message(
+                "parser",
+                "Please write a single statement at a time.".to_string(),
+            )))
+        } else {
+            Ok(statements.remove(0))
+        }
+    }
+
+    pub fn prepare(&self, statement: &str) -> Result<PreparedStatement<'_>, String> {
+        //! Parse a single SQL query with `?` or `$1`, `$2`, ... placeholders
+        //! for values that are bound later, see [`PreparedStatement`].
+        //!
+        //! A query can't mix both placeholder styles.
+
+        let tokens = Tokenizer::new(self.dialect.as_ref(), statement)
+            .tokenize()
+            .map_err(|e| {
+                system_message(
+                    "parser",
+                    format!(
+                        "Error parsing query: {}",
+                        highlight_argument(e.to_string().as_str())
+                    ),
+                )
+            })?;
+
+        let mut positional = 0;
+        let mut numbered = 0;
+
+        for token in tokens.iter() {
+            if let Some(placeholder) = _placeholder(token) {
+                match placeholder {
+                    "?" => positional += 1,
+                    _ => {
+                        let number = placeholder
+                            .strip_prefix('$')
+                            .and_then(|number| number.parse::<usize>().ok())
+                            .filter(|number| *number > 0)
+                            .ok_or(format!(
+                                "invalid placeholder {}: expected ? or $n",
+                                placeholder
+                            ))?;
+                        numbered = numbered.max(number);
+                    }
+                }
+            }
+        }
+
+        if positional > 0 && numbered > 0 {
+            return Err("invalid placeholders: ? and $n can't be mixed".to_string());
+        }
+
+        let prepared = PreparedStatement {
+            parser: self,
+            tokens,
+            param_count: positional.max(numbered),
+        };
+        prepared._parse(prepared.tokens.clone())?;
+
+        Ok(prepared)
+    }
+}
+
+fn _placeholder(token: &Token) -> Option<&str> {
+    //! Get the placeholder a token stands for, if it is one.
+    //!
+    //! Dialects where `$` starts an identifier, like MySQL, read `$1` as a word.
+
+    match token {
+        Token::Placeholder(placeholder) => Some(placeholder),
+        Token::Word(word) if word.quote_style.is_none() && word.value.starts_with('$') => {
+            Some(&word.value)
+        }
+        _ => None,
+    }
+}
+
+/// A parsed SQL query whose placeholders are bound to values on execution.
+///
+/// The values are put into the query as single literal tokens, never pasted
+/// into its text, so a value can't change the shape of the query. A value that
+/// is a whole number becomes a number literal, anything else a string literal.
+/// Whether a value fits its column is checked when the statement runs, like
+/// any other literal.
+///
+/// # Example
+/// ```
+/// use ferrum_engine::cli::parsers::SqlParser;
+/// use sqlparser::dialect::MySqlDialect;
+///
+/// let parser = SqlParser::new(Box::new(MySqlDialect {}));
+/// let prepared = parser.prepare("SELECT * FROM users WHERE name = ?").unwrap();
+///
+/// assert_eq!(prepared.param_count(), 1);
+/// assert!(prepared.bind(vec!["ana' OR '1' = '1".to_string()]).is_ok());
+/// assert!(prepared.bind(vec![]).is_err());
+/// ```
+pub struct PreparedStatement<'a> {
+    parser: &'a SqlParser,
+    tokens: Vec<Token>,
+    param_count: usize,
+}
+
+impl PreparedStatement<'_> {
+    fn _parse(&self, tokens: Vec<Token>) -> Result<Statement, String> {
+        let mut statements = parser::Parser::new(self.parser.dialect.as_ref())
+            .with_tokens(tokens)
+            .parse_statements()
+            .map_err(|e| {
+                system_message(
+                    "parser",
+                    format!(
+                        "Error parsing query: {}",
+                        highlight_argument(e.to_string().as_str())
+                    ),
+                )
+            })?;
+
+        if statements.len() != 1 {
             Err(system_message(
                 "parser",
                 "Please write a single statement at a time.".to_string(),
@@ -73,4 +278,65 @@ impl SqlParser {
             Ok(statements.remove(0))
         }
     }
+
+    pub fn param_count(&self) -> usize {
+        //! The number of values the statement needs.
+
+        self.param_count
+    }
+
+    pub fn bind(&self, params: Vec<String>) -> Result<Statement, String> {
+        //! Put the `params` in place of the placeholders, the first value for
+        //! the first `?` or for `$1`, and so on.
+        //!
+        //! Fails if there are not exactly as many values as the statement needs.
+
+        if params.len() != self.param_count {
+            return Err(format!(
+                "invalid params: {} expected, {} provided",
+                self.param_count,
+                params.len()
+            ));
+        }
+
+        let mut next_positional = 0;
+        let tokens = self
+            .tokens
+            .iter()
+            .map(|token| {
+                let Some(placeholder) = _placeholder(token) else {
+                    return token.clone();
+                };
+
+                let index = match placeholder.strip_prefix('$') {
+                    Some(number) => number.parse::<usize>().unwrap() - 1,
+                    None => {
+                        next_positional += 1;
+                        next_positional - 1
+                    }
+                };
+
+                let value = &params[index];
+                if value.parse::<i64>().is_ok() {
+                    Token::Number(value.clone(), false)
+                } else {
+                    Token::SingleQuotedString(value.clone())
+                }
+            })
+            .collect();
+
+        self._parse(tokens)
+    }
+
+    pub fn execute(
+        &self,
+        params: Vec<String>,
+        session: &Arc<RwLock<Session>>,
+    ) -> Result<SqlResult, EngineError> {
+        //! Bind the `params` and run the statement in the `session`, see
+        //! [`PreparedStatement::bind`].
+
+        let statement = self.bind(params)?;
+        SqlExecutor::new(statement, session).execute()
+    }
 }
@@ -0,0 +1,60 @@
+//! The plan of a SELECT statement: the steps the executor takes to answer it.
+//!
+//! A plan is built before the query runs, so `EXPLAIN` can describe those steps
+//! without running them.
+
+/// How the rows of the queried table are read.
+pub enum TableAccess {
+    /// Every row of the table is read.
+    FullScan,
+    /// Only the rows with a `column` value between `low` and `high`, both
+    /// inclusive, are read through the column's ordered index.
+    IndexRange { column: String, low: i64, high: i64 },
+}
+
+pub struct SelectPlan {
+    pub table_name: String,
+    pub access: TableAccess,
+    pub filter: Option<String>,
+    pub group_by: Vec<String>,
+    pub having: Option<String>,
+    pub projection: Vec<String>,
+    pub order_by: Option<String>,
+    pub limit: Option<String>,
+}
+
+impl SelectPlan {
+    pub fn steps(&self) -> Vec<String> {
+        //! Describe the plan as one line per step, in the order they run.
+
+        let mut steps = vec![match &self.access {
+            TableAccess::FullScan => format!("Full scan on {}", self.table_name),
+            TableAccess::IndexRange { column, low, high } if low == high => {
+                format!("Index lookup on {} ({} = {})", self.table_name, column, low)
+            }
+            TableAccess::IndexRange { column, low, high } => format!(
+                "Index range scan on {} ({} BETWEEN {} AND {})",
+                self.table_name, column, low, high
+            ),
+        }];
+
+        if let Some(filter) = &self.filter {
+            steps.push(format!("Filter: {}", filter));
+        }
+        if !self.group_by.is_empty() {
+            steps.push(format!("Group by: {}", self.group_by.join(", ")));
+        }
+        if let Some(having) = &self.having {
+            steps.push(format!("Having: {}", having));
+        }
+        steps.push(format!("Project: {}", self.projection.join(", ")));
+        if let Some(order_by) = &self.order_by {
+            steps.push(format!("Order by: {}", order_by));
+        }
+        if let Some(limit) = &self.limit {
+            steps.push(format!("Limit: {}", limit));
+        }
+
+        steps
+    }
+}
@@ -1,20 +1,60 @@
 use dotenvy::dotenv;
-use std::env;
+use std::{env, sync::Arc, time::Duration};
+
+use crate::sessions::limit::{ConnectionLimit, WhenFull};
+
+const DEFAULT_SESSION_IDLE_TIMEOUT_SECS: u64 = 300;
 
 pub struct EngineConfig {
-    pub registry_file_path: String,
+    /// Where the registry is loaded from and saved to. Without it, all data
+    /// stays in memory and is lost on exit.
+    pub registry_file_path: Option<String>,
+    /// How long a pooled session may stay idle before
+    /// [`SessionPool::sweep_idle`](crate::sessions::pool::SessionPool::sweep_idle)
+    /// expires it. Unused until the server mode exists.
+    pub session_idle_timeout: Duration,
+    /// How many connections a server serves at once, without a limit if unset.
+    /// Never 0, a server that takes no connections is no server at all.
+    pub max_connections: Option<usize>,
+    /// How long a connection waits for a free slot when the server is full.
+    /// Without it, the connection is refused right away.
+    pub connection_queue_timeout: Option<Duration>,
 }
 
 impl EngineConfig {
     pub fn new() -> EngineConfig {
         dotenv().ok();
 
-        fn get(key: &str) -> String {
-            env::var(key).unwrap_or_else(|_| panic!("Missing env var: {}", key))
-        }
-
         EngineConfig {
-            registry_file_path: get("FERRUM_REGISTRY_FILE"),
+            registry_file_path: env::var("FERRUM_REGISTRY_FILE").ok(),
+            session_idle_timeout: Duration::from_secs(
+                env::var("FERRUM_SESSION_IDLE_TIMEOUT")
+                    .ok()
+                    .and_then(|secs| secs.parse().ok())
+                    .unwrap_or(DEFAULT_SESSION_IDLE_TIMEOUT_SECS),
+            ),
+            max_connections: env::var("FERRUM_MAX_CONNECTIONS")
+                .ok()
+                .and_then(|max| max.parse().ok())
+                .filter(|max| *max > 0),
+            connection_queue_timeout: env::var("FERRUM_CONNECTION_QUEUE_TIMEOUT")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .map(Duration::from_secs),
         }
     }
+
+    pub fn connection_limit(&self) -> Option<Arc<ConnectionLimit>> {
+        //! The limit on the connections of a server, if `max_connections` is set.
+        //! Connections over it are queued if `connection_queue_timeout` is set,
+        //! and refused otherwise.
+
+        let when_full = match self.connection_queue_timeout {
+            Some(timeout) => WhenFull::Queue(timeout),
+            None => WhenFull::Refuse,
+        };
+
+        self.max_connections
+            .map(|max_connections| ConnectionLimit::new(max_connections, when_full))
+    }
 }
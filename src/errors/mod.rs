@@ -0,0 +1,95 @@
+//! The errors of the engine, with a kind that can be told apart by code.
+//!
+//! An [`EngineError`] keeps the message the engine has always shown for it,
+//! which is what its [`Display`] writes, and adds a stable [`EngineError::code`]
+//! for embedders and a wire protocol to match on.
+//!
+//! Most of the engine still fails with plain `String`s. A `String` converts to
+//! [`EngineError::Other`] and any error converts back to its message, so both
+//! mix freely with `?`. For now the kinds are kept from inserts and column
+//! lookups in the persistence layer, through the SQL parser, up to
+//! [`SqlExecutor::execute`].
+//!
+//! [`SqlExecutor::execute`]: crate::cli::SqlExecutor::execute
+
+use std::fmt::Display;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EngineError {
+    /// A table that is not in the database.
+    TableNotFound(String),
+    /// A column that is not in the table.
+    ColumnNotFound(String),
+    /// A value that is not valid for the type of its column.
+    TypeMismatch(String),
+    /// A NULL on a column that does not allow it.
+    NullViolation(String),
+    /// A foreign key that refers to no row of its parent table.
+    ForeignKeyViolation(String),
+    /// A statement that is not valid SQL.
+    ParseError(String),
+    /// Any other failure, not told apart yet.
+    Other(String),
+}
+
+impl EngineError {
+    pub fn code(&self) -> &'static str {
+        //! The kind of the error as a stable, machine-readable code.
+
+        match self {
+            EngineError::TableNotFound(_) => "table_not_found",
+            EngineError::ColumnNotFound(_) => "column_not_found",
+            EngineError::TypeMismatch(_) => "type_mismatch",
+            EngineError::NullViolation(_) => "null_violation",
+            EngineError::ForeignKeyViolation(_) => "foreign_key_violation",
+            EngineError::ParseError(_) => "parse_error",
+            EngineError::Other(_) => "other",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            EngineError::TableNotFound(message)
+            | EngineError::ColumnNotFound(message)
+            | EngineError::TypeMismatch(message)
+            | EngineError::NullViolation(message)
+            | EngineError::ForeignKeyViolation(message)
+            | EngineError::ParseError(message)
+            | EngineError::Other(message) => message,
+        }
+    }
+
+    pub fn prefixed(mut self, prefix: &str) -> EngineError {
+        //! Put `prefix` in front of the message, keeping the kind, e.g. to name
+        //! the failing row of a bulk insert.
+
+        match &mut self {
+            EngineError::TableNotFound(message)
+            | EngineError::ColumnNotFound(message)
+            | EngineError::TypeMismatch(message)
+            | EngineError::NullViolation(message)
+            | EngineError::ForeignKeyViolation(message)
+            | EngineError::ParseError(message)
+            | EngineError::Other(message) => message.insert_str(0, prefix),
+        }
+        self
+    }
+}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<String> for EngineError {
+    fn from(message: String) -> Self {
+        EngineError::Other(message)
+    }
+}
+
+impl From<EngineError> for String {
+    fn from(error: EngineError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,242 @@
+/// Incremental versions of the aggregators.
+///
+/// An [`Accumulator`] is fed one row at a time, so an aggregate can be
+/// computed while the rows are being scanned, instead of collecting them
+/// into a [`Vec<Row>`] first. The final value matches what the batch
+/// `run` of the same aggregator returns over the same rows.
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::persistence::Row;
+
+use super::collation::{Collation, column_and_collation};
+use super::{count, first, group_concat, last, max, min, sum};
+
+#[derive(Clone)]
+pub enum Accumulator {
+    Count {
+        col_index: Option<usize>,
+        count: usize,
+    },
+    CountDistinct {
+        col_index: usize,
+        values: HashSet<String>,
+    },
+    Sum {
+        col_index: usize,
+        total: i64,
+    },
+    First {
+        col_index: usize,
+        value: Option<String>,
+    },
+    Last {
+        col_index: usize,
+        value: Option<String>,
+    },
+    GroupConcat {
+        col_index: usize,
+        separator: String,
+        text: Option<String>,
+    },
+    Min {
+        col_index: usize,
+        collation: Collation,
+        value: Option<String>,
+    },
+    Max {
+        col_index: usize,
+        collation: Collation,
+        value: Option<String>,
+    },
+}
+
+fn _single_column(name: &str, args: &[String]) -> Result<usize, String> {
+    if args.len() != 1 || args.contains(&"*".to_string()) {
+        return Err(format!("{} strictly allows a single column.", name));
+    }
+
+    args.first()
+        .unwrap()
+        .parse::<usize>()
+        .map_err(|_| "No index specified.".to_string())
+}
+
+impl Accumulator {
+    pub fn new(name: &str, args: &[String]) -> Result<Accumulator, String> {
+        //! Create an empty accumulator for the aggregator `name`, taking the
+        //! same arguments as its batch `run`.
+
+        match name.to_uppercase().as_str() {
+            count::AGGR_NAME => {
+                if args.contains(&"*".to_string()) {
+                    Ok(Accumulator::Count {
+                        col_index: None,
+                        count: 0,
+                    })
+                } else if args.first().is_some_and(|arg| arg == count::DISTINCT_TOKEN) {
+                    Ok(Accumulator::CountDistinct {
+                        col_index: _single_column(count::AGGR_NAME, &args[1..])?,
+                        values: HashSet::new(),
+                    })
+                } else {
+                    Ok(Accumulator::Count {
+                        col_index: Some(_single_column(count::AGGR_NAME, args)?),
+                        count: 0,
+                    })
+                }
+            }
+            sum::AGGR_NAME => Ok(Accumulator::Sum {
+                col_index: _single_column(sum::AGGR_NAME, args)?,
+                total: 0,
+            }),
+            min::AGGR_NAME => {
+                let (col_index, collation) = column_and_collation(min::AGGR_NAME, args)?;
+                Ok(Accumulator::Min {
+                    col_index,
+                    collation,
+                    value: None,
+                })
+            }
+            max::AGGR_NAME => {
+                let (col_index, collation) = column_and_collation(max::AGGR_NAME, args)?;
+                Ok(Accumulator::Max {
+                    col_index,
+                    collation,
+                    value: None,
+                })
+            }
+            first::AGGR_NAME => Ok(Accumulator::First {
+                col_index: _single_column(first::AGGR_NAME, args)?,
+                value: None,
+            }),
+            last::AGGR_NAME => Ok(Accumulator::Last {
+                col_index: _single_column(last::AGGR_NAME, args)?,
+                value: None,
+            }),
+            group_concat::AGGR_NAME | group_concat::AGGR_ALIAS => {
+                let (col_index, separator) = group_concat::column_and_separator(args)?;
+                Ok(Accumulator::GroupConcat {
+                    col_index,
+                    separator,
+                    text: None,
+                })
+            }
+            _ => Err(format!("Unknown aggregate function: {}", name)),
+        }
+    }
+
+    pub fn update(&mut self, row: &Row) -> Result<(), String> {
+        //! Feed a single row into the accumulator. NULL cells are skipped.
+
+        match self {
+            Accumulator::Count { col_index, count } => match col_index {
+                None => *count += 1,
+                Some(index) => {
+                    if row.get_str(*index).is_some() {
+                        *count += 1;
+                    }
+                }
+            },
+            Accumulator::CountDistinct { col_index, values } => {
+                if let Some(value) = row.get_str(*col_index)
+                    && !values.contains(value.as_ref())
+                {
+                    values.insert(value.into_owned());
+                }
+            }
+            Accumulator::Sum { col_index, total } => {
+                if let Some(value) = row.get_str(*col_index) {
+                    *total = sum::add(*total, &value)?;
+                }
+            }
+            Accumulator::First { col_index, value } => {
+                if value.is_none()
+                    && let Some(cell) = row.get_str(*col_index)
+                {
+                    *value = Some(cell.into_owned());
+                }
+            }
+            Accumulator::Last { col_index, value } => {
+                if let Some(cell) = row.get_str(*col_index) {
+                    *value = Some(cell.into_owned());
+                }
+            }
+            Accumulator::GroupConcat {
+                col_index,
+                separator,
+                text,
+            } => {
+                if let Some(value) = row.get_str(*col_index) {
+                    match text {
+                        Some(text) => {
+                            text.push_str(separator);
+                            text.push_str(&value);
+                        }
+                        None => *text = Some(value.into_owned()),
+                    }
+                }
+            }
+            Accumulator::Min {
+                col_index,
+                collation,
+                value,
+            } => {
+                if let Some(cell) = row.get_str(*col_index)
+                    && value
+                        .as_ref()
+                        .is_none_or(|current| collation.compare(&cell, current) == Ordering::Less)
+                {
+                    *value = Some(cell.into_owned());
+                }
+            }
+            Accumulator::Max {
+                col_index,
+                collation,
+                value,
+            } => {
+                if let Some(cell) = row.get_str(*col_index)
+                    && value
+                        .as_ref()
+                        .is_none_or(|current| collation.compare(&cell, current) == Ordering::Greater)
+                {
+                    *value = Some(cell.into_owned());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(&self) -> Result<String, String> {
+        //! Get the aggregated value of all the rows fed so far.
+
+        match self {
+            Accumulator::Count { count, .. } => Ok(count.to_string()),
+            Accumulator::CountDistinct { values, .. } => Ok(values.len().to_string()),
+            Accumulator::Sum { total, .. } => Ok(total.to_string()),
+            Accumulator::GroupConcat { text, .. } => Ok(text.clone().unwrap_or_default()),
+            Accumulator::Min { value, .. } => value
+                .clone()
+                .ok_or_else(|| format!("{} found no values to compare.", min::AGGR_NAME)),
+            Accumulator::Max { value, .. } => value
+                .clone()
+                .ok_or_else(|| format!("{} found no values to compare.", max::AGGR_NAME)),
+            Accumulator::First { value, .. } => value
+                .clone()
+                .ok_or_else(|| format!("{} found only NULL values.", first::AGGR_NAME)),
+            Accumulator::Last { value, .. } => value
+                .clone()
+                .ok_or_else(|| format!("{} found only NULL values.", last::AGGR_NAME)),
+        }
+    }
+
+    pub fn evaluate(&self) -> Result<Option<String>, String> {
+        //! Like [`Accumulator::finish`], but the aggregated value may be NULL.
+
+        match self {
+            Accumulator::First { value, .. } | Accumulator::Last { value, .. } => Ok(value.clone()),
+            _ => self.finish().map(Some),
+        }
+    }
+}
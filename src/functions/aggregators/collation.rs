@@ -0,0 +1,77 @@
+/// The ordering used when text values are compared by an aggregator.
+///
+/// Handed to MIN and MAX as an optional second argument holding the
+/// collation's name, e.g. `MIN(name COLLATE nocase)` in SQL. Columns of a
+/// numeric type are handed the [`Collation::Numeric`] one instead, see
+/// [`super::numeric_collation`].
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Collation {
+    /// Rust's natural, case-sensitive string ordering.
+    #[default]
+    Binary,
+    /// Case-insensitive ordering; values that only differ in case fall back
+    /// to the binary ordering so the result stays deterministic.
+    NoCase,
+    /// Ordering by value, for numeric columns, so `10` is above `2`. Values
+    /// that are not numbers fall back to the binary ordering.
+    Numeric,
+}
+
+impl Collation {
+    pub fn from_name(name: &str) -> Result<Collation, String> {
+        match name.to_uppercase().as_str() {
+            "BINARY" => Ok(Collation::Binary),
+            "NOCASE" => Ok(Collation::NoCase),
+            "NUMERIC" => Ok(Collation::Numeric),
+            _ => Err(format!(
+                "invalid collation {}: expected binary, nocase or numeric",
+                name
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Collation::Binary => "BINARY",
+            Collation::NoCase => "NOCASE",
+            Collation::Numeric => "NUMERIC",
+        }
+    }
+
+    pub fn compare(&self, left: &str, right: &str) -> Ordering {
+        //! Compare two values with this collation.
+
+        match self {
+            Collation::Numeric => match (left.parse::<f64>(), right.parse::<f64>()) {
+                (Ok(left), Ok(right)) => left.total_cmp(&right),
+                _ => left.cmp(right),
+            },
+            Collation::Binary => left.cmp(right),
+            Collation::NoCase => left
+                .to_lowercase()
+                .cmp(&right.to_lowercase())
+                .then_with(|| left.cmp(right)),
+        }
+    }
+}
+
+pub(super) fn column_and_collation(
+    name: &str,
+    args: &[String],
+) -> Result<(usize, Collation), String> {
+    //! Read the `[column, collation?]` arguments of a comparing aggregator.
+
+    let (column, collation) = match args {
+        [column] => (column, Collation::default()),
+        [column, collation] => (column, Collation::from_name(collation)?),
+        _ => return Err(format!("{} strictly allows a single column.", name)),
+    };
+
+    let col_index = column
+        .parse::<usize>()
+        .map_err(|_| "No index specified.".to_string())?;
+
+    Ok((col_index, collation))
+}
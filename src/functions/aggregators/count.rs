@@ -1,33 +1,61 @@
 /// The COUNT(arg) aggregator.
 ///
-/// Returns the total number of non-numm values in the given data.
+/// Returns the total number of non-numm values in the given data. With a
+/// leading [`DISTINCT_TOKEN`] argument, only unique non-null values count.
+use std::borrow::Cow;
+use std::collections::HashSet;
+
 use crate::persistence::Row;
 
+use super::resolver::Aggregate;
+
 pub(super) const AGGR_NAME: &str = "COUNT";
 
-pub(super) fn run(args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
-    if args.contains(&"*".to_string()) {
-        Ok(rows.len().to_string())
-    } else {
-        if args.len() > 1 {
-            Err(format!(
-                "{} takes in a wildcard or a single column.",
-                AGGR_NAME
-            ))
+/// The argument put before the column in `COUNT(DISTINCT col)`.
+pub const DISTINCT_TOKEN: &str = "DISTINCT";
+
+pub(super) struct Count;
+
+impl Aggregate for Count {
+    fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
+        if args.contains(&"*".to_string()) {
+            Ok(rows.len().to_string())
+        } else if args.first().is_some_and(|arg| arg == DISTINCT_TOKEN) {
+            if args.len() != 2 {
+                return Err(format!("{} DISTINCT takes in a single column.", AGGR_NAME));
+            }
+
+            let col_index = args[1]
+                .parse::<usize>()
+                .map_err(|_| "No index specified.".to_string())?;
+
+            let values: HashSet<Cow<str>> = rows
+                .iter()
+                .filter_map(|row| row.get_str(col_index))
+                .collect();
+
+            Ok(values.len().to_string())
         } else {
-            let mut total_count = 0;
-            let col_index = {
-                let _a = args.first().unwrap();
-                _a.parse::<usize>().expect("No index specified.")
-            };
-
-            rows.iter().for_each(|row| {
-                if row.0.get(col_index).is_some() {
-                    total_count += 1;
-                }
-            });
-
-            Ok(total_count.to_string())
+            if args.len() > 1 {
+                Err(format!(
+                    "{} takes in a wildcard or a single column.",
+                    AGGR_NAME
+                ))
+            } else {
+                let mut total_count = 0;
+                let col_index = args
+                    .first()
+                    .and_then(|arg| arg.parse::<usize>().ok())
+                    .ok_or_else(|| format!("{} strictly allows a single column.", AGGR_NAME))?;
+
+                rows.iter().for_each(|row| {
+                    if row.get_str(col_index).is_some() {
+                        total_count += 1;
+                    }
+                });
+
+                Ok(total_count.to_string())
+            }
         }
     }
 }
@@ -0,0 +1,35 @@
+/// The FIRST(col) aggregator.
+///
+/// Returns the first non-null value of a column, in the order the rows are
+/// handed in, or NULL if there is none.
+use crate::persistence::Row;
+
+use super::resolver::Aggregate;
+
+pub(super) const AGGR_NAME: &str = "FIRST";
+
+pub(super) struct First;
+
+impl Aggregate for First {
+    fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
+        self.evaluate(args, rows)?
+            .ok_or_else(|| format!("{} found only NULL values.", AGGR_NAME))
+    }
+
+    fn evaluate(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<Option<String>, String> {
+        let col_index = single_column(AGGR_NAME, args)?;
+
+        Ok(rows
+            .iter()
+            .find_map(|row| row.get_str(col_index).map(String::from)))
+    }
+}
+
+pub(super) fn single_column(name: &str, args: &[String]) -> Result<usize, String> {
+    match args {
+        [column] => column
+            .parse::<usize>()
+            .map_err(|_| "No index specified.".to_string()),
+        _ => Err(format!("{} strictly allows a single column.", name)),
+    }
+}
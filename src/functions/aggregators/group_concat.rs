@@ -0,0 +1,55 @@
+/// The GROUP_CONCAT(col, separator?) aggregator, also known as STRING_AGG.
+///
+/// Returns the non-null values of a column joined by the separator, which is
+/// a comma unless given as a literal second argument.
+use std::borrow::Cow;
+
+use crate::persistence::Row;
+
+use super::resolver::Aggregate;
+
+pub(super) const AGGR_NAME: &str = "GROUP_CONCAT";
+pub(super) const AGGR_ALIAS: &str = "STRING_AGG";
+
+const DEFAULT_SEPARATOR: &str = ",";
+
+pub(super) fn column_and_separator(args: &[String]) -> Result<(usize, String), String> {
+    //! Read the `[column, 'separator'?]` arguments, where the separator is a
+    //! quoted literal.
+
+    let (column, separator) = match args {
+        [column] => (column, DEFAULT_SEPARATOR),
+        [column, separator]
+            if separator.len() >= 2 && separator.starts_with('\'') && separator.ends_with('\'') =>
+        {
+            (column, &separator[1..separator.len() - 1])
+        }
+        _ => {
+            return Err(format!(
+                "{} takes in a column and an optional separator.",
+                AGGR_NAME
+            ));
+        }
+    };
+
+    let col_index = column
+        .parse::<usize>()
+        .map_err(|_| "No index specified.".to_string())?;
+
+    Ok((col_index, separator.to_string()))
+}
+
+pub(super) struct GroupConcat;
+
+impl Aggregate for GroupConcat {
+    fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
+        let (col_index, separator) = column_and_separator(args)?;
+
+        let values: Vec<Cow<str>> = rows
+            .iter()
+            .filter_map(|row| row.get_str(col_index))
+            .collect();
+
+        Ok(values.join(&separator))
+    }
+}
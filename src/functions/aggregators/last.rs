@@ -0,0 +1,28 @@
+/// The LAST(col) aggregator.
+///
+/// Returns the last non-null value of a column, in the order the rows are
+/// handed in, or NULL if there is none.
+use crate::persistence::Row;
+
+use super::first::single_column;
+use super::resolver::Aggregate;
+
+pub(super) const AGGR_NAME: &str = "LAST";
+
+pub(super) struct Last;
+
+impl Aggregate for Last {
+    fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
+        self.evaluate(args, rows)?
+            .ok_or_else(|| format!("{} found only NULL values.", AGGR_NAME))
+    }
+
+    fn evaluate(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<Option<String>, String> {
+        let col_index = single_column(AGGR_NAME, args)?;
+
+        Ok(rows
+            .iter()
+            .rev()
+            .find_map(|row| row.get_str(col_index).map(String::from)))
+    }
+}
@@ -1,28 +1,32 @@
 /// Find the largest value of a particular index.
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
 use crate::persistence::Row;
 
+use super::collation::column_and_collation;
+use super::resolver::Aggregate;
+
 pub(super) const AGGR_NAME: &str = "MAX";
 
-pub(super) fn run(args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
-    if args.len() > 1 {
-        Err(format!("{} strictly allows a single column.", AGGR_NAME))
-    } else {
-        let col_index = {
-            let _a = args.first().unwrap();
-            _a.parse::<usize>().expect("No index specified.")
-        };
-        let mut max = None;
+pub(super) struct Max;
+
+impl Aggregate for Max {
+    fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
+        let (col_index, collation) = column_and_collation(AGGR_NAME, args)?;
+        let mut max: Option<Cow<str>> = None;
 
         rows.iter().for_each(|row| {
-            if let Some(value) = row.0.get(col_index) {
-                if matches!(max, None) {
-                    max = value.clone();
-                } else if value.as_ref().unwrap().gt(max.as_ref().unwrap()) {
-                    max = value.clone()
-                }
+            if let Some(value) = row.get_str(col_index)
+                && max
+                    .as_ref()
+                    .is_none_or(|current| collation.compare(&value, current) == Ordering::Greater)
+            {
+                max = Some(value);
             }
         });
 
-        Ok(max.unwrap())
+        max.map(Cow::into_owned)
+            .ok_or_else(|| format!("{} found no values to compare.", AGGR_NAME))
     }
 }
@@ -1,23 +1,102 @@
-use crate::persistence::Row;
+use std::sync::LazyLock;
 
+use crate::persistence::{DataType, Row, Schema};
+
+mod accumulator;
+mod collation;
 mod count;
+mod first;
+mod group_concat;
+mod last;
 mod max;
 mod min;
+mod resolver;
+mod sum;
+
+pub use accumulator::Accumulator;
+pub use collation::Collation;
+pub use count::DISTINCT_TOKEN;
+pub use resolver::{Aggregate, AggregateResolver};
 
-const ALLOWED_AGGREGATORS: [&str; 3] = [count::AGGR_NAME, min::AGGR_NAME, max::AGGR_NAME];
+/// The resolver holding all the in-built aggregators, built once on first use.
+static DEFAULT_RESOLVER: LazyLock<AggregateResolver> = LazyLock::new(AggregateResolver::default);
 
 /// A central method that works as a registry for all aggregators.
 ///
-/// To add one, simple add another match arm.
+/// To add one, register it inside [`AggregateResolver::default`].
 pub fn run(name: &String, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
-    match name.to_uppercase().as_str() {
-        count::AGGR_NAME => count::run(args, rows),
-        max::AGGR_NAME => max::run(args, rows),
-        min::AGGR_NAME => min::run(args, rows),
-        _ => Err(format!("Unknown aggregate function: {}", name)),
+    let name = name.to_uppercase();
+
+    if !DEFAULT_RESOLVER.is_registered(&name) {
+        return Err(format!("Unknown aggregate function: {}", name));
+    }
+
+    DEFAULT_RESOLVER.run(&name, args, rows)
+}
+
+pub fn run_with_schema(
+    name: &String,
+    args: &[String],
+    schema: &Schema,
+    rows: &Vec<Row>,
+) -> Result<String, String> {
+    //! Like [`run`], but the column argument may be a column name of `schema`
+    //! too, which is resolved to its index before the aggregator runs.
+    //!
+    //! The column is the first argument, or the one after a [`DISTINCT_TOKEN`].
+    //! Indices and wildcards are passed on as they are.
+
+    let mut args = args.to_vec();
+    let position = usize::from(args.first().is_some_and(|arg| arg == DISTINCT_TOKEN));
+
+    if let Some(column) = args.get_mut(position)
+        && column != "*"
+        && column.parse::<usize>().is_err()
+    {
+        let col_index = schema
+            .column_index(column)
+            .ok_or_else(|| schema.column_not_found(column))?;
+        *column = col_index.to_string();
     }
+
+    if let Some((_, col_info)) = args
+        .first()
+        .and_then(|column| column.parse::<usize>().ok())
+        .and_then(|col_index| schema.get(col_index))
+    {
+        numeric_collation(name, col_info.datatype(), &mut args);
+    }
+
+    run(name, &args, rows)
+}
+
+pub fn numeric_collation(name: &str, datatype: &DataType, args: &mut Vec<String>) {
+    //! Make MIN and MAX compare the values of a numeric column by number, by
+    //! handing them the [`Collation::Numeric`] in place of any text collation.
+    //!
+    //! The `args` are the resolved arguments, starting with the column index.
+    //! Other aggregators, and columns of other types, are left as they are.
+
+    let compares = [min::AGGR_NAME, max::AGGR_NAME].contains(&name.to_uppercase().as_str());
+
+    if compares && datatype.is_numeric() && !args.is_empty() {
+        args.truncate(1);
+        args.push(Collation::Numeric.name().to_string());
+    }
+}
+
+pub fn evaluate(name: &str, args: &Vec<String>, rows: &Vec<Row>) -> Result<Option<String>, String> {
+    //! Like [`run`], but the aggregated value may be NULL.
+
+    let name = name.to_uppercase();
+
+    if !DEFAULT_RESOLVER.is_registered(&name) {
+        return Err(format!("Unknown aggregate function: {}", name));
+    }
+
+    DEFAULT_RESOLVER.evaluate(&name, args, rows)
 }
 
 pub fn is_allowed(name: &String) -> bool {
-    ALLOWED_AGGREGATORS.contains(&name.as_str())
+    DEFAULT_RESOLVER.is_registered(&name.to_uppercase())
 }
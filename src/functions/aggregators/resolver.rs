@@ -1,6 +1,4 @@
-//! [ALTERNATIVE APPROACH - IF THE FUNCTIONS BECOME TOO MANY TO MANAGE]
-//! 
-//! This module contains all the built-in aggregator functions.
+//! This module contains the registry for all the aggregator functions.
 //!
 //! # Current Plan
 //!
@@ -25,9 +23,19 @@ use indexmap::IndexMap;
 
 use crate::persistence::Row;
 
+use super::{count, first, group_concat, last, max, min, sum};
+
 /// A trait that must be implemented by a struct to be registered and used as
 /// an aggregate function by the persistence engine.
-pub(crate) trait Aggregate {
+///
+/// The rows are handed in the order the engine scanned them, which is the
+/// order they were inserted into the table, after WHERE has been applied.
+/// `ORDER BY` sorts the aggregated result and is not applied beforehand.
+/// Order sensitive aggregators, like FIRST and LAST, rely on this order and
+/// must not reorder the rows themselves.
+///
+/// Aggregators are shared between sessions, so they must be [`Send`] and [`Sync`].
+pub trait Aggregate: Send + Sync {
     fn _has_wild_card(&self, args: &Vec<String>) -> bool {
         if args.contains(&"*".to_string()) {
             true
@@ -37,13 +45,60 @@ pub(crate) trait Aggregate {
     }
 
     fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String>;
+
+    fn evaluate(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<Option<String>, String> {
+        //! Run the aggregator where the result is allowed to be NULL, as [`None`].
+        //!
+        //! Defaults to [`Aggregate::run`], which never gives NULL. Aggregators
+        //! that can, such as FIRST and LAST, override this.
+
+        self.run(args, rows).map(Some)
+    }
 }
 
-pub(crate) struct AggregateResolver {
+pub struct AggregateResolver {
     registry: IndexMap<String, Box<dyn Aggregate>>,
 }
 
+impl Default for AggregateResolver {
+    fn default() -> Self {
+        //! A resolver with all the in-built aggregators registered.
+
+        let mut resolver = AggregateResolver::new();
+
+        resolver.register(count::AGGR_NAME.to_string(), Box::new(count::Count));
+        resolver.register(min::AGGR_NAME.to_string(), Box::new(min::Min));
+        resolver.register(max::AGGR_NAME.to_string(), Box::new(max::Max));
+        resolver.register(sum::AGGR_NAME.to_string(), Box::new(sum::Sum));
+        resolver.register(
+            group_concat::AGGR_NAME.to_string(),
+            Box::new(group_concat::GroupConcat),
+        );
+        resolver.register(
+            group_concat::AGGR_ALIAS.to_string(),
+            Box::new(group_concat::GroupConcat),
+        );
+        resolver.register(first::AGGR_NAME.to_string(), Box::new(first::First));
+        resolver.register(last::AGGR_NAME.to_string(), Box::new(last::Last));
+
+        resolver
+    }
+}
+
 impl AggregateResolver {
+    pub fn new() -> AggregateResolver {
+        //! An empty resolver with no aggregators registered. See
+        //! [`AggregateResolver::default`] for one with the in-built ones.
+
+        AggregateResolver {
+            registry: IndexMap::new(),
+        }
+    }
+
+    pub fn is_registered(&self, name: &String) -> bool {
+        self._exists_in_registry(name)
+    }
+
     fn _exists_in_registry(&self, name: &String) -> bool {
         self.registry
             .keys()
@@ -73,4 +128,18 @@ impl AggregateResolver {
             aggregator.run(args, rows)
         }
     }
+
+    pub fn evaluate(
+        &self,
+        name: &String,
+        args: &Vec<String>,
+        rows: &Vec<Row>,
+    ) -> Result<Option<String>, String> {
+        //! Run a particular aggregator, where the result may be NULL.
+
+        match self.registry.get(name) {
+            Some(aggregator) => aggregator.evaluate(args, rows),
+            None => Err(format!("Aggregator named {} does not exist.", name)),
+        }
+    }
 }
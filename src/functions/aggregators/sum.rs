@@ -0,0 +1,45 @@
+/// The SUM(col) aggregator.
+///
+/// Returns the total of all the non-null numeric values in the given data.
+use crate::persistence::Row;
+
+use super::resolver::Aggregate;
+
+pub(super) const AGGR_NAME: &str = "SUM";
+
+pub(super) fn parse_number(value: &str) -> Result<i64, String> {
+    value
+        .parse::<i64>()
+        .map_err(|_| format!("{} takes in numeric values only, got {}.", AGGR_NAME, value))
+}
+
+pub(super) fn add(total: i64, value: &str) -> Result<i64, String> {
+    //! Add the numeric `value` to the running `total`, failing on overflow.
+
+    total
+        .checked_add(parse_number(value)?)
+        .ok_or_else(|| format!("{} overflowed the numeric limit.", AGGR_NAME))
+}
+
+pub(super) struct Sum;
+
+impl Aggregate for Sum {
+    fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
+        if args.len() != 1 || args.contains(&"*".to_string()) {
+            Err(format!("{} strictly allows a single column.", AGGR_NAME))
+        } else {
+            let col_index = args[0]
+                .parse::<usize>()
+                .map_err(|_| format!("{} strictly allows a single column.", AGGR_NAME))?;
+            let mut total: i64 = 0;
+
+            for row in rows.iter() {
+                if let Some(value) = row.get_str(col_index) {
+                    total = add(total, &value)?;
+                }
+            }
+
+            Ok(total.to_string())
+        }
+    }
+}
@@ -14,5 +14,5 @@
 /// This is a test layout. I have no idea how to go well about this at
 /// the moment :)
 
-pub(crate) mod scalars;
-pub(crate) mod aggregators;
\ No newline at end of file
+pub mod scalars;
+pub mod aggregators;
\ No newline at end of file
@@ -0,0 +1,23 @@
+/// The ABS(col) scalar.
+///
+/// Gives the absolute numeric value of a column.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "ABS";
+
+pub(super) struct Abs;
+
+impl Scalar for Abs {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        if args.len() != 1 {
+            return Err(format!("{} strictly allows a single column.", SCLR_NAME));
+        }
+
+        let value = operands::number(SCLR_NAME, args, row)?;
+
+        Ok(operands::format_number(value.abs()))
+    }
+}
@@ -1,27 +1,24 @@
-/// Find the largest value of a particular index.
+/// The ADD(col, value) scalar.
+///
+/// Adds a number to the numeric value of a column.
+///
+/// The result has as many decimal places as the operand with the most.
 use crate::persistence::Row;
 
+use super::operands;
+use super::resolver::Scalar;
+
 pub(super) const SCLR_NAME: &str = "ADD";
 
-pub(super) fn run(args: &Vec<String>, row: &Row) -> Result<String, String> {
-    let col_index = {
-        let _a = args.first().unwrap();
-        _a.parse::<usize>().expect("No index specified.")
-    };
+pub(super) struct Add;
 
-    let add_value = {
-        let _a = args.get(1).unwrap();
-        _a.parse::<usize>()
-            .expect("Strictly integer value allowed.")
-    };
+impl Scalar for Add {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let (value, operand) = operands::parse(SCLR_NAME, args, row)?;
 
-    let mut value = {
-        let _v = row.0.get(col_index).unwrap();
-        _v.clone().unwrap().parse::<usize>()
+        value
+            .checked_add(operand)
+            .map(|value| value.to_string())
+            .ok_or_else(|| format!("{} overflowed the numeric limit.", SCLR_NAME))
     }
-    .unwrap();
-
-    value += add_value;
-
-    Ok(value.to_string())
 }
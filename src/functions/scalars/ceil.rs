@@ -0,0 +1,23 @@
+/// The CEIL(col) scalar.
+///
+/// Rounds the numeric value of a column up to the nearest integer.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "CEIL";
+
+pub(super) struct Ceil;
+
+impl Scalar for Ceil {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        if args.len() != 1 {
+            return Err(format!("{} strictly allows a single column.", SCLR_NAME));
+        }
+
+        let value = operands::number(SCLR_NAME, args, row)?;
+
+        Ok(operands::format_number(value.ceil()))
+    }
+}
@@ -0,0 +1,33 @@
+/// The COALESCE(arg, ...) scalar.
+///
+/// Gives the value of the first argument, column or literal, that is not
+/// NULL. If all of them are NULL, so is the result.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "COALESCE";
+
+pub(super) struct Coalesce;
+
+impl Scalar for Coalesce {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        self.evaluate(args, row)?
+            .ok_or_else(|| format!("{} found only NULL values.", SCLR_NAME))
+    }
+
+    fn evaluate(&self, args: &Vec<String>, row: &Row) -> Result<Option<String>, String> {
+        if args.is_empty() {
+            return Err(format!("{} takes in at least one argument.", SCLR_NAME));
+        }
+
+        for arg in args.iter() {
+            if let Some(value) = operands::value(SCLR_NAME, arg, row)? {
+                return Ok(Some(value.to_string()));
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,30 @@
+/// The CONCAT(arg, ...) scalar.
+///
+/// Joins the values of any number of columns and literals, in order. NULL
+/// values are skipped.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "CONCAT";
+
+pub(super) struct Concat;
+
+impl Scalar for Concat {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        if args.is_empty() {
+            return Err(format!("{} takes in at least one argument.", SCLR_NAME));
+        }
+
+        let mut result = String::new();
+
+        for arg in args.iter() {
+            if let Some(value) = operands::value(SCLR_NAME, arg, row)? {
+                result.push_str(&value);
+            }
+        }
+
+        Ok(result)
+    }
+}
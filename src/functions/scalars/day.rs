@@ -0,0 +1,21 @@
+/// The DAY(col) scalar.
+///
+/// Gives the day of the month of the date value of a column.
+use chrono::Datelike;
+
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "DAY";
+
+pub(super) struct Day;
+
+impl Scalar for Day {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let date = operands::date(SCLR_NAME, args, row)?;
+
+        Ok(date.day().to_string())
+    }
+}
@@ -0,0 +1,23 @@
+/// The FLOOR(col) scalar.
+///
+/// Rounds the numeric value of a column down to the nearest integer.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "FLOOR";
+
+pub(super) struct Floor;
+
+impl Scalar for Floor {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        if args.len() != 1 {
+            return Err(format!("{} strictly allows a single column.", SCLR_NAME));
+        }
+
+        let value = operands::number(SCLR_NAME, args, row)?;
+
+        Ok(operands::format_number(value.floor()))
+    }
+}
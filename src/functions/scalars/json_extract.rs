@@ -0,0 +1,97 @@
+/// The JSON_EXTRACT(col, path) scalar.
+///
+/// Gives the value at the `path` of the JSON document in a column, where
+/// the path starts at the root `$` and goes down through `.key` fields and
+/// `[index]` array items, e.g. `'$.address.cities[0]'`. Texts are given
+/// without their quotes, objects and arrays in their compact JSON form. A
+/// path that leads nowhere, or to a JSON `null`, gives NULL.
+use serde_json::Value;
+
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "JSON_EXTRACT";
+
+pub(super) struct JsonExtract;
+
+/// A single step of a JSON path.
+enum PathStep<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> Result<Vec<PathStep<'_>>, String> {
+    //! Split a `$.key[index]` path into its steps.
+
+    let invalid = || format!("{} got an invalid path {}.", SCLR_NAME, path);
+
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut steps = vec![];
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            steps.push(PathStep::Key(&after[..end]));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let index = after[..end].parse::<usize>().map_err(|_| invalid())?;
+            steps.push(PathStep::Index(index));
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(steps)
+}
+
+impl Scalar for JsonExtract {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        self.evaluate(args, row)?
+            .ok_or_else(|| format!("{} found nothing at the path.", SCLR_NAME))
+    }
+
+    fn evaluate(&self, args: &Vec<String>, row: &Row) -> Result<Option<String>, String> {
+        let [column, path] = args.as_slice() else {
+            return Err(format!("{} takes in a column and a path.", SCLR_NAME));
+        };
+
+        let Some(document) = operands::value(SCLR_NAME, column, row)? else {
+            return Ok(None);
+        };
+        let path = operands::value(SCLR_NAME, path, row)?
+            .ok_or_else(|| format!("{} cannot operate on a NULL path.", SCLR_NAME))?;
+        let steps = parse_path(&path)?;
+
+        let document: Value = serde_json::from_str(&document).map_err(|_| {
+            format!(
+                "{} strictly allows JSON values, got {}.",
+                SCLR_NAME, document
+            )
+        })?;
+
+        let mut value = &document;
+        for step in steps {
+            let next = match step {
+                PathStep::Key(key) => value.get(key),
+                PathStep::Index(index) => value.get(index),
+            };
+            match next {
+                Some(next) => value = next,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(match value {
+            Value::Null => None,
+            Value::String(text) => Some(text.clone()),
+            value => Some(value.to_string()),
+        })
+    }
+}
@@ -0,0 +1,19 @@
+/// The LENGTH(col) scalar.
+///
+/// Gives the number of characters in the value of a column.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "LENGTH";
+
+pub(super) struct Length;
+
+impl Scalar for Length {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let value = operands::single_cell(SCLR_NAME, args, row)?;
+
+        Ok(value.chars().count().to_string())
+    }
+}
@@ -0,0 +1,19 @@
+/// The LOWER(col) scalar.
+///
+/// Converts the text value of a column to lower case.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "LOWER";
+
+pub(super) struct Lower;
+
+impl Scalar for Lower {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let value = operands::single_cell(SCLR_NAME, args, row)?;
+
+        Ok(value.to_lowercase())
+    }
+}
@@ -1,23 +1,73 @@
+use std::sync::LazyLock;
+
 use crate::persistence::Row;
 
+mod abs;
 mod add;
+mod ceil;
+mod coalesce;
+mod concat;
+mod day;
+mod div;
+mod floor;
+mod json_extract;
+mod length;
+mod lower;
+mod modulo;
+mod month;
+mod mul;
+mod now;
+mod operands;
+mod resolver;
+mod round;
+mod sub;
+mod trim;
+mod upper;
+mod uuid;
+mod year;
+
+pub use now::TIMESTAMP_FORMAT;
+pub use resolver::{Scalar, ScalarResolver};
 
-const ALLOWED_SCALARS: [&str; 1] = [add::SCLR_NAME];
+/// The resolver holding all the in-built scalars, built once on first use.
+static DEFAULT_RESOLVER: LazyLock<ScalarResolver> = LazyLock::new(ScalarResolver::default);
 
 /// A central method that works as a registry for all scalars.
 ///
-/// To add one, simply add another match arm.
+/// To add one, register it inside [`ScalarResolver::default`].
 ///
-/// Get the pointer to the function, to call it later.
-pub fn get_runner(
-    name: &String,
-) -> Result<fn(&Vec<String>, &Row) -> Result<String, String>, String> {
-    match name.to_uppercase().as_str() {
-        add::SCLR_NAME => Ok(add::run),
-        _ => Err(format!("Unknown scalar function: {}", name)),
-    }
+/// Get the handle to the scalar, to call it later.
+pub fn get_runner(name: &String) -> Result<&'static dyn Scalar, String> {
+    DEFAULT_RESOLVER
+        .get(&name.to_uppercase())
+        .ok_or_else(|| format!("Unknown scalar function: {}", name))
+}
+
+pub fn run(name: &String, args: &Vec<String>, row: &Row) -> Result<String, String> {
+    get_runner(name)?.run(args, row)
+}
+
+pub fn evaluate(name: &String, args: &Vec<String>, row: &Row) -> Result<Option<String>, String> {
+    get_runner(name)?.evaluate(args, row)
+}
+
+pub fn literal_argument(value: &str) -> String {
+    //! Encode a literal value as a scalar argument.
+    //!
+    //! Scalars get a column argument as the bare index of the column, and a
+    //! literal one wrapped in single quotes, so the two are told apart.
+
+    format!("'{}'", value)
+}
+
+pub fn is_volatile(name: &str) -> bool {
+    //! Whether `name` is a volatile scalar, see [`Scalar::is_volatile`].
+
+    DEFAULT_RESOLVER
+        .get(&name.to_uppercase())
+        .is_some_and(|scalar| scalar.is_volatile())
 }
 
 pub fn is_allowed(name: &String) -> bool {
-    ALLOWED_SCALARS.contains(&name.as_str())
+    DEFAULT_RESOLVER.is_registered(&name.to_uppercase())
 }
@@ -0,0 +1,30 @@
+/// The MOD(col, value) scalar.
+///
+/// Gives the remainder of dividing the numeric value of a column by a number.
+///
+/// The result has as many decimal places as the operand with the most.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "MOD";
+
+pub(super) struct Mod;
+
+impl Scalar for Mod {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let (value, operand) = operands::parse(SCLR_NAME, args, row)?;
+
+        value
+            .checked_rem(operand)
+            .map(|value| value.to_string())
+            .ok_or_else(|| {
+                if operand.is_zero() {
+                    format!("{} cannot divide by zero.", SCLR_NAME)
+                } else {
+                    format!("{} overflowed the numeric limit.", SCLR_NAME)
+                }
+            })
+    }
+}
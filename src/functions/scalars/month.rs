@@ -0,0 +1,21 @@
+/// The MONTH(col) scalar.
+///
+/// Gives the month, from 1 to 12, of the date value of a column.
+use chrono::Datelike;
+
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "MONTH";
+
+pub(super) struct Month;
+
+impl Scalar for Month {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let date = operands::date(SCLR_NAME, args, row)?;
+
+        Ok(date.month().to_string())
+    }
+}
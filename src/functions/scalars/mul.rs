@@ -0,0 +1,24 @@
+/// The MUL(col, value) scalar.
+///
+/// Multiplies the numeric value of a column by a number.
+///
+/// The result has as many decimal places as the operand with the most.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "MUL";
+
+pub(super) struct Mul;
+
+impl Scalar for Mul {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let (value, operand) = operands::parse(SCLR_NAME, args, row)?;
+
+        value
+            .checked_mul(operand)
+            .map(|value| value.to_string())
+            .ok_or_else(|| format!("{} overflowed the numeric limit.", SCLR_NAME))
+    }
+}
@@ -0,0 +1,30 @@
+/// The NOW() scalar.
+///
+/// Gives the current local time as a timestamp, the same for every row
+/// it is called on in the same second.
+use chrono::Local;
+
+use crate::persistence::Row;
+
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "NOW";
+
+/// The format of the timestamps given by [`Now`].
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub(super) struct Now;
+
+impl Scalar for Now {
+    fn run(&self, args: &Vec<String>, _row: &Row) -> Result<String, String> {
+        if !args.is_empty() {
+            return Err(format!("{} does not take in any arguments.", SCLR_NAME));
+        }
+
+        Ok(Local::now().format(TIMESTAMP_FORMAT).to_string())
+    }
+
+    fn is_volatile(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,133 @@
+/// Shared argument handling for the scalars.
+///
+/// A column argument is already resolved to its index while a literal is
+/// wrapped in single quotes, see [`super::literal_argument`]. The first
+/// argument of the fixed scalars is always a column. The arithmetic scalars
+/// take in a `(col, value)` pair, where the value is a number literal or
+/// another numeric column. They work on [`Decimal`]s, so decimal places are
+/// kept, while whole numbers behave like plain integers.
+use std::borrow::Cow;
+
+use chrono::NaiveDate;
+
+use crate::persistence::{DATE_FORMAT, Decimal, Row};
+
+/// A single decoded scalar argument.
+pub(super) enum Argument<'a> {
+    Column(usize),
+    Literal(&'a str),
+}
+
+pub(super) fn argument<'a>(name: &str, arg: &'a str) -> Result<Argument<'a>, String> {
+    //! Decode a single argument of the scalar `name`.
+
+    if arg.len() >= 2 && arg.starts_with('\'') && arg.ends_with('\'') {
+        Ok(Argument::Literal(&arg[1..arg.len() - 1]))
+    } else {
+        arg.parse::<usize>()
+            .map(Argument::Column)
+            .map_err(|_| format!("{} got an invalid argument {}.", name, arg))
+    }
+}
+
+pub(super) fn value<'a>(
+    name: &str,
+    arg: &'a str,
+    row: &'a Row,
+) -> Result<Option<Cow<'a, str>>, String> {
+    //! Get the value of a single argument, where a column gives its cell in
+    //! the `row`, which may be NULL.
+
+    match argument(name, arg)? {
+        Argument::Column(col_index) if col_index < row.0.len() => Ok(row.get_str(col_index)),
+        Argument::Column(col_index) => Err(format!(
+            "{} got an invalid column index {}.",
+            name, col_index
+        )),
+        Argument::Literal(literal) => Ok(Some(Cow::Borrowed(literal))),
+    }
+}
+
+pub(super) fn parse(name: &str, args: &[String], row: &Row) -> Result<(Decimal, Decimal), String> {
+    //! Get the numeric cell value and the number value argument for the
+    //! scalar `name`, or an error describing why they are not usable.
+
+    let cell = cell(name, args, row)?;
+
+    let value = {
+        let _a = args
+            .get(1)
+            .ok_or_else(|| format!("{} takes in a column and a value.", name))?;
+        let _v = value(name, _a, row)?
+            .ok_or_else(|| format!("{} cannot operate on a NULL value.", name))?;
+        Decimal::parse(&_v)
+            .ok_or_else(|| format!("{} strictly allows numeric values, got {}.", name, _v))?
+    };
+
+    let cell = Decimal::parse(&cell)
+        .ok_or_else(|| format!("{} strictly allows numeric columns, got {}.", name, cell))?;
+
+    Ok((cell, value))
+}
+
+pub(super) fn cell<'a>(name: &str, args: &[String], row: &'a Row) -> Result<Cow<'a, str>, String> {
+    //! Get the non-null cell of the column in the first argument of the
+    //! scalar `name`.
+
+    let col_index = args
+        .first()
+        .ok_or_else(|| format!("{} takes in a column as its first argument.", name))?
+        .parse::<usize>()
+        .map_err(|_| format!("{} takes in a column as its first argument.", name))?;
+
+    if col_index >= row.0.len() {
+        return Err(format!(
+            "{} got an invalid column index {}.",
+            name, col_index
+        ));
+    }
+
+    row.get_str(col_index)
+        .ok_or_else(|| format!("{} cannot operate on a NULL value.", name))
+}
+
+pub(super) fn single_cell<'a>(
+    name: &str,
+    args: &[String],
+    row: &'a Row,
+) -> Result<Cow<'a, str>, String> {
+    //! Same as [`cell`], for scalars that take in nothing but the column.
+
+    if args.len() != 1 {
+        return Err(format!("{} strictly allows a single column.", name));
+    }
+
+    cell(name, args, row)
+}
+
+pub(super) fn number(name: &str, args: &[String], row: &Row) -> Result<f64, String> {
+    //! Get the cell of the column in the first argument of the scalar `name`
+    //! as a number.
+
+    let cell = cell(name, args, row)?;
+
+    cell.parse::<f64>()
+        .ok()
+        .filter(|cell| cell.is_finite())
+        .ok_or_else(|| format!("{} strictly allows numeric columns, got {}.", name, cell))
+}
+
+pub(super) fn format_number(value: f64) -> String {
+    //! Display a computed number, without a negative sign on zero.
+
+    (value + 0.0).to_string()
+}
+
+pub(super) fn date(name: &str, args: &[String], row: &Row) -> Result<NaiveDate, String> {
+    //! Get the cell of the only argument of the scalar `name` as a date.
+
+    let cell = single_cell(name, args, row)?;
+
+    NaiveDate::parse_from_str(&cell, DATE_FORMAT)
+        .map_err(|_| format!("{} strictly allows date values, got {}.", name, cell))
+}
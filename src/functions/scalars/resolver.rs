@@ -1,6 +1,4 @@
-//! [ALTERNATIVE APPROACH - IF THE FUNCTIONS BECOME TOO MANY TO MANAGE]
-//! 
-//! This module contains all the available scalars that can be applied
+//! This module contains the registry for all the available scalars that can be applied
 //! on to any value in the cell of a [`Table`] struct via the of course,
 //! the [`TableReader`] object.
 //!
@@ -11,19 +9,140 @@
 //!
 //! ScalarResolver()
 //! --> ScalarRegistry(IndexMap<String, Box<dyn Scalar>>)
-//!     --> Scalar :: run(&Row)
-//! 
+//!     --> Scalar :: run(Vec<String>, &Row)
+//!
 //! A scalar is to be invoked by a high level resolver, which is a safety
 //! net that checks whether the scalar being called exists or not. All
 //! scalars are expected to return a [`String`] value once they complete.
+use indexmap::IndexMap;
+
 use crate::persistence::Row;
 
+use super::{
+    abs, add, ceil, coalesce, concat, day, div, floor, json_extract, length, lower, modulo, month,
+    mul, now, round, sub, trim, upper, uuid, year,
+};
+
 /// The persistence engine relies on all scalars to implement
 /// this trait for it to run the scalar on a row.
 ///
-/// The [`Scalar::run`] method takes in a single [`Row`] as read-ony
-/// and returns a [`String`] value if the result can be processed, or
-/// [`None`] otherwise, denoting the value could not be computed.
-pub(crate) trait Scalar {
-    fn run(row: &Row) -> Option<String>;
-}
\ No newline at end of file
+/// The [`Scalar::run`] method takes in the arguments of the call, where
+/// column arguments are already resolved to their index, and a single
+/// [`Row`] as read-only. It returns the computed [`String`] value, or
+/// an error describing why the value could not be computed.
+///
+/// Scalars are shared between sessions, so they must be [`Send`] and [`Sync`].
+pub trait Scalar: Send + Sync {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String>;
+
+    fn evaluate(&self, args: &Vec<String>, row: &Row) -> Result<Option<String>, String> {
+        //! Run the scalar where the result is allowed to be NULL, as [`None`].
+        //!
+        //! The table reader always evaluates scalars through this method. Only the
+        //! scalars that can give a NULL need to override it, by default it is just
+        //! the value of [`Scalar::run`].
+
+        self.run(args, row).map(Some)
+    }
+
+    fn is_volatile(&self) -> bool {
+        //! Whether the scalar can give a different value every time it is called
+        //! with the same arguments, like a clock. Results of queries calling a
+        //! volatile scalar are never cached.
+
+        false
+    }
+}
+
+pub struct ScalarResolver {
+    registry: IndexMap<String, Box<dyn Scalar>>,
+}
+
+impl Default for ScalarResolver {
+    fn default() -> Self {
+        //! A resolver with all the in-built scalars registered.
+
+        let mut resolver = ScalarResolver::new();
+
+        resolver.register(add::SCLR_NAME.to_string(), Box::new(add::Add));
+        resolver.register(sub::SCLR_NAME.to_string(), Box::new(sub::Sub));
+        resolver.register(mul::SCLR_NAME.to_string(), Box::new(mul::Mul));
+        resolver.register(div::SCLR_NAME.to_string(), Box::new(div::Div));
+        resolver.register(modulo::SCLR_NAME.to_string(), Box::new(modulo::Mod));
+        resolver.register(upper::SCLR_NAME.to_string(), Box::new(upper::Upper));
+        resolver.register(lower::SCLR_NAME.to_string(), Box::new(lower::Lower));
+        resolver.register(length::SCLR_NAME.to_string(), Box::new(length::Length));
+        resolver.register(trim::SCLR_NAME.to_string(), Box::new(trim::Trim));
+        resolver.register(concat::SCLR_NAME.to_string(), Box::new(concat::Concat));
+        resolver.register(
+            coalesce::SCLR_NAME.to_string(),
+            Box::new(coalesce::Coalesce),
+        );
+        resolver.register(round::SCLR_NAME.to_string(), Box::new(round::Round));
+        resolver.register(ceil::SCLR_NAME.to_string(), Box::new(ceil::Ceil));
+        resolver.register(floor::SCLR_NAME.to_string(), Box::new(floor::Floor));
+        resolver.register(abs::SCLR_NAME.to_string(), Box::new(abs::Abs));
+        resolver.register(now::SCLR_NAME.to_string(), Box::new(now::Now));
+        resolver.register(year::SCLR_NAME.to_string(), Box::new(year::Year));
+        resolver.register(month::SCLR_NAME.to_string(), Box::new(month::Month));
+        resolver.register(day::SCLR_NAME.to_string(), Box::new(day::Day));
+        resolver.register(uuid::SCLR_NAME.to_string(), Box::new(uuid::NewUuid));
+        resolver.register(
+            json_extract::SCLR_NAME.to_string(),
+            Box::new(json_extract::JsonExtract),
+        );
+
+        resolver
+    }
+}
+
+impl ScalarResolver {
+    pub fn new() -> ScalarResolver {
+        //! An empty resolver with no scalars registered. See
+        //! [`ScalarResolver::default`] for one with the in-built ones.
+
+        ScalarResolver {
+            registry: IndexMap::new(),
+        }
+    }
+
+    pub fn is_registered(&self, name: &String) -> bool {
+        self.registry.contains_key(name)
+    }
+
+    pub fn register(&mut self, name: String, scalar: Box<dyn Scalar>) {
+        self.registry.insert(name, scalar);
+    }
+
+    pub fn get(&self, name: &String) -> Option<&dyn Scalar> {
+        //! Get a handle to a particular scalar, to run it over many rows.
+
+        self.registry.get(name).map(|scalar| scalar.as_ref())
+    }
+
+    pub fn run(&self, name: &String, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        //! Run a particular scalar.
+        //!
+        //! Takes the name of the scalar and the arguments, alongwith
+        //! a read-only reference to the row.
+
+        match self.get(name) {
+            Some(scalar) => scalar.run(args, row),
+            None => Err(format!("Scalar named {} does not exist.", name)),
+        }
+    }
+
+    pub fn evaluate(
+        &self,
+        name: &String,
+        args: &Vec<String>,
+        row: &Row,
+    ) -> Result<Option<String>, String> {
+        //! Same as [`ScalarResolver::run`], but allows the result to be NULL.
+
+        match self.get(name) {
+            Some(scalar) => scalar.evaluate(args, row),
+            None => Err(format!("Scalar named {} does not exist.", name)),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+/// The ROUND(col, [digits]) scalar.
+///
+/// Rounds the numeric value of a column to the given number of decimal
+/// digits, or to the nearest integer when no digits are given. Halves are
+/// rounded away from zero.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "ROUND";
+
+pub(super) struct Round;
+
+impl Scalar for Round {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(format!(
+                "{} takes in a column and an optional precision.",
+                SCLR_NAME
+            ));
+        }
+
+        let value = operands::number(SCLR_NAME, args, row)?;
+
+        let digits = match args.get(1) {
+            Some(arg) => {
+                let _d = operands::value(SCLR_NAME, arg, row)?
+                    .ok_or_else(|| format!("{} cannot round to a NULL precision.", SCLR_NAME))?;
+                _d.parse::<u32>()
+                    .ok()
+                    .filter(|digits| *digits <= 15)
+                    .ok_or_else(|| {
+                        format!(
+                            "{} allows a precision between 0 and 15, got {}.",
+                            SCLR_NAME, _d
+                        )
+                    })?
+            }
+            None => 0,
+        };
+
+        let scale = 10f64.powi(digits as i32);
+        let rounded = (value * scale).round() / scale;
+
+        Ok(format!("{:.*}", digits as usize, rounded + 0.0))
+    }
+}
@@ -0,0 +1,24 @@
+/// The SUB(col, value) scalar.
+///
+/// Subtracts a number from the numeric value of a column.
+///
+/// The result has as many decimal places as the operand with the most.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "SUB";
+
+pub(super) struct Sub;
+
+impl Scalar for Sub {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let (value, operand) = operands::parse(SCLR_NAME, args, row)?;
+
+        value
+            .checked_sub(operand)
+            .map(|value| value.to_string())
+            .ok_or_else(|| format!("{} overflowed the numeric limit.", SCLR_NAME))
+    }
+}
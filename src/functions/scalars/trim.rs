@@ -0,0 +1,19 @@
+/// The TRIM(col) scalar.
+///
+/// Removes the leading and trailing whitespace from the value of a column.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "TRIM";
+
+pub(super) struct Trim;
+
+impl Scalar for Trim {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let value = operands::single_cell(SCLR_NAME, args, row)?;
+
+        Ok(value.trim().to_string())
+    }
+}
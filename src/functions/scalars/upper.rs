@@ -0,0 +1,19 @@
+/// The UPPER(col) scalar.
+///
+/// Converts the text value of a column to upper case.
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "UPPER";
+
+pub(super) struct Upper;
+
+impl Scalar for Upper {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let value = operands::single_cell(SCLR_NAME, args, row)?;
+
+        Ok(value.to_uppercase())
+    }
+}
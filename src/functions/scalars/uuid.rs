@@ -0,0 +1,27 @@
+/// The UUID() scalar.
+///
+/// Gives a new random (version 4) UUID in its canonical form, a different
+/// one for every row it is called on.
+use uuid::Uuid;
+
+use crate::persistence::Row;
+
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "UUID";
+
+pub(super) struct NewUuid;
+
+impl Scalar for NewUuid {
+    fn run(&self, args: &Vec<String>, _row: &Row) -> Result<String, String> {
+        if !args.is_empty() {
+            return Err(format!("{} does not take in any arguments.", SCLR_NAME));
+        }
+
+        Ok(Uuid::new_v4().to_string())
+    }
+
+    fn is_volatile(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,21 @@
+/// The YEAR(col) scalar.
+///
+/// Gives the year of the date value of a column.
+use chrono::Datelike;
+
+use crate::persistence::Row;
+
+use super::operands;
+use super::resolver::Scalar;
+
+pub(super) const SCLR_NAME: &str = "YEAR";
+
+pub(super) struct Year;
+
+impl Scalar for Year {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let date = operands::date(SCLR_NAME, args, row)?;
+
+        Ok(date.year().to_string())
+    }
+}
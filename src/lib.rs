@@ -2,6 +2,7 @@ pub mod cli;
 pub mod persistence;
 pub mod sessions;
 pub mod config;
+pub mod errors;
 
-mod functions;
-mod serialization;
\ No newline at end of file
+pub mod functions;
+pub mod serialization;
\ No newline at end of file
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use clap::Parser;
 use ferrum_engine::{
     cli::{
@@ -11,14 +13,47 @@ fn main() {
     let args = CliParser::parse();
     // let args: Vec<String> = env::args().collect();
 
-    let config = EngineConfig::new();
+    let mut config = EngineConfig::new();
+    if args.max_connections.is_some() {
+        config.max_connections = args.max_connections;
+    }
+    if let Some(secs) = args.queue_timeout {
+        config.connection_queue_timeout = Some(Duration::from_secs(secs));
+    }
 
     let mode = args
         .mode
         .expect("usage: please specify a mode: client/server");
 
     match mode {
-        CliMode::Client => cli::run_client(&config),
+        CliMode::Client if args.file.is_some() => {
+            let succeeded = cli::run_script(
+                &config,
+                args.file.as_deref().unwrap(),
+                args.dry_run,
+                args.format,
+                args.timing,
+                args.data_dir.as_deref(),
+                args.quiet,
+            );
+            if !succeeded {
+                std::process::exit(1);
+            }
+        }
+        CliMode::Client if args.create_user => {
+            if !cli::create_user(&config, args.data_dir.as_deref()) {
+                std::process::exit(1);
+            }
+        }
+        CliMode::Client => cli::run_client(
+            &config,
+            args.format,
+            args.timing,
+            args.auth,
+            args.log.as_deref(),
+            args.data_dir.as_deref(),
+            args.quiet,
+        ),
         CliMode::Server => cli::run_server(),
     }
 }
@@ -0,0 +1,31 @@
+//! Settings of a database that apply to every table created in it, see
+//! [`Database::with_config`].
+//!
+//! [`Database::with_config`]: super::Database::with_config
+
+use serde::{Deserialize, Serialize};
+
+/// The length of a `txt` column, when neither the column nor its database
+/// define one.
+pub const DEFAULT_TEXT_LIMIT: usize = 50;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseConfig {
+    /// The most characters a `txt` column without a length of its own takes,
+    /// a `txt(n)` column always keeps its `n`.
+    pub default_text_limit: usize,
+}
+
+impl DatabaseConfig {
+    pub fn new() -> DatabaseConfig {
+        DatabaseConfig {
+            default_text_limit: DEFAULT_TEXT_LIMIT,
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
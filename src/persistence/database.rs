@@ -1,14 +1,25 @@
 use std::{
     collections::HashMap,
+    path::Path,
     sync::{Arc, RwLock},
 };
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-use crate::persistence::{Row, index::ForeignKeyConstraint, table::TableData};
+use crate::persistence::{
+    DataType, Row, TableReader,
+    index::ForeignKeyConstraint,
+    table::{TableData, TableSnapshot},
+};
 
-use super::table::Table;
+use super::config::DatabaseConfig;
+use super::diff::{SchemaDiff, diff_tables};
+use super::loader::{LoadOptions, LoadProgress, OnError};
+use super::table::{_empty_as_null, Table};
+use crate::cli::closest_name;
+use crate::errors::EngineError;
+use crate::serialization::csv;
 
 /// The collective of multiple [`Table`] objects.
 ///
@@ -35,6 +46,7 @@ use super::table::Table;
 pub struct Database {
     name: String,
     tables: HashMap<String, Arc<RwLock<Table>>>,
+    config: DatabaseConfig,
 }
 
 /// A serializable interface for the [`Database`] struct.
@@ -44,6 +56,19 @@ pub struct Database {
 pub struct DatabaseData {
     name: String,
     tables: HashMap<String, TableData>,
+    #[serde(default)]
+    config: DatabaseConfig,
+}
+
+/// A copy of every table of a [`Database`] at some point in time.
+///
+/// Taken with [`Database::snapshot`] and put back with [`Database::restore`],
+/// which brings back the rows, indexes and schemas of the tables as well as
+/// the set of tables itself. Restoring keeps the original table handles, so
+/// anyone still holding one sees the restored data.
+pub struct DatabaseSnapshot {
+    tables: HashMap<String, Arc<RwLock<Table>>>,
+    data: HashMap<String, TableSnapshot>,
 }
 
 /// A single place to store all databases.
@@ -76,22 +101,33 @@ impl Database {
     fn _validate_foreign_key_constraint(
         &self,
         constraint: &ForeignKeyConstraint,
+        new_table: &Table,
     ) -> Result<usize, String> {
+        //! Find the column a foreign key refers to, in an existing table or in
+        //! the `new_table` being created, for a self-referencing key.
+        //!
+        //! Returns the position of the referred column in its table.
+
         let ForeignKeyConstraint {
             table_name,
             column_name,
             ..
         } = constraint;
 
+        if *table_name == new_table.name() {
+            let new_table_schema = new_table.schema.read().unwrap();
+
+            return new_table_schema.column_index(column_name).ok_or(format!(
+                "invalid foreign key on {}; column {} doesn't exist ",
+                table_name, column_name
+            ));
+        }
+
         if let Some(table) = self.tables.get(table_name) {
             let table_ro = table.read().unwrap();
             let table_ro_schema = table_ro.schema.read().unwrap();
 
-            if let Some(index) = table_ro_schema
-                .get_vec()
-                .iter()
-                .position(|(col, _)| col == column_name)
-            {
+            if let Some(index) = table_ro_schema.column_index(column_name) {
                 Ok(index)
             } else {
                 Err(format!(
@@ -101,32 +137,115 @@ impl Database {
             }
         } else {
             Err(format!(
-                "invalid foreign key on {}; column {} doesn't exist ",
-                table_name, column_name
+                "invalid foreign key on {}; table doesn't exist",
+                table_name
             ))
         }
     }
 
-    fn _validate_foreign_key(&self, table_name: &str, value: &String) -> Result<bool, String> {
+    fn _referencing_tables(&self, table_name: &str) -> Vec<String> {
+        //! Get the names of all the other tables with a foreign key on `table_name`.
+
+        let mut referencing_tables: Vec<String> = self
+            .tables
+            .iter()
+            .filter(|(name, _)| name.as_str() != table_name)
+            .filter(|(_, table)| {
+                let table = table.read().unwrap();
+                let schema = table.schema.read().unwrap();
+                schema
+                    .get_foreign_key_constraints()
+                    .iter()
+                    .any(|(_, constraint)| constraint.table_name == table_name)
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        referencing_tables.sort();
+        referencing_tables
+    }
+
+    fn _validate_foreign_key(
+        &self,
+        constraint: &ForeignKeyConstraint,
+        value: &str,
+    ) -> Result<bool, String> {
         //! Validate the given key exists in the target table according to the defined foreign key
         //! relationship.
+        //!
+        //! Uses the parent handle and column position the constraint was resolved to, and
+        //! falls back to finding them by name for constraints that are not resolved.
+
+        let parent = match constraint.parent() {
+            Some(parent) => parent,
+            None => self
+                .tables
+                .get(&constraint.table_name)
+                .cloned()
+                .ok_or_else(|| format!("err: does not exist: table '{}'", constraint.table_name))?,
+        };
+        let parent = parent.read().unwrap();
 
-        let fk_table_ro = self
-            .tables
-            .get(table_name)
-            .ok_or_else(|| format!("err: does not exist: table '{}'", table_name))?;
-        Ok(fk_table_ro.read().unwrap().pk_exists(value))
+        let col_index = match constraint.column_index() {
+            Some(col_index) => col_index,
+            None => parent
+                .schema
+                .read()
+                .unwrap()
+                .column_index(&constraint.column_name)
+                .ok_or_else(|| {
+                    format!(
+                        "err: does not exist: column '{}.{}'",
+                        constraint.table_name, constraint.column_name
+                    )
+                })?,
+        };
+
+        Ok(parent.contains_value(col_index, value))
+    }
+
+    fn _link_foreign_keys(&self, table: &Arc<RwLock<Table>>) {
+        //! Hand every foreign key of the `table` the handle of its parent table.
+
+        let constraints = table
+            .read()
+            .unwrap()
+            .schema
+            .read()
+            .unwrap()
+            .get_foreign_key_constraints();
+
+        for (column_index, constraint) in constraints {
+            if let Some(parent) = self.tables.get(&constraint.table_name) {
+                table
+                    .write()
+                    .unwrap()
+                    .update_foreign_key_parent(column_index, parent);
+            }
+        }
     }
 
     pub fn new(name: String) -> Database {
         //! Create a new database with no tables.
 
+        Self::with_config(name, DatabaseConfig::new())
+    }
+
+    pub fn with_config(name: String, config: DatabaseConfig) -> Database {
+        //! Create a new database with no tables, whose tables are created with
+        //! the settings of the `config`.
+
         Database {
             name,
             tables: HashMap::new(),
+            config,
         }
     }
 
+    pub fn config(&self) -> &DatabaseConfig {
+        &self.config
+    }
+
     pub fn name(&self) -> String {
         self.name.clone()
     }
@@ -138,35 +257,132 @@ impl Database {
     ) -> Result<(), String> {
         //! Create a [`super::table::Table`] and store inside the database's hash map
         //! for quick retrieval and relationship management.
+        //!
+        //! Fails, without creating the table, if a foreign key refers to a table
+        //! or column that does not exist.
+
+        self.create_table_with_checks(name, column_definitions, vec![])
+    }
+
+    pub fn create_table_with_checks(
+        &mut self,
+        name: String,
+        column_definitions: Vec<String>,
+        checks: Vec<(String, String)>,
+    ) -> Result<(), String> {
+        //! Like [`Database::create_table`], with the CHECK constraints of the table
+        //! as `(column, expression)` pairs, see [`Table::add_check`].
+        //!
+        //! Fails, without creating the table, if a check does not compile.
 
-        let mut table = Table::new(name, column_definitions)?;
+        let mut table = Table::with_config(name, column_definitions, &self.config)?;
+        for (column, expression) in checks {
+            table.add_check(&column, &expression)?;
+        }
         let constraints = {
             let table = table.schema.read().unwrap();
             table.get_foreign_key_constraints()
         };
 
         for (column_index, constraint) in constraints {
-            if let Ok(key_index) = self._validate_foreign_key_constraint(&constraint) {
-                table.update_foreign_key_index(column_index, key_index);
-            }
+            let key_index = self._validate_foreign_key_constraint(&constraint, &table)?;
+            table.update_foreign_key_index(column_index, key_index);
+        }
+
+        let table = Arc::new(RwLock::new(table));
+        let name = table.read().unwrap().name();
+        self.tables.insert(name, Arc::clone(&table));
+        self._link_foreign_keys(&table);
+
+        Ok(())
+    }
+
+    pub fn create_table_from_reader(
+        &mut self,
+        name: String,
+        reader: &TableReader,
+    ) -> Result<usize, String> {
+        //! Create a table holding a copy of the rows of the `reader`, the result of
+        //! a `CREATE TABLE ... AS SELECT`.
+        //!
+        //! Every column keeps the type and nullability of the reader's column,
+        //! but no keys. The table is only added once all rows are copied, so a
+        //! failed copy leaves the database as it was.
+        //!
+        //! Returns the number of rows copied.
+
+        if self.contains_table(&name) {
+            return Err(format!("invalid table {}: already exists", name));
+        }
+
+        let column_definitions = {
+            let schema = reader.schema.read().unwrap();
+            schema
+                .get_vec()
+                .iter()
+                .map(|(col_name, col_info)| {
+                    let datatype = match (&col_info.datatype, col_info.max_limit) {
+                        (DataType::Number, _) => "num".to_string(),
+                        (DataType::Text, Some(limit)) => format!("txt({})", limit),
+                        (DataType::Text, None) => "txt".to_string(),
+                        (DataType::Date, _) => "date".to_string(),
+                        (DataType::Json, _) => "json".to_string(),
+                        (DataType::Uuid, _) => "uuid".to_string(),
+                        (DataType::Bool, _) => "bool".to_string(),
+                        (DataType::Decimal(precision, scale), _) => {
+                            format!("dec({},{})", precision, scale)
+                        }
+                        // Enum values never hold spaces or commas, see `Table::new`
+                        (DataType::Enum(values), _) => format!("enum({})", values.join(",")),
+                    };
+                    let nullable = if col_info.nullable { " null" } else { "" };
+                    format!("{} {}{}", col_name, datatype, nullable)
+                })
+                .collect()
+        };
+
+        let mut table = Table::with_config(name, column_definitions, &self.config)?;
+        let mut n_insertions = 0;
+        for row in reader.borrow_rows().iter() {
+            table.insert_values(row.cells())?;
+            n_insertions += 1;
         }
 
         self.tables
             .insert(table.name(), Arc::new(RwLock::new(table)));
 
-        Ok(())
+        Ok(n_insertions)
+    }
+
+    pub fn insert_into_table(
+        &mut self,
+        table_name: &str,
+        data: Vec<String>,
+    ) -> Result<Row, EngineError> {
+        //! Insert the `data` row into the table, where an empty value is a NULL.
+        //!
+        //! Returns a copy of the inserted row, see [`Database::insert_values_into_table`].
+
+        let data = data.iter().map(|value| _empty_as_null(value)).collect();
+        self.insert_values_into_table(table_name, data)
     }
 
-    pub fn insert_into_table(&mut self, table_name: &str, data: Vec<String>) -> Result<(), String> {
-        //! Insert the `data` row into the table.
+    pub fn insert_values_into_table(
+        &mut self,
+        table_name: &str,
+        data: Vec<Option<String>>,
+    ) -> Result<Row, EngineError> {
+        //! Insert the `data` row into the table, where [`None`] is a NULL.
+        //!
+        //! Returns a copy of the inserted row.
         //!
         //! - The function first reads through the table's schema to verify the foreign keys.
+        //!   A NULL foreign key refers to nothing, so it is not checked.
         //! - After all foreign keys have been checked, insertion takes place.
-        //!
-        //! # Issues
-        //! - How will a null value be placed in place of the foreign key?
 
-        let table = self.tables.get(table_name).unwrap();
+        let table = self.tables.get(table_name).ok_or_else(|| {
+            EngineError::TableNotFound(format!("err: does not exist: table '{}'", table_name))
+        })?;
         let constraints = {
             let table = table.read().unwrap();
             let schema = table.schema.read().unwrap();
@@ -180,37 +396,162 @@ impl Database {
             let table_name = &constraint.table_name;
             let column_name = &constraint.column_name;
 
-            if !self._validate_foreign_key(table_name, value)? {
-                return Err(format!(
+            if let Some(value) = value
+                && !self._validate_foreign_key(constraint, value)?
+            {
+                return Err(EngineError::ForeignKeyViolation(format!(
                     "err: does not exist: `{}` in `{}.{}`",
                     value, table_name, column_name
-                ));
+                )));
             }
         }
 
-        table.write().unwrap().insert(data)?;
-
-        Ok(())
+        table.write().unwrap().insert_values(data)
     }
 
     pub fn insert_many_into_table(
         &mut self,
         table_name: &str,
         rows: Vec<Vec<String>>,
+    ) -> Result<usize, EngineError> {
+        //! Bulk insert the `rows`, where an empty value is a NULL.
+        //!
+        //! Returns the number of rows inserted, see [`Database::insert_many_values_into_table`].
+
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|value| _empty_as_null(value)).collect())
+            .collect();
+        self.insert_many_values_into_table(table_name, rows)
+    }
+
+    pub fn insert_many_values_into_table(
+        &mut self,
+        table_name: &str,
+        rows: Vec<Vec<Option<String>>>,
+    ) -> Result<usize, EngineError> {
+        //! Bulk version of [`Database::insert_values_into_table`].
+        //!
+        //! All rows are checked against the schema first, see [`Table::check_values`],
+        //! so a wrong column count or type fails before any row is written.
+        //!
+        //! The insert is atomic. If a row still fails, on its keys for example,
+        //! the table is put back the way it was and the error names the row, by
+        //! its position in `rows` starting from 1.
+
+        let table = self.get_table(table_name).ok_or_else(|| {
+            EngineError::TableNotFound(format!("err: does not exist: table '{}'", table_name))
+        })?;
+        let before = {
+            let table = table.read().unwrap();
+            table.check_values(&rows)?;
+            table.snapshot()
+        };
+
+        let n_insertions = rows.len();
+
+        for (position, row) in rows.into_iter().enumerate() {
+            if let Err(error) = self.insert_values_into_table(table_name, row) {
+                table.write().unwrap().restore(before);
+                return Err(error.prefixed(&format!("invalid row {}: ", position + 1)));
+            }
+        }
+
+        Ok(n_insertions)
+    }
+
+    pub fn import_csv(
+        &mut self,
+        table_name: &str,
+        path: &Path,
+        has_header: bool,
     ) -> Result<usize, String> {
-        //! Bulk insert operation, that uses the singular row insertion function
-        //! under the hood.
+        //! Bulk insert the records of a CSV file into an existing table.
+        //!
+        //! Every record goes through [`Database::insert_into_table`], so the
+        //! schema's types, nullability and keys are honored; an empty field
+        //! is inserted as NULL. With `has_header`, the first record is skipped.
+        //!
+        //! Like the other bulk inserts, the import is not transactional. It
+        //! stops at the first failing record and reports its row number in
+        //! the file, keeping the rows inserted before it.
+        //!
+        //! Returns the total number of imported rows.
+
+        if !self.tables.contains_key(table_name) {
+            return Err(format!("err: does not exist: table {}", table_name));
+        }
 
+        let records = csv::read_file(path)?;
+        let skip = if has_header { 1 } else { 0 };
         let mut n_insertions = 0;
 
-        for row in rows {
-            self.insert_into_table(table_name, row)?;
+        for (index, record) in records.into_iter().enumerate().skip(skip) {
+            self.insert_into_table(table_name, record)
+                .map_err(|e| format!("invalid csv row {}: {}", index + 1, e))?;
             n_insertions += 1;
         }
 
         Ok(n_insertions)
     }
 
+    pub fn bulk_load<F>(
+        &mut self,
+        table_name: &str,
+        path: &Path,
+        options: &LoadOptions,
+        mut on_progress: F,
+    ) -> Result<LoadProgress, String>
+    where
+        F: FnMut(&LoadProgress),
+    {
+        //! Load the records of a CSV or TSV file into an existing table, like
+        //! [`Database::import_csv`], reporting the progress along the way.
+        //!
+        //! `on_progress` is called every [`LoadOptions::progress_every`]
+        //! records and once more when the load ends, e.g. with
+        //! `|progress| eprintln!("{}", progress)` for a periodic stderr line.
+        //!
+        //! A record that fails is reported in [`LoadProgress::errors`] with its
+        //! row number in the file. With [`OnError::Skip`] the load carries on
+        //! with the next record; with [`OnError::Abort`] it stops and fails
+        //! with that error, keeping the rows loaded before it.
+        //!
+        //! Returns the progress of the finished load.
+
+        if !self.tables.contains_key(table_name) {
+            return Err(format!("err: does not exist: table {}", table_name));
+        }
+
+        let records = csv::read_file_with(path, options.delimiter)?;
+        let skip = if options.has_header { 1 } else { 0 };
+        let mut progress = LoadProgress::default();
+
+        for (index, record) in records.into_iter().enumerate().skip(skip) {
+            progress.records += 1;
+
+            match self.insert_into_table(table_name, record) {
+                Ok(_) => progress.loaded += 1,
+                Err(e) => {
+                    let error = format!("invalid csv row {}: {}", index + 1, e);
+                    progress.errors.push(error.clone());
+
+                    if options.on_error == OnError::Abort {
+                        on_progress(&progress);
+                        return Err(error);
+                    }
+                }
+            }
+
+            if options.progress_every > 0 && progress.records % options.progress_every == 0 {
+                on_progress(&progress);
+            }
+        }
+
+        on_progress(&progress);
+        Ok(progress)
+    }
+
     // TODO: Change this to a key validator before filters are applied.
     pub fn update_table_set(
         &mut self,
@@ -228,31 +569,34 @@ impl Database {
         //! # Issues
         //! - How does cascading effect take place after a successful update?
 
-        let mut table = {
-            if let Some(_t) = self.tables.get(table_name) {
-                _t.write().unwrap()
-            } else {
-                return Err(format!("err: does not exist: table {}", table_name));
-            }
-        };
+        let table = self
+            .tables
+            .get(table_name)
+            .ok_or_else(|| format!("err: does not exist: table {}", table_name))?;
 
+        // The keys are checked before the table is locked for writing, a table
+        // referencing itself is read during the check.
         for (column_name, value) in data.iter() {
-            if let Some(constraint) = table
+            let constraint = table
+                .read()
+                .unwrap()
                 .schema
                 .read()
                 .unwrap()
-                .get_foreign_key_constraint(column_name)
+                .get_foreign_key_constraint(column_name);
+
+            if let Some(constraint) = constraint
+                && !value.is_empty()
+                && !self._validate_foreign_key(&constraint, value)?
             {
-                if !self._validate_foreign_key(&constraint.table_name, &value)? {
-                    return Err(format!(
-                        "err: does not exist: key '{}' on table '{}'",
-                        &value, &constraint.table_name
-                    ));
-                }
+                return Err(format!(
+                    "err: does not exist: key '{}' on table '{}'",
+                    &value, &constraint.table_name
+                ));
             }
         }
 
-        table.update(pk, &data)
+        table.write().unwrap().update(pk, data)
     }
 
     pub fn update_table_set_with_filters(
@@ -332,19 +676,14 @@ impl Database {
         //!
         //! Returns the total number of processed rows.
         //! This is not atomic. Rows processed before error will not be reversed post-error.
-        //!
-        //! Issues
-        //! - The way the values are currently handled could be made better but the strategy
-        //! is not obvious for now
+        //! See [`Table::delete_many`].
 
-        let mut n_deleted = 0;
-
-        for pk in pks {
-            self.delete_from_table_value(table_name, pk)?;
-            n_deleted += 1;
-        }
+        let table = self
+            .tables
+            .get(table_name)
+            .ok_or_else(|| format!("err: does not exist: table {}", table_name))?;
 
-        Ok(n_deleted)
+        table.write().unwrap().delete_many(pks)
     }
 
     pub fn delete_from_table_with_filter(
@@ -371,10 +710,22 @@ impl Database {
     }
 
     pub fn get_table(&self, table_name: &str) -> Option<Arc<RwLock<Table>>> {
+        //! Get a handle of a table, cloning its [`Arc`], so it can be held and
+        //! locked on its own after the database is released.
+        //!
+        //! Gives [`None`] if there is no table by that name.
+
         let table = self.tables.get(table_name)?;
         Some(Arc::clone(table))
     }
 
+    pub fn get_table_by_ref(&self, table_name: &str) -> Option<&Arc<RwLock<Table>>> {
+        //! Borrow the handle of a table, without cloning the [`Arc`] like
+        //! [`Database::get_table`] does.
+
+        self.tables.get(table_name)
+    }
+
     pub fn get_table_names(&self) -> Vec<String> {
         self.tables.keys().cloned().collect()
     }
@@ -411,23 +762,213 @@ impl Database {
         Ok(truncated_row_count)
     }
 
-    pub fn drop_table(&mut self, table_name: &str) -> Result<usize, String> {
+    pub fn rename_table(&mut self, old_name: &str, new_name: &str) -> Result<(), String> {
+        //! Rename a table, and update every foreign key pointing to it.
+        //!
+        //! Fails if there is no table `old_name` or a table `new_name` exists already.
+
+        if self.tables.contains_key(new_name) {
+            return Err(format!("invalid rename {}: table already exists", new_name));
+        }
+
+        let table = self
+            .tables
+            .remove(old_name)
+            .ok_or_else(|| format!("err: does not exist: table {}", old_name))?;
+
+        table.write().unwrap().name = new_name.to_string();
+        self.tables.insert(new_name.to_string(), table);
+
+        for table in self.tables.values() {
+            let table = table.read().unwrap();
+            let mut schema = table.schema.write().unwrap();
+            schema.rename_foreign_key_table(old_name, new_name);
+        }
+
+        Ok(())
+    }
+
+    pub fn rename_column(
+        &mut self,
+        table_name: &str,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<(), String> {
+        //! Rename a column of a table, and update every foreign key pointing to it.
+
+        let table = self
+            .get_table(table_name)
+            .ok_or_else(|| format!("err: does not exist: table {}", table_name))?;
+
+        table.write().unwrap().rename_column(old_name, new_name)?;
+
+        for (name, table) in self.tables.iter() {
+            if name == table_name {
+                continue;
+            }
+
+            let table = table.read().unwrap();
+            let mut schema = table.schema.write().unwrap();
+            schema.rename_foreign_key_column(table_name, old_name, new_name);
+        }
+
+        Ok(())
+    }
+
+    pub fn drop_table(&mut self, table_name: &str) -> Result<Option<Arc<RwLock<Table>>>, String> {
         //! Removes a table from the database's table registry.
         //!
-        //! Issues
-        //! - Dropping should check if there are any relationships referring to the
-        //! target table, and raise an error if there are relationships to prevent
-        //! dangling FKs.
+        //! A table that is referred to by a foreign key of another table is not
+        //! dropped, to prevent dangling FKs. A table referring to itself is fine.
+        //!
+        //! Returns the dropped table, or [`None`] if there was no such table.
 
-        let dropped_table = self.tables.remove(table_name).unwrap();
-        let dropped_table_row_count = dropped_table.read().unwrap()._rows();
+        Ok(self.drop_tables(&[table_name.to_string()])?.pop())
+    }
 
-        Ok(dropped_table_row_count)
+    pub fn drop_tables(
+        &mut self,
+        table_names: &[String],
+    ) -> Result<Vec<Arc<RwLock<Table>>>, String> {
+        //! Remove several tables at once, like [`Database::drop_table`].
+        //!
+        //! Every table is checked before any is dropped, so either all of them
+        //! are dropped or none. A foreign key between two of the dropped tables
+        //! does not hold them back. Names without a table are skipped.
+        //!
+        //! Returns the dropped tables.
+
+        for table_name in table_names.iter() {
+            let referencing_tables: Vec<String> = self
+                ._referencing_tables(table_name)
+                .into_iter()
+                .filter(|name| !table_names.contains(name))
+                .collect();
+
+            if !referencing_tables.is_empty() {
+                return Err(format!(
+                    "invalid drop {}: referenced by foreign keys in {}",
+                    table_name,
+                    referencing_tables.join(", ")
+                ));
+            }
+        }
+
+        Ok(table_names
+            .iter()
+            .filter_map(|table_name| self.tables.remove(table_name))
+            .collect())
+    }
+}
+
+// Database snapshots
+impl Database {
+    pub fn snapshot(&self) -> DatabaseSnapshot {
+        //! Copy the current state of all tables, see [`DatabaseSnapshot`].
+
+        let data = self
+            .tables
+            .iter()
+            .map(|(name, table)| (name.clone(), table.read().unwrap().snapshot()))
+            .collect();
+
+        DatabaseSnapshot {
+            tables: self.tables.clone(),
+            data,
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: DatabaseSnapshot) {
+        //! Bring the database back to the state of the `snapshot`.
+        //!
+        //! Tables created after the snapshot are dropped again and tables
+        //! dropped after it come back.
+
+        let DatabaseSnapshot { tables, mut data } = snapshot;
+
+        for (name, table) in tables.iter() {
+            if let Some(table_snapshot) = data.remove(name) {
+                table.write().unwrap().restore(table_snapshot);
+            }
+        }
+
+        self.tables = tables;
+    }
+}
+
+// Database schema diffs
+impl Database {
+    pub fn diff(&self, other: &Database) -> SchemaDiff {
+        //! Compare the schema of this database with the one of `other`, see
+        //! [`SchemaDiff`]. Tables and columns are matched by name and listed in
+        //! name and column order.
+
+        let mut names = self.get_table_names();
+        names.sort();
+        let mut other_names = other.get_table_names();
+        other_names.sort();
+
+        let mut diff = SchemaDiff::default();
+        for name in names.iter() {
+            let Some(other_table) = other.tables.get(name) else {
+                diff.removed_tables.push(name.clone());
+                continue;
+            };
+
+            let table = self.tables[name].read().unwrap();
+            if let Some(table_diff) = diff_tables(&table, &other_table.read().unwrap()) {
+                diff.changed_tables.push(table_diff);
+            }
+        }
+        diff.added_tables = other_names
+            .into_iter()
+            .filter(|name| !self.tables.contains_key(name))
+            .collect();
+
+        diff
     }
 }
 
 // Database serialization
 impl Database {
+    pub fn to_sql_dump(&self) -> String {
+        //! Write the whole database as SQL statements that recreate it when
+        //! replayed through the parser and executor.
+        //!
+        //! Tables are ordered so that every table comes after the tables its
+        //! foreign keys refer to, and by name otherwise.
+
+        let mut remaining = self.get_table_names();
+        remaining.sort();
+
+        let mut ordered: Vec<String> = vec![];
+
+        while !remaining.is_empty() {
+            let position = remaining
+                .iter()
+                .position(|table_name| {
+                    let table = self.tables[table_name].read().unwrap();
+                    let schema = table.schema.read().unwrap();
+                    schema
+                        .get_foreign_key_constraints()
+                        .iter()
+                        .all(|(_, constraint)| {
+                            constraint.table_name == *table_name
+                                || !remaining.contains(&constraint.table_name)
+                        })
+                })
+                .unwrap_or(0);
+
+            ordered.push(remaining.remove(position));
+        }
+
+        ordered
+            .iter()
+            .map(|table_name| self.tables[table_name].read().unwrap().to_sql_dump())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
     pub fn to_data(&self) -> DatabaseData {
         let tables = self
             .tables
@@ -438,6 +979,7 @@ impl Database {
         DatabaseData {
             name: self.name.clone(),
             tables,
+            config: self.config.clone(),
         }
     }
 
@@ -448,10 +990,17 @@ impl Database {
             .map(|(name, table_data)| (name, Arc::new(RwLock::new(Table::from_data(table_data)))))
             .collect();
 
-        Database {
+        let database = Database {
             name: data.name,
             tables,
+            config: data.config,
+        };
+
+        for table in database.tables.values() {
+            database._link_foreign_keys(table);
         }
+
+        database
     }
 }
 
@@ -475,36 +1024,60 @@ impl DatabaseRegistry {
     ) -> Result<Arc<RwLock<Database>>, String> {
         //! Create a new database if it does not already exist.
         //!
-        //! Throws an exception if a database with the same name already exists.
-
-        let db = Arc::new(RwLock::new(Database::new(db_name.to_string())));
+        //! Fails if a database with the same name already exists, unless
+        //! `if_not_exists` is set, then the existing database is returned as is.
 
-        if self.registry.contains_key(db_name) {
+        if let Some(db) = self.registry.get(db_name) {
             if if_not_exists {
-                self.get_database(db_name)
-            } else {
-                Err(format!(
-                    "Integrity violation; database {} already exists",
-                    db_name
-                ))
+                return Ok(Arc::clone(db));
             }
-        } else {
-            self.registry.insert(db_name.to_string(), db);
-            let created_db = self
-                .registry
-                .get(db_name)
-                .expect("Failed to create database.");
+            return Err(format!(
+                "Integrity violation; database {} already exists",
+                db_name
+            ));
+        }
 
-            Ok(Arc::clone(&created_db))
+        let db = Arc::new(RwLock::new(Database::new(db_name.to_string())));
+        self.registry.insert(db_name.to_string(), Arc::clone(&db));
+        Ok(db)
+    }
+
+    pub fn add_database(&mut self, database: Database) -> Result<Arc<RwLock<Database>>, String> {
+        //! Register an existing database, e.g. one loaded from a file, under its name.
+        //!
+        //! Fails like [`DatabaseRegistry::create_database`] if the name is taken.
+
+        let db_name = database.name();
+        if self.registry.contains_key(&db_name) {
+            return Err(format!(
+                "Integrity violation; database {} already exists",
+                db_name
+            ));
         }
+
+        let db = Arc::new(RwLock::new(database));
+        self.registry.insert(db_name, Arc::clone(&db));
+        Ok(db)
     }
 
     pub fn get_database(&self, db_name: &str) -> Result<Arc<RwLock<Database>>, String> {
-        let db = self
-            .registry
-            .get(db_name)
-            .expect(format!("Database {} does not exist.", db_name).as_str());
-        Ok(Arc::clone(db))
+        //! Get a handle of the database named `db_name`.
+        //!
+        //! Fails if there is none, naming the closest existing database if one is close.
+
+        match self.registry.get(db_name) {
+            Some(db) => Ok(Arc::clone(db)),
+            None => {
+                let names = self.registry.keys().map(String::as_str);
+                Err(match closest_name(db_name, names) {
+                    Some(closest) => format!(
+                        "invalid database {}: does not exist, did you mean '{}'?",
+                        db_name, closest
+                    ),
+                    None => format!("invalid database {}: does not exist", db_name),
+                })
+            }
+        }
     }
 
     pub fn get_database_names(&self) -> Vec<String> {
@@ -514,7 +1087,8 @@ impl DatabaseRegistry {
     }
 
     pub fn drop_database(&mut self, db_name: &str) -> Option<Arc<RwLock<Database>>> {
-        //! Delete an existing database
+        //! Delete an existing database, returning it, or [`None`] if there is
+        //! no database by that name, for `DROP DATABASE IF EXISTS`.
         //!
         //! The registry will FORCE a delete regardless of whether there are foreign key connections
         //! or not.
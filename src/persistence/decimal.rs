@@ -0,0 +1,175 @@
+use std::fmt::Display;
+
+/// A fixed-point number, stored as a whole number of `10^-scale` units.
+///
+/// Values of a [`super::DataType::Decimal`] column are read and written through
+/// it, so they keep exactly the digits they were given, e.g. `5.25` is 525
+/// units at scale 2. The units are an [`i64`], the same range a `num` column
+/// has, which leaves room for up to 18 digits.
+///
+/// # Example
+/// ```
+/// use ferrum_engine::persistence::Decimal;
+///
+/// let price = Decimal::parse("5").unwrap().with_scale(2).unwrap();
+/// assert_eq!(price.to_string(), "5.00");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decimal {
+    units: i64,
+    scale: u32,
+}
+
+impl Decimal {
+    pub fn parse(text: &str) -> Option<Decimal> {
+        //! Read a number like `-12`, `5.25` or `.5`, keeping as many decimal
+        //! places as it is written with.
+
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        // More places than the units can hold at all
+        if fraction.len() > 18 {
+            return None;
+        }
+
+        let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !all_digits(integer)
+            || !all_digits(fraction)
+        {
+            return None;
+        }
+
+        // Parsed with the sign, so the most negative value still fits
+        let sign = if negative { "-" } else { "" };
+        let units = format!("{}{}{}", sign, integer, fraction)
+            .parse::<i64>()
+            .ok()?;
+
+        Some(Decimal {
+            units,
+            scale: fraction.len() as u32,
+        })
+    }
+
+    pub fn scale(&self) -> u32 {
+        //! The number of decimal places.
+
+        self.scale
+    }
+
+    pub fn integer_digits(&self) -> u32 {
+        //! The number of digits before the decimal point, without leading zeros.
+
+        let integer = self.units.unsigned_abs() / 10u64.pow(self.scale);
+        match integer {
+            0 => 0,
+            integer => integer.ilog10() + 1,
+        }
+    }
+
+    pub fn to_integer(self) -> Option<i64> {
+        //! The value as a whole number, if it has no fraction, so `3.00` is `3`.
+
+        let unit = 10i64.checked_pow(self.scale)?;
+        (self.units % unit == 0).then_some(self.units / unit)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.units == 0
+    }
+
+    pub fn with_scale(self, scale: u32) -> Option<Decimal> {
+        //! The same value with more decimal places, padded with zeros.
+        //!
+        //! Gives [`None`] if it has more decimal places than `scale` already,
+        //! or does not fit the units with the extra places.
+
+        let extra = scale.checked_sub(self.scale)?;
+
+        Some(Decimal {
+            units: self.units.checked_mul(10i64.checked_pow(extra)?)?,
+            scale,
+        })
+    }
+
+    fn _align(self, other: Decimal) -> Option<(i64, i64, u32)> {
+        //! Get the units of both values at the larger scale of the two.
+
+        let scale = self.scale.max(other.scale);
+        Some((
+            self.with_scale(scale)?.units,
+            other.with_scale(scale)?.units,
+            scale,
+        ))
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let (left, right, scale) = self._align(other)?;
+        let units = left.checked_add(right)?;
+        Some(Decimal { units, scale })
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        let (left, right, scale) = self._align(other)?;
+        let units = left.checked_sub(right)?;
+        Some(Decimal { units, scale })
+    }
+
+    pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        //! Multiply, keeping the larger scale of the two. Extra places are cut
+        //! off, rounding towards zero.
+
+        let scale = self.scale.max(other.scale);
+        let extra = 10i64.checked_pow(self.scale + other.scale - scale)?;
+        let units = self.units.checked_mul(other.units)? / extra;
+        Some(Decimal { units, scale })
+    }
+
+    pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+        //! Divide, keeping the larger scale of the two and rounding towards zero.
+        //!
+        //! Gives [`None`] when dividing by zero.
+
+        let (left, right, scale) = self._align(other)?;
+        let units = left
+            .checked_mul(10i64.checked_pow(scale)?)?
+            .checked_div(right)?;
+        Some(Decimal { units, scale })
+    }
+
+    pub fn checked_rem(self, other: Decimal) -> Option<Decimal> {
+        //! The remainder of dividing, at the larger scale of the two.
+        //!
+        //! Gives [`None`] when dividing by zero.
+
+        let (left, right, scale) = self._align(other)?;
+        let units = left.checked_rem(right)?;
+        Some(Decimal { units, scale })
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.units);
+        }
+
+        let divisor = 10u64.pow(self.scale);
+        let sign = if self.units < 0 { "-" } else { "" };
+        let units = self.units.unsigned_abs();
+
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            units / divisor,
+            units % divisor,
+            width = self.scale as usize
+        )
+    }
+}
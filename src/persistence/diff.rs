@@ -0,0 +1,248 @@
+//! The differences between the schemas of two databases, see [`Database::diff`].
+//!
+//! Only the structure is compared: which tables exist and, for the tables of
+//! both databases, which columns they have and how the columns are defined.
+//! Rows are never looked at.
+//!
+//! [`Database::diff`]: super::Database::diff
+
+use std::fmt::Display;
+
+use super::schema::{ColumnInformation, DataType, Timestamp};
+use super::table::Table;
+
+/// A part of a column definition that differs, as `(before, after)`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnChange {
+    /// The type, with its length limit, e.g. `TXT(50)`.
+    Type(String, String),
+    /// Whether the column allows NULL.
+    Nullability(bool, bool),
+    /// Every constraint of the column, e.g. `PRIMARY KEY, UNIQUE`.
+    Constraints(String, String),
+}
+
+/// A column that both versions of a table have, but define differently.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnDiff {
+    pub column: String,
+    pub changes: Vec<ColumnChange>,
+}
+
+/// The column differences of a table that both databases have.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableDiff {
+    pub table: String,
+    /// Columns only the other version of the table has, with their definition.
+    pub added_columns: Vec<(String, String)>,
+    /// Columns only this version of the table has.
+    pub removed_columns: Vec<String>,
+    pub changed_columns: Vec<ColumnDiff>,
+}
+
+/// How to turn the schema of one database into the schema of another.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaDiff {
+    /// Tables only the other database has.
+    pub added_tables: Vec<String>,
+    /// Tables only this database has.
+    pub removed_tables: Vec<String>,
+    /// Tables both databases have, with different columns.
+    pub changed_tables: Vec<TableDiff>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        //! Whether both schemas are the same.
+
+        self.added_tables.is_empty()
+            && self.removed_tables.is_empty()
+            && self.changed_tables.is_empty()
+    }
+}
+
+/// A column definition, split into the parts a [`ColumnChange`] compares.
+struct ColumnDefinition {
+    datatype: String,
+    nullable: bool,
+    constraints: String,
+}
+
+impl ColumnDefinition {
+    fn of(table: &Table, index: usize, col_info: &ColumnInformation) -> ColumnDefinition {
+        let mut datatype = match &col_info.datatype {
+            DataType::Enum(values) => format!("ENUM({})", values.join(",")),
+            datatype => datatype.to_string(),
+        };
+        if let Some(max_limit) = col_info.max_limit {
+            datatype = format!("{}({})", datatype, max_limit);
+        }
+
+        let mut constraints = vec![];
+        if table.primary_key_columns.contains(&index) {
+            constraints.push("PRIMARY KEY".to_string());
+        }
+        if matches!(table.auto_increment, Some((col_index, _)) if col_index == index) {
+            constraints.push("AUTO".to_string());
+        }
+        if col_info.unique {
+            constraints.push("UNIQUE".to_string());
+        }
+        if let Some((parent, column)) = col_info.foreign_key() {
+            constraints.push(format!("REFERENCES {}({})", parent, column));
+        }
+        match col_info.timestamp {
+            Some(Timestamp::Created) => constraints.push("CREATED".to_string()),
+            Some(Timestamp::Updated) => constraints.push("UPDATED".to_string()),
+            None => {}
+        }
+        if let Some(check) = &col_info.check {
+            constraints.push(format!("CHECK ({})", check));
+        }
+
+        ColumnDefinition {
+            datatype,
+            nullable: col_info.nullable,
+            constraints: constraints.join(", "),
+        }
+    }
+
+    fn changes_to(&self, other: &ColumnDefinition) -> Vec<ColumnChange> {
+        let mut changes = vec![];
+        if self.datatype != other.datatype {
+            changes.push(ColumnChange::Type(
+                self.datatype.clone(),
+                other.datatype.clone(),
+            ));
+        }
+        if self.nullable != other.nullable {
+            changes.push(ColumnChange::Nullability(self.nullable, other.nullable));
+        }
+        if self.constraints != other.constraints {
+            changes.push(ColumnChange::Constraints(
+                self.constraints.clone(),
+                other.constraints.clone(),
+            ));
+        }
+        changes
+    }
+}
+
+impl Display for ColumnDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.datatype, _nullability(self.nullable))?;
+        if !self.constraints.is_empty() {
+            write!(f, " {}", self.constraints)?;
+        }
+        Ok(())
+    }
+}
+
+fn _nullability(nullable: bool) -> &'static str {
+    if nullable { "NULL" } else { "NOT NULL" }
+}
+
+pub(super) fn diff_tables(table: &Table, other: &Table) -> Option<TableDiff> {
+    //! Compare the columns of two versions of a table, matching them by name.
+    //!
+    //! Gives [`None`] if both versions define the same columns.
+
+    let schema = table.schema.read().unwrap();
+    let other_schema = other.schema.read().unwrap();
+    let mut diff = TableDiff {
+        table: table.name(),
+        ..TableDiff::default()
+    };
+
+    for (index, (col_name, col_info)) in schema.get_vec().iter().enumerate() {
+        let Some(other_index) = other_schema.column_index(col_name) else {
+            diff.removed_columns.push(col_name.clone());
+            continue;
+        };
+
+        let (_, other_info) = other_schema.get(other_index).unwrap();
+        let changes = ColumnDefinition::of(table, index, col_info)
+            .changes_to(&ColumnDefinition::of(other, other_index, other_info));
+        if !changes.is_empty() {
+            diff.changed_columns.push(ColumnDiff {
+                column: col_name.clone(),
+                changes,
+            });
+        }
+    }
+
+    for (index, (col_name, col_info)) in other_schema.get_vec().iter().enumerate() {
+        if schema.column_index(col_name).is_none() {
+            let definition = ColumnDefinition::of(other, index, col_info);
+            diff.added_columns
+                .push((col_name.clone(), definition.to_string()));
+        }
+    }
+
+    let unchanged = diff.added_columns.is_empty()
+        && diff.removed_columns.is_empty()
+        && diff.changed_columns.is_empty();
+    (!unchanged).then_some(diff)
+}
+
+impl Display for ColumnChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnChange::Type(before, after) => write!(f, "type {} -> {}", before, after),
+            ColumnChange::Nullability(before, after) => {
+                write!(f, "{} -> {}", _nullability(*before), _nullability(*after))
+            }
+            ColumnChange::Constraints(before, after) => {
+                let none = |constraints: &str| {
+                    if constraints.is_empty() {
+                        "no constraints".to_string()
+                    } else {
+                        constraints.to_string()
+                    }
+                };
+                write!(f, "{} -> {}", none(before), none(after))
+            }
+        }
+    }
+}
+
+impl Display for SchemaDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        //! One line per difference, e.g.
+        //!
+        //! ```text
+        //! + table orders
+        //! - table legacy
+        //! ~ table products
+        //!     + column stock NUM NOT NULL
+        //!     - column color
+        //!     ~ column name: type TXT(20) -> TXT(50), NOT NULL -> NULL
+        //! ```
+
+        if self.is_empty() {
+            return writeln!(f, "no schema differences");
+        }
+
+        for table in &self.added_tables {
+            writeln!(f, "+ table {}", table)?;
+        }
+        for table in &self.removed_tables {
+            writeln!(f, "- table {}", table)?;
+        }
+        for table in &self.changed_tables {
+            writeln!(f, "~ table {}", table.table)?;
+            for (column, definition) in &table.added_columns {
+                writeln!(f, "    + column {} {}", column, definition)?;
+            }
+            for column in &table.removed_columns {
+                writeln!(f, "    - column {}", column)?;
+            }
+            for column in &table.changed_columns {
+                let changes: Vec<String> = column.changes.iter().map(|c| c.to_string()).collect();
+                writeln!(f, "    ~ column {}: {}", column.column, changes.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
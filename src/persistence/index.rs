@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock, Weak};
+
+use super::table::Table;
 
 /// The basic types of key linkages allowed between records.
 /// [Key::PrimaryKey] is an indicator for the
@@ -16,16 +19,24 @@ pub(crate) enum Key {
 
 /// A simple foreign key constraint, that will be returned and saved in
 /// the [super::schema::Schema]'s [super::schema::ColumnInformation].
+///
+/// Once resolved, it also holds a weak handle of the parent table, so inserts
+/// look the key up in the parent directly. The handle is weak so a table
+/// referencing itself does not keep itself alive, and it is not saved; loaded
+/// constraints find the parent by its name instead.
 #[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct ForeignKeyConstraint {
     pub(crate) table_name: String,
     pub(crate) column_name: String,
     column_index: Option<usize>,
+    #[serde(skip)]
+    parent: Option<Weak<RwLock<Table>>>,
 }
 
 /// A simple index implementation to find the rows by primary key quickly.
 ///
-/// Composite keys are concatenated with a separator.
+/// Composite keys are concatenated with [`Index::encode_key`], which prefixes every
+/// value with its length so no two different keys can ever produce the same string.
 ///
 /// # Issues
 /// - The index is NOT multi-thread compatible. This means there is a grave danger
@@ -43,6 +54,22 @@ impl Index {
         Index { key_index_map }
     }
 
+    pub fn encode_key<S: AsRef<str>>(values: &[S]) -> String {
+        //! Encode the values of a (possibly composite) key as a single string.
+        //!
+        //! Every value is written as `<len>:<value>`, so values that contain
+        //! separators can not collide, e.g. `["a:b", "c"]` and `["a", "b:c"]`.
+
+        values
+            .iter()
+            .map(|value| format!("{}:{}", value.as_ref().len(), value.as_ref()))
+            .collect()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.key_index_map.contains_key(key)
+    }
+
     pub fn insert(&mut self, key: String, index: usize) {
         self.key_index_map.insert(key, index);
     }
@@ -68,16 +95,89 @@ impl Index {
     }
 }
 
+/// An ordered index over a numeric column, to find the rows of a range of values
+/// without scanning the whole table.
+///
+/// Unlike [`Index`], a value may map to many rows. NULL cells are not indexed.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct OrderedIndex {
+    value_rows_map: BTreeMap<i64, Vec<usize>>,
+}
+
+impl OrderedIndex {
+    pub fn new() -> OrderedIndex {
+        OrderedIndex::default()
+    }
+
+    pub fn insert(&mut self, value: i64, index: usize) {
+        self.value_rows_map.entry(value).or_default().push(index);
+    }
+
+    pub fn remove(&mut self, value: i64, index: usize) {
+        if let Some(indices) = self.value_rows_map.get_mut(&value) {
+            indices.retain(|row_index| *row_index != index);
+            if indices.is_empty() {
+                self.value_rows_map.remove(&value);
+            }
+        }
+    }
+
+    pub fn range(&self, low: i64, high: i64) -> Vec<usize> {
+        //! Get the rows with a value between `low` and `high`, both inclusive.
+        //!
+        //! The row indices are sorted, so the rows keep their storage order.
+
+        if low > high {
+            return vec![];
+        }
+
+        let mut indices: Vec<usize> = self
+            .value_rows_map
+            .range(low..=high)
+            .flat_map(|(_, indices)| indices.iter().copied())
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    pub fn shift_index_back(&mut self, start_index: usize) {
+        //! Close the gap left by a deleted row, see [`Index::shift_index_back`].
+
+        for indices in self.value_rows_map.values_mut() {
+            for row_index in indices.iter_mut() {
+                if *row_index > start_index {
+                    *row_index -= 1;
+                }
+            }
+        }
+    }
+}
+
 impl ForeignKeyConstraint {
     pub(crate) fn update_index(&mut self, index: usize) {
         self.column_index = Some(index);
     }
 
+    pub(crate) fn update_parent(&mut self, parent: &Arc<RwLock<Table>>) {
+        self.parent = Some(Arc::downgrade(parent));
+    }
+
+    pub(crate) fn column_index(&self) -> Option<usize> {
+        self.column_index
+    }
+
+    pub(crate) fn parent(&self) -> Option<Arc<RwLock<Table>>> {
+        //! Get the parent table, unless it was never resolved or has been dropped.
+
+        self.parent.as_ref()?.upgrade()
+    }
+
     pub fn new(table_name: String, column_name: String) -> ForeignKeyConstraint {
         ForeignKeyConstraint {
             table_name,
             column_name,
             column_index: None,
+            parent: None,
         }
     }
 }
@@ -0,0 +1,197 @@
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+
+use crate::errors::EngineError;
+
+use super::row::Row;
+use super::schema::Schema;
+use super::table::TableReader;
+
+type LazyFilter<'a> = Box<dyn Fn(&Row) -> bool + 'a>;
+
+/// A single recorded operation of a [`LazyReader`].
+enum Stage<'a> {
+    Filter(LazyFilter<'a>),
+    Select(Vec<usize>),
+    Offset(usize),
+    Limit(usize),
+}
+
+/// A lazy version of the [`TableReader`] chain.
+///
+/// [`TableReader::filter`], [`TableReader::select`] and friends copy the rows
+/// into a new reader at every step. A [`LazyReader`] only records the steps,
+/// and runs them all in a single pass over the borrowed rows when
+/// [`LazyReader::collect`] or [`LazyReader::scan`] is called. Only the rows
+/// that make it through every step are copied.
+///
+/// The steps run in the order they were chained, so a filter after a select
+/// sees the selected columns, exactly like the eager chain.
+///
+/// # Example
+/// ```
+/// use ferrum_engine::persistence::Table;
+///
+/// let mut table = Table::new(
+///     "users".to_string(),
+///     vec!["id num pk".to_string(), "name txt".to_string()],
+/// )
+/// .unwrap();
+/// table.insert(vec!["1".to_string(), "ana".to_string()]).unwrap();
+/// table.insert(vec!["2".to_string(), "bob".to_string()]).unwrap();
+///
+/// let reader = table.reader();
+/// let names = reader
+///     .lazy()
+///     .filter(|row| row.get_str(0).as_deref() == Some("2"))
+///     .unwrap()
+///     .select(vec!["name".to_string()])
+///     .unwrap()
+///     .scan();
+///
+/// assert_eq!(names[0].get_str(0).as_deref(), Some("bob"));
+/// ```
+pub struct LazyReader<'a> {
+    source: &'a TableReader,
+    schema: Schema,
+    stages: Vec<Stage<'a>>,
+}
+
+impl<'a> LazyReader<'a> {
+    pub(super) fn new(source: &'a TableReader) -> LazyReader<'a> {
+        let schema = source.schema.read().unwrap().clone();
+
+        LazyReader {
+            source,
+            schema,
+            stages: Vec::new(),
+        }
+    }
+
+    pub fn schema(&self) -> &Schema {
+        //! The schema of the rows as they will come out of the chain so far.
+
+        &self.schema
+    }
+
+    pub fn filter<F>(mut self, filter: F) -> Result<LazyReader<'a>, String>
+    where
+        F: Fn(&Row) -> bool + 'a,
+    {
+        //! Keep only the rows matching the filter criteria.
+
+        self.stages.push(Stage::Filter(Box::new(filter)));
+        Ok(self)
+    }
+
+    pub fn select(mut self, fields: Vec<String>) -> Result<LazyReader<'a>, EngineError> {
+        //! Keep only the `fields` columns, in the given order.
+        //!
+        //! Fails if a field is not a column of the chain so far.
+
+        let indices = fields
+            .iter()
+            .map(|field| {
+                self.schema
+                    .column_index(field)
+                    .ok_or_else(|| self.schema.column_not_found(field))
+            })
+            .collect::<Result<Vec<usize>, EngineError>>()?;
+
+        self.schema = Schema::new(
+            indices
+                .iter()
+                .map(|&index| self.schema.get(index).unwrap().clone())
+                .collect(),
+        );
+        self.stages.push(Stage::Select(indices));
+        Ok(self)
+    }
+
+    pub fn offset(mut self, rows: Option<usize>) -> Result<LazyReader<'a>, String> {
+        //! Skip the first number of rows.
+
+        if let Some(rows) = rows {
+            self.stages.push(Stage::Offset(rows));
+        }
+        Ok(self)
+    }
+
+    pub fn limit(mut self, rows: Option<usize>) -> Result<LazyReader<'a>, String> {
+        //! Keep at most the said number of rows.
+
+        if let Some(rows) = rows {
+            self.stages.push(Stage::Limit(rows));
+        }
+        Ok(self)
+    }
+
+    pub fn scan(self) -> Vec<Row> {
+        //! Run the chain and return the resulting rows.
+        //!
+        //! Stops reading the source as soon as a limit is reached.
+
+        let source = self.source.rows.read().unwrap();
+        // Rows still to skip or take, for every offset and limit stage.
+        let mut counters: Vec<usize> = self
+            .stages
+            .iter()
+            .map(|stage| match stage {
+                Stage::Offset(rows) | Stage::Limit(rows) => *rows,
+                _ => 0,
+            })
+            .collect();
+        let mut rows = Vec::new();
+
+        'rows: for row in source.iter() {
+            let mut row = Cow::Borrowed(row);
+
+            for (stage, counter) in self.stages.iter().zip(counters.iter_mut()) {
+                match stage {
+                    Stage::Filter(filter) if !filter(&row) => continue 'rows,
+                    Stage::Filter(_) => {}
+                    Stage::Select(indices) => {
+                        row = Cow::Owned(Row(indices
+                            .iter()
+                            .map(|&index| row.0[index].clone())
+                            .collect()));
+                    }
+                    Stage::Offset(_) if *counter > 0 => {
+                        *counter -= 1;
+                        continue 'rows;
+                    }
+                    Stage::Offset(_) => {}
+                    Stage::Limit(_) if *counter == 0 => break 'rows,
+                    Stage::Limit(_) => *counter -= 1,
+                }
+            }
+
+            rows.push(row.into_owned());
+        }
+
+        rows
+    }
+
+    pub fn collect(self) -> TableReader {
+        //! Run the chain and return the result as a new [`TableReader`].
+        //!
+        //! An empty chain shares the rows of the source instead of copying them.
+
+        if self.stages.is_empty() {
+            return TableReader {
+                schema: Arc::clone(&self.source.schema),
+                rows: Arc::clone(&self.source.rows),
+                all_rows: self.source.all_rows.clone(),
+            };
+        }
+
+        let schema = self.schema.clone();
+        let rows = self.scan();
+
+        TableReader {
+            schema: Arc::new(RwLock::new(schema)),
+            rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
+        }
+    }
+}
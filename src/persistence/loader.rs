@@ -0,0 +1,78 @@
+//! Options and progress of a bulk load, see [`Database::bulk_load`].
+//!
+//! [`Database::bulk_load`]: super::Database::bulk_load
+
+use std::fmt;
+
+/// What a bulk load does with a record that can't be inserted.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OnError {
+    /// Report the record and carry on with the next one.
+    Skip,
+    /// Stop at the record, keeping the rows loaded before it.
+    #[default]
+    Abort,
+}
+
+pub struct LoadOptions {
+    /// The character between fields, `,` for CSV and `\t` for TSV.
+    pub delimiter: char,
+    /// Whether the first record holds the column names, and is not loaded.
+    pub has_header: bool,
+    pub on_error: OnError,
+    /// Report the progress after this many records; `0` only reports at the end.
+    pub progress_every: usize,
+}
+
+impl LoadOptions {
+    pub fn new() -> LoadOptions {
+        //! Load a CSV file with a header, stopping at the first bad record and
+        //! reporting every 10000 records.
+
+        LoadOptions {
+            delimiter: ',',
+            has_header: true,
+            on_error: OnError::default(),
+            progress_every: 10_000,
+        }
+    }
+
+    pub fn tsv() -> LoadOptions {
+        LoadOptions {
+            delimiter: '\t',
+            ..LoadOptions::new()
+        }
+    }
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How far a bulk load has got.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadProgress {
+    /// The records read from the file so far, the header excluded.
+    pub records: usize,
+    /// The rows inserted into the table so far.
+    pub loaded: usize,
+    /// Why each failed record was not loaded, naming its row in the file.
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for LoadProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        //! e.g. `1000 record(s) read, 998 loaded, 2 error(s)`, a line ready to
+        //! be written to stderr by a progress callback.
+
+        write!(
+            f,
+            "{} record(s) read, {} loaded, {} error(s)",
+            self.records,
+            self.loaded,
+            self.errors.len()
+        )
+    }
+}
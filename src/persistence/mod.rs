@@ -10,11 +10,24 @@ mod table;
 mod row;
 mod schema;
 mod index;
+mod value;
+mod lazy;
+mod loader;
+mod decimal;
+mod diff;
+mod config;
 
 //  External API
-pub use database::{Database, DatabaseRegistry};
-pub use table::{Table, TableReader};
-pub use row::Row;
+pub use database::{Database, DatabaseRegistry, DatabaseSnapshot};
+pub use table::{Table, TableReader, TableSnapshot};
+pub use lazy::LazyReader;
+pub use loader::{LoadOptions, LoadProgress, OnError};
+pub use row::{NULL_TOKEN, Row};
+pub use schema::{ColumnInformation, DATE_FORMAT, DataType, Schema, Timestamp};
+pub use value::Value;
+pub use decimal::Decimal;
+pub use diff::{ColumnChange, ColumnDiff, SchemaDiff, TableDiff};
+pub use config::{DEFAULT_TEXT_LIMIT, DatabaseConfig};
 
 // External API for (De)Serialization
-pub use database::DatabaseRegistryData;
\ No newline at end of file
+pub use database::{DatabaseData, DatabaseRegistryData};
\ No newline at end of file
@@ -1,14 +1,90 @@
-use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt::Display;
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Row(pub Vec<Option<String>>);
+use super::schema::Schema;
+use super::value::Value;
 
-// impl Row {
-//     pub fn at(&self, index: usize) -> &Option<String> {
-//         self.0.get(index).unwrap()
-//     }
-// }
+/// The token used in place of a NULL cell when a [`Row`] is rendered as text.
+pub const NULL_TOKEN: &str = "NIL";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row(pub Vec<Value>);
+
+impl Row {
+    pub fn parse(schema: &Schema, cells: Vec<Option<String>>) -> Row {
+        //! Read the text `cells` as values of their columns in the `schema`, see
+        //! [`Value::parse`].
+
+        Row(cells
+            .iter()
+            .zip(schema.get_vec())
+            .map(|(cell, (_, col_info))| Value::parse(col_info.datatype(), cell.as_deref()))
+            .collect())
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        //! Get the cell at `index`, or [`None`] if the row has no such column.
+
+        self.0.get(index)
+    }
+
+    pub fn get_str(&self, index: usize) -> Option<Cow<'_, str>> {
+        //! Get the value at `index` as text, borrowed for a [`Value::Text`].
+        //! Gives [`None`] both for a NULL cell and when the row has no such column.
+
+        match self.0.get(index)? {
+            Value::Null => None,
+            Value::Text(text) => Some(Cow::Borrowed(text)),
+            value => Some(Cow::Owned(value.to_string())),
+        }
+    }
+
+    pub fn is_null(&self, index: usize) -> bool {
+        //! Check if the cell at `index` is NULL. A column the row does not have
+        //! is not a NULL cell, so this is `false` for it.
+
+        matches!(self.0.get(index), Some(Value::Null))
+    }
+
+    pub fn cells(&self) -> Vec<Option<String>> {
+        //! Turn every value back into text, the form rows are saved in, see
+        //! [`Value::to_cell`].
+
+        self.0.iter().map(Value::to_cell).collect()
+    }
+
+    pub fn format_with(&self, separator: &str) -> String {
+        //! Render the cells unpadded, between `separator`s, e.g. `1,NIL,Pen`
+        //! with `","`. A NULL cell is written as [`NULL_TOKEN`].
+        //!
+        //! Unlike [`Display`], the result can be read back without ambiguity:
+        //! a value holding the separator, a quote or a line break, or reading
+        //! as [`NULL_TOKEN`] itself, is quoted with its quotes doubled.
+
+        self.0
+            .iter()
+            .map(|value| match value {
+                Value::Null => NULL_TOKEN.to_string(),
+                value => _quote_field(&value.to_string(), separator),
+            })
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+}
+
+pub(super) fn _quote_field(value: &str, separator: &str) -> String {
+    //! Quote `value` if it could be mistaken for a separator, a line break or a NULL.
+
+    let ambiguous = value == NULL_TOKEN
+        || (!separator.is_empty() && value.contains(separator))
+        || value.contains(['"', '\n', '\r']);
+
+    if ambiguous {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
 
 impl Display for Row {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -16,8 +92,8 @@ impl Display for Row {
             .0
             .iter()
             .map(|value| match value {
-                Some(string) => format!("{:16}", string.as_str()),
-                None => "NIL".to_string(),
+                Value::Null => NULL_TOKEN.to_string(),
+                value => format!("{:16}", value),
             })
             .collect();
         writeln!(f, "| {} |", row.join(" | "))
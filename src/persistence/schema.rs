@@ -1,12 +1,110 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::num::IntErrorKind;
+use uuid::Uuid;
 
+use std::sync::{Arc, RwLock};
+
+use crate::cli::closest_name;
+use crate::errors::EngineError;
+use crate::persistence::decimal::Decimal;
 use crate::persistence::index::ForeignKeyConstraint;
+use crate::persistence::row::_quote_field;
+use crate::persistence::table::Table;
+
+/// The format in which [`DataType::Date`] values are written and stored.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
 
 #[derive(Clone, Serialize, Deserialize)]
 pub enum DataType {
     Number,
     Text,
+    Date,
+    Json,
+    Uuid,
+    Bool,
+    /// A fixed-point number with at most `precision` digits, `scale` of them
+    /// after the decimal point, see [`super::Decimal`].
+    Decimal(u32, u32),
+    /// A text that must be one of the listed values.
+    Enum(Vec<String>),
+}
+
+impl DataType {
+    pub fn is_numeric(&self) -> bool {
+        //! Whether the values of the type compare and compute as numbers.
+
+        matches!(self, DataType::Number | DataType::Decimal(..))
+    }
+
+    pub fn coerce(&self, value: &str) -> Result<String, String> {
+        //! Convert a written value to the form it is stored in, for a column of
+        //! this type. Every value arrives as text, so the rules are:
+        //!
+        //! - `num` takes whole numbers, and decimals without a fraction, so
+        //!   `3.0` is stored as `3`. Signs and leading zeros are dropped.
+        //! - `dec(p,s)` takes any number within its digits, padded to `s` places.
+        //! - `txt` takes any value as written, numbers included.
+        //! - `date` takes `YYYY-MM-DD` dates.
+        //! - `json` takes any JSON document, stored compact.
+        //! - `uuid` takes hyphenated UUIDs, stored in lower case.
+        //! - `bool` takes `true` and `false` in any case, and `1` and `0`.
+        //! - `enum(...)` takes one of its values, matching case.
+        //!
+        //! Anything else is rejected, and the error says why, to be completed
+        //! with the value and the column.
+
+        match self {
+            DataType::Number => match value.parse::<i64>() {
+                Ok(number) => Ok(number.to_string()),
+                Err(error) => match Decimal::parse(value).and_then(Decimal::to_integer) {
+                    Some(number) => Ok(number.to_string()),
+                    None => Err(match error.kind() {
+                        IntErrorKind::PosOverflow => format!("above the maximum of {}", i64::MAX),
+                        IntErrorKind::NegOverflow => format!("below the minimum of {}", i64::MIN),
+                        _ => "not a whole number".to_string(),
+                    }),
+                },
+            },
+            DataType::Decimal(precision, scale) => match Decimal::parse(value) {
+                None => Err("not a decimal number".to_string()),
+                Some(decimal) if decimal.scale() > *scale => {
+                    Err(format!("more than {} decimal place(s)", scale))
+                }
+                Some(decimal) if decimal.integer_digits() > precision - scale => Err(format!(
+                    "more than {} digit(s) before the point",
+                    precision - scale
+                )),
+                Some(decimal) => decimal
+                    .with_scale(*scale)
+                    .map(|decimal| decimal.to_string())
+                    .ok_or_else(|| "out of range".to_string()),
+            },
+            DataType::Text => Ok(value.to_string()),
+            // Stored in the canonical format, so dates also compare in order as text
+            DataType::Date => NaiveDate::parse_from_str(value, DATE_FORMAT)
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .map_err(|_| "not a YYYY-MM-DD date".to_string()),
+            DataType::Json => serde_json::from_str::<serde_json::Value>(value)
+                .map(|document| document.to_string())
+                .map_err(|_| "not a JSON document".to_string()),
+            DataType::Uuid => match Uuid::try_parse(value) {
+                Ok(uuid) if value.len() == 36 => Ok(uuid.to_string()),
+                _ => Err("not a canonical UUID".to_string()),
+            },
+            DataType::Bool => match value.to_lowercase().as_str() {
+                "true" | "1" => Ok("true".to_string()),
+                "false" | "0" => Ok("false".to_string()),
+                _ => Err("not true or false".to_string()),
+            },
+            DataType::Enum(values) if values.iter().any(|allowed| allowed == value) => {
+                Ok(value.to_string())
+            }
+            DataType::Enum(values) => Err(format!("expected one of {}", values.join(", "))),
+        }
+    }
 }
 
 impl Display for DataType {
@@ -14,17 +112,42 @@ impl Display for DataType {
         let datatype = match self {
             DataType::Number => "NUM",
             DataType::Text => "TXT",
+            DataType::Date => "DATE",
+            DataType::Json => "JSON",
+            DataType::Uuid => "UUID",
+            DataType::Bool => "BOOL",
+            DataType::Enum(_) => "ENUM",
+            DataType::Decimal(precision, scale) => {
+                return write!(f, "DEC({},{})", precision, scale);
+            }
         };
         write!(f, "{}", datatype)
     }
 }
 
+/// When the engine fills a timestamp column with the current time, written
+/// like the `NOW()` scalar does.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Timestamp {
+    /// Once, when the row is inserted.
+    Created,
+    /// When the row is inserted and on every update of it.
+    Updated,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ColumnInformation {
     pub(super) datatype: DataType,
     pub(super) max_limit: Option<usize>,
     pub(super) nullable: bool,
     pub(super) foreign_key_constraint: Option<ForeignKeyConstraint>,
+    #[serde(default)]
+    pub(super) unique: bool,
+    #[serde(default)]
+    pub(super) timestamp: Option<Timestamp>,
+    /// The expression of the column's CHECK constraint, in SQL.
+    #[serde(default)]
+    pub(super) check: Option<String>,
 }
 
 impl ColumnInformation {
@@ -39,9 +162,39 @@ impl ColumnInformation {
             max_limit: Some(256),
             nullable: false,
             foreign_key_constraint: None,
+            unique: false,
+            timestamp: None,
+            check: None,
         }
     }
 
+    pub fn datatype(&self) -> &DataType {
+        &self.datatype
+    }
+
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        //! Whether the engine maintains this column, see [`Timestamp`].
+
+        self.timestamp
+    }
+
+    pub fn check(&self) -> Option<&str> {
+        //! The expression every value of this column has to satisfy, if any.
+
+        self.check.as_deref()
+    }
+
+    pub fn foreign_key(&self) -> Option<(&str, &str)> {
+        //! Get the `(table, column)` this column refers to, if it is a foreign key.
+
+        self.foreign_key_constraint.as_ref().map(|constraint| {
+            (
+                constraint.table_name.as_str(),
+                constraint.column_name.as_str(),
+            )
+        })
+    }
+
     pub fn from(datatype: DataType, max_limit: Option<usize>, nullable: bool) -> ColumnInformation {
         //! Create a [`ColumnInformation`] object from custom information, available to the engine.
         //!
@@ -52,19 +205,54 @@ impl ColumnInformation {
             max_limit,
             nullable,
             foreign_key_constraint: None,
+            unique: false,
+            timestamp: None,
+            check: None,
         }
     }
 }
 
+/// The ordered columns of a table, with a lookup from column name to position.
+///
+/// The lookup is rebuilt whenever the columns change, so it never goes stale.
+/// Only the columns are serialized.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Schema(Vec<(String, ColumnInformation)>);
+#[serde(
+    from = "Vec<(String, ColumnInformation)>",
+    into = "Vec<(String, ColumnInformation)>"
+)]
+pub struct Schema(Vec<(String, ColumnInformation)>, HashMap<String, usize>);
+
+impl From<Vec<(String, ColumnInformation)>> for Schema {
+    fn from(schema: Vec<(String, ColumnInformation)>) -> Self {
+        Schema::new(schema)
+    }
+}
+
+impl From<Schema> for Vec<(String, ColumnInformation)> {
+    fn from(schema: Schema) -> Self {
+        schema.0
+    }
+}
 
 impl Schema {
     pub fn new(schema: Vec<(String, ColumnInformation)>) -> Schema {
         //! Create a schema from a vector of column names and its associated
         //! [`ColumnInformation`]s
 
-        Schema(schema)
+        let mut schema = Schema(schema, HashMap::new());
+        schema._rebuild_lookup();
+        schema
+    }
+
+    fn _rebuild_lookup(&mut self) {
+        //! Map every column name to its position. When names repeat, the first
+        //! column wins, like a linear scan would.
+
+        self.1.clear();
+        for (index, (name, _)) in self.0.iter().enumerate() {
+            self.1.entry(name.clone()).or_insert(index);
+        }
     }
 
     pub fn get(&self, index: usize) -> Option<&(String, ColumnInformation)> {
@@ -86,6 +274,66 @@ impl Schema {
         self.0.len()
     }
 
+    pub fn column_not_found(&self, column_name: &str) -> EngineError {
+        //! The error for a column that is not in the schema, naming the closest
+        //! existing column if one is close.
+
+        let columns = self.0.iter().map(|(col_name, _)| col_name.as_str());
+        EngineError::ColumnNotFound(match closest_name(column_name, columns) {
+            Some(closest) => format!(
+                "invalid column {}: does not exist, did you mean '{}'?",
+                column_name, closest
+            ),
+            None => format!("invalid column {}: does not exist", column_name),
+        })
+    }
+
+    pub fn column_index(&self, column_name: &str) -> Option<usize> {
+        //! Find the position of the column `column_name` in the schema, without
+        //! scanning the columns.
+        //!
+        //! This is how column names from a query are turned into the indexes
+        //! that aggregators and scalars take in.
+
+        self.1.get(column_name).copied()
+    }
+
+    pub fn push(&mut self, column_name: String, column_info: ColumnInformation) {
+        //! Add a column at the end of the schema.
+
+        self.1.entry(column_name.clone()).or_insert(self.0.len());
+        self.0.push((column_name, column_info));
+    }
+
+    pub fn rename(&mut self, index: usize, column_name: String) {
+        //! Rename the column at `index`. Does nothing if there is no such column.
+
+        if let Some((name, _)) = self.0.get_mut(index) {
+            *name = column_name;
+            self._rebuild_lookup();
+        }
+    }
+
+    pub fn truncate(&mut self, columns: usize) {
+        //! Keep only the first number of `columns`, dropping the rest.
+
+        self.0.truncate(columns);
+        self._rebuild_lookup();
+    }
+
+    pub fn format_with(&self, separator: &str) -> String {
+        //! Render the column names between `separator`s, as the header of rows
+        //! rendered by [`Row::format_with`], quoting them the same way.
+        //!
+        //! [`Row::format_with`]: crate::persistence::Row::format_with
+
+        self.0
+            .iter()
+            .map(|(col, _)| _quote_field(col, separator))
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
     pub(crate) fn get_foreign_key_constraints(&self) -> Vec<(usize, ForeignKeyConstraint)> {
         //! Get all the non-none fk constraints.
         //!
@@ -123,6 +371,15 @@ impl Schema {
         })
     }
 
+    pub(crate) fn set_check(&mut self, schema_index: usize, check: Option<String>) {
+        //! Set the CHECK expression of the column at `schema_index`, see
+        //! [`ColumnInformation::check`].
+
+        if let Some((_, col_info)) = self.0.get_mut(schema_index) {
+            col_info.check = check;
+        }
+    }
+
     pub(crate) fn update_foreign_key_index(&mut self, schema_index: usize, key_index: usize) {
         if let Some((_, col_info)) = self.0.get_mut(schema_index) {
             col_info
@@ -133,13 +390,46 @@ impl Schema {
         }
     }
 
-    pub fn get_vec_mut(&mut self) -> &mut Vec<(String, ColumnInformation)> {
-        //! Get the 0 attribute as a read-only reference.
-        //!
-        //! Returns the reference to the vector with column name and
-        //! column information.
+    pub(crate) fn update_foreign_key_parent(
+        &mut self,
+        schema_index: usize,
+        parent: &Arc<RwLock<Table>>,
+    ) {
+        if let Some((_, col_info)) = self.0.get_mut(schema_index)
+            && let Some(constraint) = col_info.foreign_key_constraint.as_mut()
+        {
+            constraint.update_parent(parent);
+        }
+    }
+
+    pub(crate) fn rename_foreign_key_table(&mut self, old_name: &str, new_name: &str) {
+        //! Point all fk constraints on the table `old_name` to `new_name` instead.
+
+        for (_, col_info) in self.0.iter_mut() {
+            if let Some(constraint) = col_info.foreign_key_constraint.as_mut()
+                && constraint.table_name == old_name
+            {
+                constraint.table_name = new_name.to_string();
+            }
+        }
+    }
+
+    pub(crate) fn rename_foreign_key_column(
+        &mut self,
+        table_name: &str,
+        old_name: &str,
+        new_name: &str,
+    ) {
+        //! Point all fk constraints on the column `table_name.old_name` to `new_name` instead.
 
-        self.0.as_mut()
+        for (_, col_info) in self.0.iter_mut() {
+            if let Some(constraint) = col_info.foreign_key_constraint.as_mut()
+                && constraint.table_name == table_name
+                && constraint.column_name == old_name
+            {
+                constraint.column_name = new_name.to_string();
+            }
+        }
     }
 }
 
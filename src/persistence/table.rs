@@ -1,24 +1,45 @@
+use chrono::Local;
+use indexmap::IndexMap;
 use log::warn;
 use serde::{Deserialize, Serialize};
 
-use crate::cli::{FunctionArg, SelectColumn};
-use crate::functions::{aggregators, scalars};
-
-use super::index::{ForeignKeyConstraint, Index, Key};
-use super::row::Row;
-use super::schema::{ColumnInformation, DataType, Schema};
-
-use std::collections::{HashMap, VecDeque};
+use crate::cli::{FunctionArg, SelectColumn, compile_check, is_quiet};
+use crate::errors::EngineError;
+use crate::functions::aggregators;
+use crate::functions::scalars::{self, Scalar, TIMESTAMP_FORMAT};
+use crate::serialization::csv;
+
+use super::config::DatabaseConfig;
+use super::index::{ForeignKeyConstraint, Index, Key, OrderedIndex};
+use super::lazy::LazyReader;
+use super::row::{NULL_TOKEN, Row};
+use super::schema::{ColumnInformation, DataType, Schema, Timestamp};
+use super::value::Value;
+
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Display;
-use std::sync::{Arc, RwLock};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 
 /// Creates a new table with the specified schema.
 ///
 /// # Column Format
 /// Each column definition is a space-separated string:
-/// - `"column_name datatype [pk]"`
-/// - Datatypes: `num` (number), `txt` (text)
+/// - `"column_name datatype [pk] [auto] [unique] [null] [created|updated]"`
+/// - Datatypes: `num` (number), `txt` (text of up to 50 characters, or the default of the
+///   [`DatabaseConfig`]), `txt(length)` (text of up to `length` characters), `date` (`YYYY-MM-DD`),
+///   `json` (a JSON document), `uuid` (`xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`),
+///   `dec(precision,scale)` (a fixed-point number), `enum(value,...)` (one of the listed values,
+///   which hold no spaces or commas)
 /// - Optional: `pk` marks column as part of primary key
+/// - Optional: `unique` rejects repeated non-null values in the column
+/// - Optional: `null` allows empty values, stored as NULL
+/// - Optional: `auto` fills an empty `num pk` value from a per-table counter
+/// - Optional: `created` or `updated` makes a `txt` column a timestamp the engine maintains
 ///
 /// # Note
 /// In case a `pk` value is not mentioned, the first column
@@ -57,6 +78,17 @@ use std::sync::{Arc, RwLock};
 /// - Column list is empty
 /// - Column definition is malformed
 /// - Duplicate column names exist
+///
+/// # Soft Delete
+/// [`Table::delete_soft`] only marks a row as deleted. The row keeps its place
+/// and its keys, so no index shifts, but it is hidden from readers and from
+/// lookups by key, updates and foreign key checks until it is brought back
+/// with [`Table::recover`]. Use [`TableReader::with_deleted`] to see the
+/// marked rows as well, and [`Table::vacuum`] to remove them for good.
+///
+/// ## Issues
+/// - A soft-deleted row still holds its primary key, so inserting the same key
+///   again fails.
 pub struct Table {
     pub(crate) name: String,
     pub(crate) schema: Arc<RwLock<Schema>>,
@@ -64,20 +96,66 @@ pub struct Table {
     pub(crate) primary_key_columns: Vec<usize>,
     pub(crate) is_indexed: bool,
     pub(crate) index: Index,
+    pub(crate) unique_indexes: HashMap<usize, Index>,
+    pub(crate) ordered_indexes: HashMap<usize, OrderedIndex>,
+    pub(crate) auto_increment: Option<(usize, u64)>,
+    pub(crate) deleted: HashSet<usize>,
+    pub(crate) checks: Vec<Check>,
+    pub(crate) version: u64,
+}
+
+/// A CHECK constraint of a column, compiled against the table schema.
+///
+/// Added with [`Table::add_check`], apart from the column definitions, and
+/// compiled like a WHERE clause, see [`compile_check`]. Only the expression
+/// is stored in the schema, so the filter is compiled again whenever the
+/// table is loaded.
+pub(crate) struct Check {
+    name: String,
+    column: usize,
+    expression: String,
+    filter: Box<dyn Fn(&Row) -> bool + Send + Sync>,
 }
 
 /// Serialization type for the [`Table`] struct
 ///
 /// Removes the unneeded [`Arc`] and [`RwLock`] enclosures to
-/// give a raw storable object type.
+/// give a raw storable object type. Rows are stored as text cells, see
+/// [`Row::cells`], and read back as values of their columns.
 #[derive(Serialize, Deserialize)]
 pub struct TableData {
     name: String,
     schema: Schema,
-    rows: Vec<Row>,
+    rows: Vec<Vec<Option<String>>>,
     primary_key_columns: Vec<usize>,
     is_indexed: bool,
     index: Index,
+    #[serde(default)]
+    unique_indexes: HashMap<usize, Index>,
+    #[serde(default)]
+    ordered_indexes: HashMap<usize, OrderedIndex>,
+    #[serde(default)]
+    auto_increment: Option<(usize, u64)>,
+    #[serde(default)]
+    deleted: HashSet<usize>,
+}
+
+/// A copy of the state of a [`Table`] at some point in time.
+///
+/// Taken with [`Table::snapshot`] and put back with [`Table::restore`], which
+/// brings back the name, the rows, the soft-deleted marks, every index, the
+/// schema and the auto-increment counter. Unlike a [`TableData`], restoring a snapshot
+/// needs no index rebuild, so it is cheap enough for quick rollbacks.
+#[derive(Clone)]
+pub struct TableSnapshot {
+    name: String,
+    schema: Schema,
+    rows: Vec<Row>,
+    index: Index,
+    unique_indexes: HashMap<usize, Index>,
+    ordered_indexes: HashMap<usize, OrderedIndex>,
+    auto_increment: Option<(usize, u64)>,
+    deleted: HashSet<usize>,
 }
 
 /// Creates a reader object over a [Table]'s data snapshot.
@@ -94,6 +172,8 @@ pub struct TableData {
 /// [TableReader] object.
 /// - [TableReader::select] selects specific columns of the table to convert to
 /// another [TableReader] object.
+/// - [TableReader::lazy] chains these steps without copying rows, see [LazyReader].
+/// - [TableReader::with_deleted] brings back the soft-deleted rows of the table.
 ///
 /// # Issues
 /// - TableReader does NOT support indexing, because it does not know how to use that
@@ -101,6 +181,31 @@ pub struct TableData {
 pub struct TableReader {
     pub schema: Arc<RwLock<Schema>>,
     pub rows: Arc<RwLock<Vec<Row>>>,
+    /// All rows of the table, including soft-deleted ones, when `rows` hides some.
+    pub(crate) all_rows: Option<Arc<RwLock<Vec<Row>>>>,
+}
+
+/// Words reserved by the column definition format.
+const COLUMN_KEYWORDS: [&str; 14] = [
+    "pk", "fk", "num", "txt", "date", "json", "uuid", "bool", "unique", "null", "auto",
+    "created", "updated", "enum",
+];
+
+/// The last [`Table::version`] handed out, shared by all tables.
+static TABLE_VERSIONS: AtomicU64 = AtomicU64::new(0);
+
+/// The parsed form of a single column definition string, before it is turned
+/// into a [`ColumnInformation`] for the [`Schema`].
+struct ColumnDefinition {
+    name: String,
+    datatype: DataType,
+    /// The length of a `txt(n)` column, [`None`] for the default one.
+    max_limit: Option<usize>,
+    keys: Vec<Key>,
+    nullable: bool,
+    unique: bool,
+    auto_increment: bool,
+    timestamp: Option<Timestamp>,
 }
 
 impl Table {
@@ -110,161 +215,396 @@ impl Table {
         //! Returns a cloned value of row count, may behave differently
         //! for multi-threaded system.
 
-        self.rows.read().unwrap().len()
+        self.row_count()
+    }
+
+    pub fn row_count(&self) -> usize {
+        //! Get the number of rows currently stored in the table, without the
+        //! soft-deleted ones.
+
+        self.rows.read().unwrap().len() - self.deleted.len()
+    }
+
+    pub fn rows(&self) -> usize {
+        //! Get the current row count, the same as [`Table::row_count`].
+
+        self.row_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.row_count() == 0
+    }
+
+    pub fn column_count(&self) -> usize {
+        //! Get the number of columns in the table's schema.
+
+        self.schema.read().unwrap().len()
     }
 
     fn _validate_field(
         &self,
-        item: String,
+        item: Option<String>,
         col_name: &String,
         col_info: &ColumnInformation,
-    ) -> Result<Option<String>, String> {
+    ) -> Result<Option<String>, EngineError> {
         //! An extended validator function to validate a single field.
         //!
         //! Returns an [`Option<String>`] if the field is valid, that can be directly pushed to row.
-
-        if item.is_empty() && col_info.nullable {
-            return Ok(None);
-        } else if item.is_empty() && !col_info.nullable {
-            return Err(format!(
-                "invalid NULL: empty strings not allowed on columm '{}'",
-                col_name
-            ));
-        } else {
-            match col_info.datatype {
-                DataType::Number => {
-                    if item.parse::<u64>().is_err() {
-                        return Err(format!(
-                            "invalid {}: value not allowed on column '{}' ({})",
-                            item, col_name, col_info.datatype
-                        ));
-                    }
-                }
-                DataType::Text => {
-                    if let Some(max_limit) = col_info.max_limit {
-                        if item.len() > max_limit {
-                            return Err(format!(
-                                "invalid {}: value not allowed on column '{}' ({})",
-                                item, col_name, col_info.datatype
-                            ));
-                        }
-                    }
-                }
+        //!
+        //! A [`None`] item is a NULL. Any other item is converted as described
+        //! in [`DataType::coerce`], so an empty string is only a valid `txt` value.
+
+        let item = match item {
+            None if col_info.nullable => return Ok(None),
+            None => {
+                return Err(EngineError::NullViolation(format!(
+                    "invalid NULL: not allowed on column '{}'",
+                    col_name
+                )));
             }
-            return Ok(Some(item));
+            Some(item) => item,
+        };
+
+        let value = col_info.datatype.coerce(&item).map_err(|reason| {
+            EngineError::TypeMismatch(format!(
+                "invalid {}: {}, on column '{}' ({})",
+                item, reason, col_name, col_info.datatype
+            ))
+        })?;
+
+        if let Some(max_limit) = col_info.max_limit
+            && value.chars().count() > max_limit
+        {
+            return Err(EngineError::TypeMismatch(format!(
+                "invalid {}: longer than {} character(s), on column '{}' ({})",
+                item, max_limit, col_name, col_info.datatype
+            )));
         }
+        Ok(Some(value))
     }
 
-    fn _validate_data(&self, data: Vec<String>) -> Result<Row, String> {
+    fn _validate_data(&self, data: Vec<Option<String>>) -> Result<Row, EngineError> {
         //! Validate the row with respect to the schema.
         //!
-        //! Returns the row if the data is correct.
+        //! Returns the row if the data is correct, its fields converted to the
+        //! [`Value`]s of their columns.
 
         let schema = self.schema.read().unwrap();
         if data.len() != schema.len() {
-            return Err(format!(
+            return Err(EngineError::Other(format!(
                 "invalid data: schema has {} column(s), but {} were provided",
                 schema.len(),
                 data.len(),
-            ));
+            )));
         }
 
-        let mut row: Vec<Option<String>> = Vec::new();
+        let mut row: Vec<Value> = Vec::new();
 
         for (item, (col_name, col_info)) in data.into_iter().zip(schema.get_vec()) {
-            row.push(self._validate_field(item, col_name, col_info)?)
+            let field = self._validate_field(item, col_name, col_info)?;
+            row.push(Value::parse(col_info.datatype(), field.as_deref()));
+        }
+        drop(schema);
+
+        let row = Row(row);
+        self._validate_checks(&row)?;
+        Ok(row)
+    }
+
+    fn _validate_checks(&self, row: &Row) -> Result<(), String> {
+        //! Make sure the row satisfies every CHECK constraint of the table.
+        //!
+        //! Like in SQL, a NULL value passes the check of its column.
+
+        for check in &self.checks {
+            let Some(value) = row.get_str(check.column) else {
+                continue;
+            };
+            if !(check.filter)(row) {
+                let schema = self.schema.read().unwrap();
+                let (col_name, _) = schema.get(check.column).unwrap();
+                return Err(format!(
+                    "invalid {}: violates check constraint {} ({}), on column '{}'",
+                    value, check.name, check.expression, col_name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn _compile_checks(table_name: &str, schema: &Schema) -> Result<Vec<Check>, String> {
+        //! Compile the CHECK constraint of every column that has one. They are
+        //! named after the table and the column, like `users_age_check`.
+
+        let mut checks = vec![];
+        for (column, (col_name, col_info)) in schema.get_vec().iter().enumerate() {
+            if let Some(expression) = &col_info.check {
+                let filter = compile_check(expression, schema.get_vec()).map_err(|e| {
+                    format!("invalid check {} on column {}: {}", expression, col_name, e)
+                })?;
+                checks.push(Check {
+                    name: format!("{}_{}_check", table_name, col_name),
+                    column,
+                    expression: expression.clone(),
+                    filter,
+                });
+            }
+        }
+        Ok(checks)
+    }
+
+    fn _parse_text_limit(col_type: &str) -> Option<usize> {
+        //! Parse the length of a `txt(n)` datatype token.
+
+        let limit = col_type
+            .strip_prefix("txt(")?
+            .strip_suffix(")")?
+            .parse::<usize>()
+            .ok()?;
+        (limit > 0).then_some(limit)
+    }
+
+    fn _parse_decimal(col_type: &str) -> Option<DataType> {
+        //! Parse a `dec(precision,scale)` datatype token.
+
+        let (precision, scale) = col_type
+            .strip_prefix("dec(")?
+            .strip_suffix(")")?
+            .split_once(",")?;
+        let precision = precision.parse::<u32>().ok()?;
+        let scale = scale.parse::<u32>().ok()?;
+
+        // Values are held in an i64, which fits 18 digits
+        if !(1..=18).contains(&precision) || scale > precision {
+            return None;
         }
+        Some(DataType::Decimal(precision, scale))
+    }
+
+    fn _parse_enum(col_type: &str) -> Option<DataType> {
+        //! Parse an `enum(value,...)` datatype token.
 
-        Ok(Row(row))
+        let values: Vec<String> = col_type
+            .strip_prefix("enum(")?
+            .strip_suffix(")")?
+            .split(",")
+            .map(|value| value.to_string())
+            .collect();
+
+        let distinct: HashSet<&String> = values.iter().collect();
+        if values.iter().any(|value| value.is_empty()) || distinct.len() != values.len() {
+            return None;
+        }
+        Some(DataType::Enum(values))
     }
 
-    fn _parse_column(
-        col_def: &str,
-    ) -> Result<(Option<String>, Option<DataType>, Option<Key>), String> {
+    fn _parse_column(col_def: &str) -> Result<ColumnDefinition, String> {
         //! Parse the column definition string.
         //!
-        //! Returns the name of the column, and the column information.
+        //! Returns the name of the column, its datatype, all the keys declared
+        //! on it, in order, and the remaining column flags.
 
         let mut col_def_vec: VecDeque<&str> = col_def.split(" ").collect();
-        let (mut column, mut datatype, mut key) = (None, None, None);
 
         // Get the name of the column making sure it is not a keyword
-        if let Some(col_name) = col_def_vec.pop_front() {
-            if vec!["pk", "fk", "num", "txt"].contains(&col_name) {
+        let name = match col_def_vec.pop_front() {
+            Some(col_name) if COLUMN_KEYWORDS.contains(&col_name) => {
                 return Err(format!(
                     "invalid input {}: keywords not allowed as column names",
                     col_name
                 ));
-            } else {
-                column = Some(col_name.to_string());
             }
-        }
+            Some(col_name) => col_name.to_string(),
+            None => return Err("invalid column: empty definition".to_string()),
+        };
 
         // Get the datatype of the column
-        if let Some(col_type) = col_def_vec.pop_front() {
-            match col_type {
-                "num" => datatype = Some(DataType::Number),
-                "txt" => datatype = Some(DataType::Text),
-                _ => {
-                    return Err(format!(
-                        "invalid datatype {}: not supported, on column {}",
-                        col_type,
-                        column.unwrap()
-                    ));
-                }
+        let mut max_limit = None;
+        let datatype = match col_def_vec.pop_front() {
+            Some("num") => DataType::Number,
+            Some("txt") => DataType::Text,
+            Some("date") => DataType::Date,
+            Some("json") => DataType::Json,
+            Some("uuid") => DataType::Uuid,
+            Some("bool") => DataType::Bool,
+            Some(col_type) if col_type.starts_with("txt(") => {
+                max_limit = Some(Self::_parse_text_limit(col_type).ok_or_else(|| {
+                    format!(
+                        "invalid datatype {}: expected txt(length) with a length above 0, on column {}",
+                        col_type, name
+                    )
+                })?);
+                DataType::Text
             }
-        }
+            Some(col_type) if col_type.starts_with("dec(") => Self::_parse_decimal(col_type)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid datatype {}: expected dec(precision,scale) with a precision of 1 to 18 and a scale not above it, on column {}",
+                        col_type, name
+                    )
+                })?,
+            Some(col_type) if col_type.starts_with("enum(") => Self::_parse_enum(col_type)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid datatype {}: expected enum(value,...) with distinct, non-empty values, on column {}",
+                        col_type, name
+                    )
+                })?,
+            Some(col_type) => {
+                return Err(format!(
+                    "invalid datatype {}: not supported, on column {}",
+                    col_type, name
+                ));
+            }
+            None => return Err(format!("invalid datatype: missing, on column {}", name)),
+        };
+
+        let mut column = ColumnDefinition {
+            name,
+            datatype,
+            max_limit,
+            keys: vec![],
+            nullable: false,
+            unique: false,
+            auto_increment: false,
+            timestamp: None,
+        };
 
-        // Get the keytype (if mentioned) of the column
-        if let Some(col_key) = col_def_vec.pop_front() {
+        // Get the keytypes and flags (if mentioned) of the column
+        while let Some(col_key) = col_def_vec.pop_front() {
             match col_key {
-                "pk" => key = Some(Key::PrimaryKey),
+                "pk" => column.keys.push(Key::PrimaryKey),
+                "unique" => column.unique = true,
+                "null" => column.nullable = true,
+                "auto" => column.auto_increment = true,
+                "created" | "updated" if column.timestamp.is_some() => {
+                    return Err(format!(
+                        "invalid {} on column {}: only one timestamp allowed",
+                        col_key, column.name
+                    ));
+                }
+                "created" => column.timestamp = Some(Timestamp::Created),
+                "updated" => column.timestamp = Some(Timestamp::Updated),
                 "fk" => {
                     let fk_ref = col_def_vec
                         .pop_front()
                         .ok_or("invalid reference table: format <table.col>")?;
 
-                    let mut fk_ref_args: VecDeque<String> = fk_ref
-                        .split(".")
-                        .into_iter()
-                        .map(|s| s.to_string())
-                        .collect();
+                    let mut fk_ref_args: VecDeque<String> =
+                        fk_ref.split(".").map(|s| s.to_string()).collect();
 
                     if fk_ref_args.len() == 2 {
-                        key = Some(Key::ForeignKey(
+                        column.keys.push(Key::ForeignKey(
                             fk_ref_args.pop_front().unwrap(),
                             fk_ref_args.pop_front().unwrap(),
                         ))
                     } else {
-                        return Err(format!("invalid reference: check your fk argument again"));
+                        return Err("invalid reference: check your fk argument again".to_string());
                     }
                 }
-                _ => return Err(format!("invalid key type {}: expected pk or fk", col_key)),
+                _ => {
+                    return Err(format!(
+                        "invalid key type {}: expected pk, fk, unique, null, auto, created or updated",
+                        col_key
+                    ));
+                }
+            }
+        }
+
+        if column.auto_increment {
+            let is_pk = column.keys.iter().any(|key| matches!(key, Key::PrimaryKey));
+            if !matches!(column.datatype, DataType::Number) || !is_pk {
+                return Err(format!(
+                    "invalid auto on column {}: only allowed on a num pk column",
+                    column.name
+                ));
             }
         }
 
-        return Ok((column, datatype, key));
+        if column.timestamp.is_some()
+            && (!matches!(column.datatype, DataType::Text) || !column.keys.is_empty())
+        {
+            return Err(format!(
+                "invalid timestamp on column {}: only allowed on a txt column without keys",
+                column.name
+            ));
+        }
+
+        Ok(column)
     }
 
-    fn _create_index_key_from_row(&self, row: &Row) -> Result<String, String> {
-        let mut values: Vec<String> = Vec::new();
+    fn _rebuild_indexes(&mut self) {
+        //! Rebuild the primary key index, all unique indexes and all ordered
+        //! indexes from the rows.
+
+        let rows = self.rows.read().unwrap();
+
+        if self.is_indexed {
+            self.index = Index::new();
+            for (row_index, row) in rows.iter().enumerate() {
+                if let Ok(key) = self._create_index_key_from_row(row) {
+                    self.index.insert(key, row_index);
+                }
+            }
+        }
+
+        for (col_index, unique_index) in self.unique_indexes.iter_mut() {
+            *unique_index = Index::new();
+            for (row_index, row) in rows.iter().enumerate() {
+                if let Some(value) = row.get_str(*col_index) {
+                    unique_index.insert(value.into_owned(), row_index);
+                }
+            }
+        }
+
+        for (col_index, ordered_index) in self.ordered_indexes.iter_mut() {
+            *ordered_index = OrderedIndex::new();
+            for (row_index, row) in rows.iter().enumerate() {
+                if let Some(value) = _ordered_value(row, *col_index) {
+                    ordered_index.insert(value, row_index);
+                }
+            }
+        }
+    }
+
+    fn _validate_unique(&self, row: &Row, row_index: Option<usize>) -> Result<(), String> {
+        //! Check the unique columns of the `row` against their secondary indexes.
+        //!
+        //! The `row_index` is the position of the row being updated, if any, so
+        //! a row does not collide with its own value. NULLs are never checked.
+
+        let schema = self.schema.read().unwrap();
 
-        for index in self.primary_key_columns.iter() {
-            let value_at_index = row.0.get(*index).unwrap();
-            values.push(value_at_index.as_ref().unwrap().clone());
+        for (col_index, unique_index) in self.unique_indexes.iter() {
+            if let Some(value) = row.get_str(*col_index)
+                && let Some(existing_index) = unique_index.get(&value)
+                && Some(existing_index) != row_index
+            {
+                let (col_name, _) = schema.get(*col_index).unwrap();
+                return Err(format!(
+                    "invalid {}: duplicate value on unique column '{}'",
+                    value, col_name
+                ));
+            }
         }
 
-        if values.len() == 0 && self.primary_key_columns.len() != 0 {
+        Ok(())
+    }
+
+    fn _create_index_key_from_row(&self, row: &Row) -> Result<String, String> {
+        let values = self._extract_pk_values(row);
+
+        if values.len() != self.primary_key_columns.len() {
             return Err("err: failed to index: unable to read columns".to_string());
         }
 
-        Ok(values.join("|"))
+        Ok(Index::encode_key(&values))
     }
 
-    fn _extract_pk_values<'a>(&self, row: &'a Row) -> Vec<&'a str> {
+    fn _extract_pk_values<'a>(&self, row: &'a Row) -> Vec<Cow<'a, str>> {
         self.primary_key_columns
             .iter()
-            .filter_map(|&idx| row.0.get(idx)?.as_ref().map(|s| s.as_str()))
+            .filter_map(|&idx| row.get_str(idx))
             .collect()
     }
 
@@ -274,19 +614,21 @@ impl Table {
         //! Returns an index to a row.
 
         let rows = self.rows.read().unwrap();
-        let key = keys.join("|");
-        rows.iter()
-            .position(|row| self._extract_pk_values(row).join("|") == key)
+        rows.iter().enumerate().position(|(index, row)| {
+            !self.deleted.contains(&index) && self._extract_pk_values(row) == keys
+        })
     }
 
     fn _find_row(&self, pk: Vec<&str>) -> Option<usize> {
         //! Search the row, in a table either with or without
         //! the index.
         //!
-        //! Returns a pointer of the found row.
+        //! Returns a pointer of the found row. Soft-deleted rows are not found.
 
         if self.is_indexed {
-            self.index.get(pk.join("|").as_str())
+            self.index
+                .get(&Index::encode_key(&pk))
+                .filter(|index| !self.deleted.contains(index))
         } else {
             self._find_row_unindexed(pk)
         }
@@ -308,7 +650,47 @@ impl Table {
     }
 
     pub fn pk_exists(&self, pk: &str) -> bool {
-        self.index.get(pk).is_some()
+        //! Check if a row that is not soft-deleted has the primary key `pk`.
+
+        self.index
+            .get(&Index::encode_key(&[pk]))
+            .is_some_and(|index| !self.deleted.contains(&index))
+    }
+
+    pub fn contains_value(&self, col_index: usize, value: &str) -> bool {
+        //! Check if any row holds `value` in the column at `col_index`.
+        //!
+        //! Looks the value up in the primary key index when the column is the
+        //! whole primary key, or in its unique index, and only scans the rows
+        //! for any other column. Soft-deleted rows hold no values.
+
+        if self.is_indexed && self.primary_key_columns == [col_index] {
+            return self.pk_exists(value);
+        }
+
+        if let Some(unique_index) = self.unique_indexes.get(&col_index) {
+            return unique_index
+                .get(value)
+                .is_some_and(|index| !self.deleted.contains(&index));
+        }
+
+        let rows = self.rows.read().unwrap();
+        rows.iter().enumerate().any(|(index, row)| {
+            !self.deleted.contains(&index) && row.get_str(col_index).as_deref() == Some(value)
+        })
+    }
+
+    pub fn get_row(&self, pk: Vec<&str>) -> Result<Option<Row>, String> {
+        //! Look up a single row by its (possibly composite) primary key.
+        //!
+        //! Returns a copy of the row, or [`None`] if no row has the key.
+
+        self._validate_pk(&pk)?;
+
+        let rows = self.rows.read().unwrap();
+        Ok(self
+            ._find_row(pk)
+            .and_then(|index| rows.get(index).cloned()))
     }
 
     pub fn new(name: String, columns: Vec<String>) -> Result<Table, String> {
@@ -317,6 +699,17 @@ impl Table {
         //!
         //! Returns an owned [Table] object.
 
+        Self::with_config(name, columns, &DatabaseConfig::new())
+    }
+
+    pub fn with_config(
+        name: String,
+        columns: Vec<String>,
+        config: &DatabaseConfig,
+    ) -> Result<Table, String> {
+        //! Like [`Table::new`], for a table of a database with the `config`,
+        //! see [`DatabaseConfig`].
+
         if columns.len() == 0 {
             return Err(String::from(
                 "invalid arguments: 0 arguments does not make a schema",
@@ -325,18 +718,24 @@ impl Table {
 
         let mut schema = vec![];
         let mut primary_key_columns = vec![];
-
-        let n_columns = columns.len();
+        let mut unique_indexes = HashMap::new();
+        let mut auto_increment = None;
 
         for (index, col_def) in columns.iter().enumerate() {
-            let (column, datatype, key) = Self::_parse_column(col_def)?;
-            let max_limit = match datatype.as_ref().unwrap() {
-                DataType::Number => None,
-                DataType::Text => Some(50),
+            let column = Self::_parse_column(col_def)?;
+            let max_limit = match column.datatype {
+                DataType::Number
+                | DataType::Decimal(..)
+                | DataType::Date
+                | DataType::Json
+                | DataType::Uuid
+                | DataType::Bool
+                | DataType::Enum(_) => None,
+                DataType::Text => column.max_limit.or(Some(config.default_text_limit)),
             };
-            let mut col_info = ColumnInformation::from(datatype.unwrap(), max_limit, false);
+            let mut col_info = ColumnInformation::from(column.datatype, max_limit, column.nullable);
 
-            if let Some(key) = key {
+            for key in column.keys {
                 match key {
                     Key::PrimaryKey => primary_key_columns.push(index),
                     Key::ForeignKey(table_name, column_name) => {
@@ -345,11 +744,29 @@ impl Table {
                     }
                 }
             }
-            schema.push((column.unwrap().clone(), col_info));
+
+            if column.unique {
+                col_info.unique = true;
+                unique_indexes.insert(index, Index::new());
+            }
+
+            col_info.timestamp = column.timestamp;
+
+            if column.auto_increment {
+                if auto_increment.is_some() {
+                    return Err(format!(
+                        "invalid auto on column {}: only one auto column allowed",
+                        column.name
+                    ));
+                }
+                auto_increment = Some((index, 1));
+            }
+
+            schema.push((column.name, col_info));
         }
 
         let schema = Arc::new(RwLock::new(Schema::new(schema)));
-        let rows = Arc::new(RwLock::new(Vec::with_capacity(n_columns)));
+        let rows = Arc::new(RwLock::new(Vec::new()));
         let index = Index::new();
 
         let mut is_indexed = true;
@@ -368,6 +785,12 @@ impl Table {
             primary_key_columns,
             is_indexed,
             index,
+            unique_indexes,
+            ordered_indexes: HashMap::new(),
+            auto_increment,
+            deleted: HashSet::new(),
+            checks: vec![],
+            version: _next_version(),
         })
     }
 
@@ -375,22 +798,198 @@ impl Table {
         self.name.clone()
     }
 
-    pub fn insert(&mut self, data: Vec<String>) -> Result<Row, String> {
+    pub fn version(&self) -> u64 {
+        //! A number that changes whenever the rows or the columns of the table
+        //! change. No two tables ever share one, not even a table and its copy
+        //! restored from a snapshot, so any cached result of a read can be kept
+        //! for as long as the version stays the same.
+
+        self.version
+    }
+
+    pub fn rename_column(&mut self, old_name: &str, new_name: &str) -> Result<(), String> {
+        //! Rename a column of the table, keeping its data and keys.
+        //!
+        //! The new name follows the same rules as in a column definition. Foreign
+        //! keys of this table on its own column are updated as well, while those of
+        //! other tables are for the [`super::Database`] to update.
+
+        if COLUMN_KEYWORDS.contains(&new_name) {
+            return Err(format!(
+                "invalid input {}: keywords not allowed as column names",
+                new_name
+            ));
+        }
+
+        let mut schema = self.schema.write().unwrap();
+
+        if schema.column_index(new_name).is_some() {
+            return Err(format!("invalid column {}: already exists", new_name));
+        }
+
+        let col_index = schema
+            .column_index(old_name)
+            .ok_or_else(|| schema.column_not_found(old_name))?;
+
+        schema.rename(col_index, new_name.to_string());
+
+        // Checks refer to columns by name, so they must still compile
+        if Self::_compile_checks(&self.name, &schema).is_err() {
+            schema.rename(col_index, old_name.to_string());
+            return Err(format!(
+                "invalid column {}: used by a check constraint",
+                old_name
+            ));
+        }
+        schema.rename_foreign_key_column(&self.name, old_name, new_name);
+        self.version = _next_version();
+
+        Ok(())
+    }
+
+    pub fn add_check(&mut self, column: &str, expression: &str) -> Result<(), String> {
+        //! Add a CHECK constraint on `column`, where the `expression` is SQL
+        //! over the columns of the table, like `age >= 0`. It is given apart
+        //! from the column definitions, so it may hold any literal.
+        //!
+        //! Fails if the column has a check already or the expression does not
+        //! compile. Rows already in the table are not checked.
+
+        let mut schema = self.schema.write().unwrap();
+        let col_index = schema
+            .column_index(column)
+            .ok_or_else(|| schema.column_not_found(column))?;
+
+        if schema.get(col_index).unwrap().1.check().is_some() {
+            return Err(format!(
+                "invalid check on column {}: only one check allowed",
+                column
+            ));
+        }
+
+        schema.set_check(col_index, Some(expression.trim().to_string()));
+        match Self::_compile_checks(&self.name, &schema) {
+            Ok(checks) => self.checks = checks,
+            Err(e) => {
+                schema.set_check(col_index, None);
+                return Err(e);
+            }
+        }
+        self.version = _next_version();
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, data: Vec<String>) -> Result<Row, EngineError> {
         //! Basic insert function that inserts a row of values by matching their data-
         //! types and nullability.
         //!
-        //! Returns a [Result<Row, String>] containing a copy of the row inserted.
+        //! Returns a copy of the row inserted, or an [`EngineError`] telling why
+        //! the row was refused.
+        //!
+        //! An empty value is stored as NULL, use [`Table::insert_values`] to store
+        //! an empty text instead.
+        //!
+        //! An empty value in an `auto` column is replaced with the next value of
+        //! the table's counter. Explicit values larger than the counter bump it.
+
+        self.insert_values(data.iter().map(|value| _empty_as_null(value)).collect())
+    }
+
+    fn _fill_auto_increment(&self, data: &mut [Option<String>]) {
+        //! Replace a NULL in the `auto` column with the next value of the counter.
+
+        if let Some((col_index, next_value)) = self.auto_increment
+            && let Some(value) = data.get_mut(col_index)
+            && value.is_none()
+        {
+            *value = Some(next_value.to_string());
+        }
+    }
+
+    fn _fill_timestamps(&self, data: &mut [Option<String>], timestamps: &[Timestamp]) {
+        //! Set every timestamp column of a kind in `timestamps` to the current time.
+
+        let now = Local::now().format(TIMESTAMP_FORMAT).to_string();
+        let schema = self.schema.read().unwrap();
+
+        for (value, (_, col_info)) in data.iter_mut().zip(schema.get_vec()) {
+            if let Some(timestamp) = col_info.timestamp
+                && timestamps.contains(&timestamp)
+            {
+                *value = Some(now.clone());
+            }
+        }
+    }
+
+    pub fn check_values(&self, rows: &[Vec<Option<String>>]) -> Result<(), EngineError> {
+        //! Check that every row has as many values as the schema has columns, and
+        //! that every value fits the type and nullability of its column, without
+        //! inserting anything.
+        //!
+        //! Keys are not checked, a duplicate key still only fails on insert.
+
+        for data in rows {
+            let mut data = data.clone();
+            self._fill_auto_increment(&mut data);
+            self._fill_timestamps(&mut data, &[Timestamp::Created, Timestamp::Updated]);
+            self._validate_data(data)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn insert_values(&mut self, data: Vec<Option<String>>) -> Result<Row, EngineError> {
+        //! Insert a row of values where [`None`] is a NULL, so an empty string is
+        //! kept as an empty text.
+        //!
+        //! Returns a copy of the row inserted, see [`Table::insert`].
+        //!
+        //! Timestamp columns are set to the current time, replacing any value given.
+
+        let mut data = data;
+        self._fill_auto_increment(&mut data);
+        self._fill_timestamps(&mut data, &[Timestamp::Created, Timestamp::Updated]);
 
         let row = self._validate_data(data)?;
+        self._validate_unique(&row, None)?;
+
         let mut rows = self.rows.write().unwrap();
         let row_index = rows.len();
 
         if self.is_indexed {
-            self.index
-                .insert(self._create_index_key_from_row(&row)?, row_index);
+            let key = self._create_index_key_from_row(&row)?;
+            if self.index.contains(&key) {
+                return Err(EngineError::Other(format!(
+                    "invalid key {}: duplicate primary key",
+                    self._extract_pk_values(&row).join(", ")
+                )));
+            }
+            self.index.insert(key, row_index);
+        }
+
+        for (col_index, unique_index) in self.unique_indexes.iter_mut() {
+            if let Some(value) = row.get_str(*col_index) {
+                unique_index.insert(value.into_owned(), row_index);
+            }
+        }
+
+        for (col_index, ordered_index) in self.ordered_indexes.iter_mut() {
+            if let Some(value) = _ordered_value(&row, *col_index) {
+                ordered_index.insert(value, row_index);
+            }
+        }
+
+        if let Some((col_index, next_value)) = self.auto_increment.as_mut()
+            && let Some(Value::Number(value)) = row.get(*col_index)
+            // Negative keys never bump the counter, which only counts up from 1.
+            && let Ok(value) = u64::try_from(*value)
+        {
+            *next_value = (*next_value).max(value + 1);
         }
 
         rows.push(row.clone());
+        self.version = _next_version();
         Ok(row)
     }
 
@@ -423,33 +1022,73 @@ impl Table {
         //! Update specific columns of a row of a table from its primary key.
         //!
         //! Returns a boolean for the number of columns updated.
+        //!
+        //! Timestamp columns can't be updated directly, `updated` ones are set to
+        //! the current time instead.
 
         self._validate_pk(&pk)?;
-        let row_index = self._find_row(pk).unwrap();
+        let row_index = self
+            ._find_row(pk)
+            .ok_or("err: invalid key; no match for this index".to_string())?;
 
-        let mut rows = self.rows.write().unwrap();
-        let row: &mut Vec<Option<String>> = rows.get_mut(row_index).unwrap().0.as_mut();
+        let mut cells = self.rows.read().unwrap()[row_index].cells();
         let mut col_updated = 0;
 
-        let schema = self.schema.read().unwrap();
-        for (col_name, col_data) in updates {
-            let index = schema
-                .get_vec()
-                .iter()
-                .position(|(s_key, _)| col_name == s_key)
-                .ok_or_else(|| format!("unexpected {}: no such column exists", col_name))?;
+        {
+            let schema = self.schema.read().unwrap();
+            for (col_name, col_data) in updates {
+                let index = schema
+                    .column_index(col_name)
+                    .ok_or_else(|| format!("unexpected {}: no such column exists", col_name))?;
+
+                let (_, col_info) = schema.get(index).expect("err: invalid index");
 
-            let (_, col_info) = schema.get(index).expect("err: invalid index");
+                if col_info.timestamp.is_some() {
+                    return Err(format!(
+                        "invalid column {}: maintained by the engine",
+                        col_name
+                    ));
+                }
 
-            let validated_value =
-                self._validate_field(col_data.to_string(), &col_name, col_info)?;
+                let validated_value =
+                    self._validate_field(_empty_as_null(col_data), col_name, col_info)?;
 
-            row[index] = validated_value;
-            col_updated += 1;
+                cells[index] = validated_value;
+                col_updated += 1;
+            }
         }
 
-        Ok(col_updated)
-    }
+        self._fill_timestamps(&mut cells, &[Timestamp::Updated]);
+        let updated_row = Row::parse(&self.schema.read().unwrap(), cells);
+        self._validate_checks(&updated_row)?;
+        self._validate_unique(&updated_row, Some(row_index))?;
+
+        let mut rows = self.rows.write().unwrap();
+        let row = rows.get_mut(row_index).unwrap();
+        self.version = _next_version();
+
+        for (col_index, unique_index) in self.unique_indexes.iter_mut() {
+            if let Some(old_value) = row.get_str(*col_index) {
+                unique_index.remove(&old_value);
+            }
+            if let Some(new_value) = updated_row.get_str(*col_index) {
+                unique_index.insert(new_value.into_owned(), row_index);
+            }
+        }
+
+        for (col_index, ordered_index) in self.ordered_indexes.iter_mut() {
+            if let Some(old_value) = _ordered_value(row, *col_index) {
+                ordered_index.remove(old_value, row_index);
+            }
+            if let Some(new_value) = _ordered_value(&updated_row, *col_index) {
+                ordered_index.insert(new_value, row_index);
+            }
+        }
+
+        *row = updated_row;
+
+        Ok(col_updated)
+    }
 
     pub fn filter_rows(
         &self,
@@ -463,8 +1102,9 @@ impl Table {
         let pks: Vec<Vec<String>> = {
             let rows = self.rows.read().unwrap();
             rows.iter()
-                .filter(|row| filter(*row))
-                .map(|row| {
+                .enumerate()
+                .filter(|(index, row)| !self.deleted.contains(index) && filter(row))
+                .map(|(_, row)| {
                     self._extract_pk_values(row)
                         .iter()
                         .map(|s| s.to_string())
@@ -486,7 +1126,9 @@ impl Table {
         let pks: Vec<Vec<String>> = {
             let rows = self.rows.read().unwrap();
             rows.iter()
-                .map(|row| {
+                .enumerate()
+                .filter(|(index, _)| !self.deleted.contains(index))
+                .map(|(_, row)| {
                     self._extract_pk_values(row)
                         .iter()
                         .map(|s| s.to_string())
@@ -515,47 +1157,140 @@ impl Table {
 
         self._validate_pk(&pk)?;
 
-        let key = pk.join("|");
+        let key = Index::encode_key(&pk);
         match self._find_row(pk) {
             Some(index) => {
                 let mut rows = self.rows.write().unwrap();
                 let deleted_row = rows.remove(index);
+                self.version = _next_version();
+                self.deleted = self
+                    .deleted
+                    .iter()
+                    .map(|&position| {
+                        if position > index {
+                            position - 1
+                        } else {
+                            position
+                        }
+                    })
+                    .collect();
 
                 if self.is_indexed {
                     self.index.remove(key.as_str());
                     self.index.shift_index_back(index);
                 }
 
+                for (col_index, unique_index) in self.unique_indexes.iter_mut() {
+                    if let Some(value) = deleted_row.get_str(*col_index) {
+                        unique_index.remove(&value);
+                    }
+                    unique_index.shift_index_back(index);
+                }
+
+                for (col_index, ordered_index) in self.ordered_indexes.iter_mut() {
+                    if let Some(value) = _ordered_value(&deleted_row, *col_index) {
+                        ordered_index.remove(value, index);
+                    }
+                    ordered_index.shift_index_back(index);
+                }
+
                 Ok(deleted_row)
             }
             None => Err("err: invalid key; no match for this index".to_string()),
         }
     }
 
+    pub fn delete_many(&mut self, pks: Vec<Vec<&str>>) -> Result<usize, String> {
+        //! Delete the rows of all the `pks` in a single pass over the rows, and
+        //! rebuild the indexes once, instead of shifting them per deleted row.
+        //!
+        //! Behaves like calling [`Table::delete`] for each key in order: the
+        //! first key without a row, including a key given twice, stops with an
+        //! error, but the rows of the keys before it are still deleted.
+        //!
+        //! Returns the number of deleted rows.
+
+        let mut targets = HashSet::new();
+        let mut error = None;
+
+        for pk in pks {
+            let target = self._validate_pk(&pk).and_then(|()| {
+                self._find_row(pk)
+                    .filter(|index| !targets.contains(index))
+                    .ok_or("err: invalid key; no match for this index".to_string())
+            });
+
+            match target {
+                Ok(index) => {
+                    targets.insert(index);
+                }
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+
+        let deleted_row_count = self._delete_rows(&targets);
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(deleted_row_count),
+        }
+    }
+
+    fn _delete_rows(&mut self, targets: &HashSet<usize>) -> usize {
+        //! Remove the rows at the `targets` positions in one pass, then rebuild
+        //! the indexes.
+        //!
+        //! Returns the number of removed rows.
+
+        if targets.is_empty() {
+            return 0;
+        }
+        self.version = _next_version();
+
+        {
+            let mut rows = self.rows.write().unwrap();
+
+            // Soft-deleted rows move up by the number of removed rows before them
+            let mut removed = 0;
+            let mut deleted = HashSet::new();
+            for position in 0..rows.len() {
+                if targets.contains(&position) {
+                    removed += 1;
+                } else if self.deleted.contains(&position) {
+                    deleted.insert(position - removed);
+                }
+            }
+            self.deleted = deleted;
+
+            let mut position = 0;
+            rows.retain(|_| {
+                let keep = !targets.contains(&position);
+                position += 1;
+                keep
+            });
+        }
+
+        self._rebuild_indexes();
+        targets.len()
+    }
+
     pub fn delete_with_filter(
         &mut self,
         filter: Box<dyn Fn(&Row) -> bool>,
     ) -> Result<usize, String> {
-        let pks: Vec<Vec<String>> = {
+        let targets: HashSet<usize> = {
             let rows = self.rows.read().unwrap();
             rows.iter()
-                .filter(|row| filter(*row))
-                .map(|row| {
-                    self._extract_pk_values(row)
-                        .iter()
-                        .map(|s| s.to_string())
-                        .collect()
-                })
+                .enumerate()
+                .filter(|(index, row)| !self.deleted.contains(index) && filter(row))
+                .map(|(index, _)| index)
                 .collect()
         };
 
-        let mut updated_row_count = 0;
-        for pk in pks {
-            self.delete(pk.iter().map(|s| s.as_str()).collect())?;
-            updated_row_count += 1;
-        }
-
-        Ok(updated_row_count)
+        Ok(self._delete_rows(&targets))
     }
 
     pub fn delete_all(&mut self) -> usize {
@@ -563,24 +1298,353 @@ impl Table {
         //!
         //! Returns the total number of deleted rows, the size of the table.
 
+        let row_count = self.row_count();
         let mut rows = self.rows.write().unwrap();
-        let row_count = rows.iter().count();
         rows.clear();
+        self.deleted.clear();
+        self.version = _next_version();
+        self.index = Index::new();
+
+        for unique_index in self.unique_indexes.values_mut() {
+            *unique_index = Index::new();
+        }
+
+        for ordered_index in self.ordered_indexes.values_mut() {
+            *ordered_index = OrderedIndex::new();
+        }
 
         row_count
     }
 
+    pub fn delete_soft(&mut self, pk: Vec<&str>) -> Result<Row, String> {
+        //! Mark the row of the `pk` as deleted, without removing it, see the
+        //! soft delete section of [`Table`].
+        //!
+        //! Returns a copy of the marked row.
+
+        self._validate_pk(&pk)?;
+
+        let index = self
+            ._find_row(pk)
+            .ok_or("err: invalid key; no match for this index".to_string())?;
+        self.deleted.insert(index);
+        self.version = _next_version();
+
+        Ok(self.rows.read().unwrap()[index].clone())
+    }
+
+    pub fn recover(&mut self, pk: Vec<&str>) -> Result<Row, String> {
+        //! Bring back the soft-deleted row of the `pk`.
+        //!
+        //! Returns a copy of the recovered row.
+
+        self._validate_pk(&pk)?;
+
+        let rows = self.rows.read().unwrap();
+        let index = self
+            .deleted
+            .iter()
+            .copied()
+            .find(|&index| self._extract_pk_values(&rows[index]) == pk)
+            .ok_or("err: invalid key; no soft-deleted row for this key".to_string())?;
+        let row = rows[index].clone();
+        drop(rows);
+
+        self.deleted.remove(&index);
+        self.version = _next_version();
+        Ok(row)
+    }
+
+    pub fn vacuum(&mut self) -> usize {
+        //! Remove the soft-deleted rows for good, compact the rows and rebuild
+        //! every index from scratch.
+        //!
+        //! Returns the number of reclaimed rows.
+
+        let targets = std::mem::take(&mut self.deleted);
+        self.version = _next_version();
+
+        {
+            let mut rows = self.rows.write().unwrap();
+            let mut position = 0;
+            rows.retain(|_| {
+                let keep = !targets.contains(&position);
+                position += 1;
+                keep
+            });
+            rows.shrink_to_fit();
+        }
+
+        self._rebuild_indexes();
+        targets.len()
+    }
+
+    pub fn snapshot(&self) -> TableSnapshot {
+        //! Copy the current state of the table, see [`TableSnapshot`].
+
+        TableSnapshot {
+            name: self.name.clone(),
+            schema: self.schema.read().unwrap().clone(),
+            rows: self.rows.read().unwrap().clone(),
+            index: self.index.clone(),
+            unique_indexes: self.unique_indexes.clone(),
+            ordered_indexes: self.ordered_indexes.clone(),
+            auto_increment: self.auto_increment,
+            deleted: self.deleted.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: TableSnapshot) {
+        //! Bring the table back to the state of the `snapshot`.
+        //!
+        //! The schema and rows are written into the existing handles, so
+        //! readers taken before the restore see the restored data as well.
+
+        self.name = snapshot.name;
+        *self.schema.write().unwrap() = snapshot.schema;
+        *self.rows.write().unwrap() = snapshot.rows;
+        self.index = snapshot.index;
+        self.unique_indexes = snapshot.unique_indexes;
+        self.ordered_indexes = snapshot.ordered_indexes;
+        self.auto_increment = snapshot.auto_increment;
+        self.deleted = snapshot.deleted;
+        self.version = _next_version();
+    }
+
+    pub fn export_csv(&self, path: &Path) -> Result<(), String> {
+        //! Write the whole table to a CSV file, see [`TableReader::export_csv`].
+
+        self.reader().export_csv(path)
+    }
+
+    pub fn export_csv_streaming(&self, mut writer: impl Write) -> Result<(), String> {
+        //! Write the whole table as CSV to `writer`, one row at a time.
+        //!
+        //! Unlike [`Table::export_csv`], no record is kept after it is written,
+        //! so memory stays bounded by the largest row whatever the size of the
+        //! table. The rows stay read locked until the export is done. Every row
+        //! is a separate write, so wrap files in a [`std::io::BufWriter`].
+
+        let schema = self.schema.read().unwrap();
+        let headers: Vec<Option<&str>> = schema
+            .get_vec()
+            .iter()
+            .map(|(col, _)| Some(col.as_str()))
+            .collect();
+        csv::write_record(&mut writer, &headers)?;
+
+        let rows = self.rows.read().unwrap();
+        for (index, row) in rows.iter().enumerate() {
+            if self.deleted.contains(&index) {
+                continue;
+            }
+
+            let cells: Vec<Option<Cow<str>>> =
+                (0..headers.len()).map(|index| row.get_str(index)).collect();
+            let fields: Vec<Option<&str>> = cells.iter().map(|cell| cell.as_deref()).collect();
+            csv::write_record(&mut writer, &fields)?;
+        }
+
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to write csv: {}", e))
+    }
+
+    pub fn to_sql_dump(&self) -> String {
+        //! Write the table as SQL: a `CREATE TABLE` statement with its keys,
+        //! followed by one `INSERT` statement per row.
+        //!
+        //! NULL cells are written as `NULL` literals, so they stay apart from
+        //! empty texts.
+
+        let schema = self.schema.read().unwrap();
+        let rows = self.rows.read().unwrap();
+
+        let mut definitions = vec![];
+        let mut foreign_keys = vec![];
+
+        for (index, (col_name, col_info)) in schema.get_vec().iter().enumerate() {
+            let mut definition = match (&col_info.datatype, col_info.max_limit) {
+                (DataType::Number, _) => format!("{} INT", col_name),
+                (DataType::Text, Some(limit)) => format!("{} VARCHAR({})", col_name, limit),
+                (DataType::Text, None) => format!("{} VARCHAR", col_name),
+                (DataType::Date, _) => format!("{} DATE", col_name),
+                (DataType::Json, _) => format!("{} JSON", col_name),
+                (DataType::Uuid, _) => format!("{} UUID", col_name),
+                (DataType::Bool, _) => format!("{} BOOLEAN", col_name),
+                (DataType::Decimal(precision, scale), _) => {
+                    format!("{} DECIMAL({},{})", col_name, precision, scale)
+                }
+                (DataType::Enum(values), _) => format!(
+                    "{} ENUM({})",
+                    col_name,
+                    values
+                        .iter()
+                        .map(|value| format!("'{}'", value.replace('\'', "''")))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            };
+
+            if col_info.nullable {
+                definition.push_str(" NULL");
+            }
+            if col_info.unique {
+                definition.push_str(" UNIQUE");
+            }
+            if let Some(expression) = &col_info.check {
+                definition.push_str(&format!(" CHECK ({})", expression));
+            }
+            if matches!(self.auto_increment, Some((col_index, _)) if col_index == index) {
+                definition.push_str(" AUTO_INCREMENT");
+            }
+            definitions.push(definition);
+
+            if let Some((table_name, column_name)) = col_info.foreign_key() {
+                foreign_keys.push(format!(
+                    "FOREIGN KEY ({}) REFERENCES {}({})",
+                    col_name, table_name, column_name
+                ));
+            }
+        }
+
+        if !self.primary_key_columns.is_empty() {
+            let key_columns: Vec<&str> = self
+                .primary_key_columns
+                .iter()
+                .filter_map(|index| schema.get(*index).map(|(col_name, _)| col_name.as_str()))
+                .collect();
+            definitions.push(format!("PRIMARY KEY ({})", key_columns.join(", ")));
+        }
+        definitions.extend(foreign_keys);
+
+        let mut statements = vec![format!(
+            "CREATE TABLE {} ({});",
+            self.name,
+            definitions.join(", ")
+        )];
+
+        let rows = rows
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.deleted.contains(index))
+            .map(|(_, row)| row);
+
+        for row in rows {
+            let values: Vec<String> = row
+                .0
+                .iter()
+                .map(|value| match value {
+                    Value::Null => "NULL".to_string(),
+                    Value::Text(text) => {
+                        format!("'{}'", text.replace('\\', "\\\\").replace('\'', "''"))
+                    }
+                    value => value.to_string(),
+                })
+                .collect();
+            statements.push(format!(
+                "INSERT INTO {} VALUES ({});",
+                self.name,
+                values.join(", ")
+            ));
+        }
+
+        statements.join("\n")
+    }
+
     pub fn reader(&self) -> TableReader {
         //! Get a reader for the table to perform read queries.
         //!
         //! Creates asynchronous copies of the schema and rows so
         //! multiple reads can be performed also enabling a locked
         //! write.
+        //!
+        //! Soft-deleted rows are left out, see [`TableReader::with_deleted`].
+
+        if self.deleted.is_empty() {
+            return TableReader {
+                schema: Arc::clone(&self.schema),
+                rows: Arc::clone(&self.rows),
+                all_rows: None,
+            };
+        }
+
+        let rows = self
+            .rows
+            .read()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.deleted.contains(index))
+            .map(|(_, row)| row.clone())
+            .collect();
 
         TableReader {
             schema: Arc::clone(&self.schema),
-            rows: Arc::clone(&self.rows),
+            rows: Arc::new(RwLock::new(rows)),
+            all_rows: Some(Arc::clone(&self.rows)),
+        }
+    }
+
+    pub fn create_ordered_index(&mut self, col_name: &str) -> Result<(), String> {
+        //! Keep an ordered index over the numeric column `col_name`, so range
+        //! lookups through [`Table::range_reader`] skip the full scan.
+        //!
+        //! Creating an index that already exists does nothing.
+
+        let col_index = {
+            let schema = self.schema.read().unwrap();
+            let col_index = schema
+                .column_index(col_name)
+                .ok_or_else(|| schema.column_not_found(col_name))?;
+
+            let (_, col_info) = schema.get(col_index).unwrap();
+            if !matches!(col_info.datatype, DataType::Number) {
+                return Err(format!(
+                    "invalid index on column {}: only allowed on a num column",
+                    col_name
+                ));
+            }
+            col_index
+        };
+
+        if let Entry::Vacant(entry) = self.ordered_indexes.entry(col_index) {
+            entry.insert(OrderedIndex::new());
+            self._rebuild_indexes();
         }
+
+        Ok(())
+    }
+
+    pub fn has_ordered_index(&self, col_name: &str) -> bool {
+        let schema = self.schema.read().unwrap();
+        schema
+            .column_index(col_name)
+            .is_some_and(|col_index| self.ordered_indexes.contains_key(&col_index))
+    }
+
+    pub fn range_reader(&self, col_name: &str, low: i64, high: i64) -> Option<TableReader> {
+        //! Get a reader over the rows whose `col_name` value lies between `low`
+        //! and `high`, both inclusive, looked up through the column's ordered index.
+        //!
+        //! Returns [`None`] if the column has no ordered index.
+
+        let col_index = self.schema.read().unwrap().column_index(col_name)?;
+        let ordered_index = self.ordered_indexes.get(&col_index)?;
+
+        let rows = self.rows.read().unwrap();
+        let rows = ordered_index
+            .range(low, high)
+            .into_iter()
+            .filter(|row_index| !self.deleted.contains(row_index))
+            .filter_map(|row_index| rows.get(row_index).cloned())
+            .collect();
+
+        Some(TableReader {
+            schema: Arc::clone(&self.schema),
+            rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
+        })
     }
 
     pub(crate) fn update_foreign_key_index(&mut self, schema_index: usize, key_index: usize) {
@@ -588,6 +1652,15 @@ impl Table {
         schema.update_foreign_key_index(schema_index, key_index);
     }
 
+    pub(crate) fn update_foreign_key_parent(
+        &mut self,
+        schema_index: usize,
+        parent: &Arc<RwLock<Table>>,
+    ) {
+        let mut schema = self.schema.write().unwrap();
+        schema.update_foreign_key_parent(schema_index, parent);
+    }
+
     pub fn perform_aggregate(&self, func_vec: &Vec<SelectColumn>) -> Result<TableReader, String> {
         //! Perform all aggregate functions, create a single row [`TableReader`]
         //! for showing results.
@@ -605,41 +1678,16 @@ impl Table {
 
         let mut result = TableReader::new();
 
-        let rows = {
-            let _rl = self.rows.read().unwrap();
-            let _r = _rl.clone();
-            _r
-        };
+        let rows = self.reader().scan();
 
         for aggr in func_vec.iter() {
             if let SelectColumn::Function {
                 name, args, alias, ..
             } = aggr
             {
-                let mut aggr_args = vec![];
+                let aggr_args = _resolve_aggregate_args(&self.schema.read().unwrap(), name, args)?;
 
-                for arg in args.iter() {
-                    match arg {
-                        FunctionArg::Wildcard => {
-                            // Process the command for all columns, no distinction
-                            aggr_args.push("*".to_string());
-                        }
-                        FunctionArg::Column(column) => {
-                            // Process the command for a set of columns
-                            let col_index = {
-                                let _s = self.schema.read().unwrap();
-                                _s.get_vec()
-                                    .iter()
-                                    .position(|(col_name, _)| col_name == column)
-                            }
-                            .unwrap();
-
-                            aggr_args.push(col_index.to_string());
-                        }
-                    }
-                }
-
-                let aggr_value = aggregators::run(name, &aggr_args, &rows)?;
+                let aggr_value = aggregators::evaluate(name, &aggr_args, &rows)?;
                 result = result.add_column(
                     (
                         alias.clone().unwrap_or(name.clone()),
@@ -652,6 +1700,205 @@ impl Table {
 
         Ok(result)
     }
+
+    pub fn perform_group_aggregate(
+        &self,
+        filter: Option<&dyn Fn(&Row) -> bool>,
+        group_columns: &[String],
+        select_columns: &[SelectColumn],
+    ) -> Result<TableReader, String> {
+        //! Filter, group and aggregate the rows in a single scan of the table.
+        //!
+        //! Gives the same result as [`TableReader::filter`] followed by
+        //! [`TableReader::group_by`], but no rows are copied on the way: each
+        //! matching row is fed straight into the [`aggregators::Accumulator`]s of
+        //! its group, and only the group keys and the running values are kept.
+
+        let schema = self.schema.read().unwrap();
+        let plan = GroupPlan::new(&schema, group_columns, select_columns)?;
+
+        let mut empty_accumulators = vec![];
+
+        for output in plan.outputs.iter() {
+            if let GroupOutput::Aggregate(name, args) = output {
+                empty_accumulators.push(aggregators::Accumulator::new(name, args)?);
+            }
+        }
+
+        let mut groups: IndexMap<Vec<Option<String>>, Vec<aggregators::Accumulator>> =
+            IndexMap::new();
+
+        for (index, row) in self.rows.read().unwrap().iter().enumerate() {
+            if self.deleted.contains(&index) {
+                continue;
+            }
+            if let Some(filter) = filter
+                && !filter(row)
+            {
+                continue;
+            }
+
+            let accumulators = groups
+                .entry(plan.group_key(row))
+                .or_insert_with(|| empty_accumulators.clone());
+
+            for accumulator in accumulators.iter_mut() {
+                accumulator.update(row)?;
+            }
+        }
+
+        let mut result_rows = vec![];
+
+        for (group_key, accumulators) in groups.iter() {
+            let mut accumulators = accumulators.iter();
+            let mut cells = vec![];
+
+            for output in plan.outputs.iter() {
+                match output {
+                    GroupOutput::Key(position) => cells.push(group_key[*position].clone()),
+                    GroupOutput::Aggregate(..) => {
+                        cells.push(accumulators.next().unwrap().evaluate()?);
+                    }
+                }
+            }
+
+            result_rows.push(Row::parse(&plan.schema, cells));
+        }
+
+        Ok(TableReader {
+            schema: Arc::new(RwLock::new(plan.schema)),
+            rows: Arc::new(RwLock::new(result_rows)),
+            all_rows: None,
+        })
+    }
+}
+
+/// A single output column of a grouped query.
+enum GroupOutput {
+    /// The value of the group column at this position of the group key.
+    Key(usize),
+    /// An aggregator name with its resolved arguments.
+    Aggregate(String, Vec<String>),
+}
+
+/// The resolved shape of a grouped query, shared by the staged
+/// [`TableReader::group_by`] and the single pass [`Table::perform_group_aggregate`].
+struct GroupPlan {
+    group_indices: Vec<usize>,
+    outputs: Vec<GroupOutput>,
+    schema: Schema,
+}
+
+impl GroupPlan {
+    fn new(
+        schema: &Schema,
+        group_columns: &[String],
+        select_columns: &[SelectColumn],
+    ) -> Result<GroupPlan, String> {
+        let group_indices = group_columns
+            .iter()
+            .map(|column| {
+                schema
+                    .column_index(column)
+                    .ok_or_else(|| schema.column_not_found(column))
+            })
+            .collect::<Result<Vec<usize>, EngineError>>()?;
+
+        let mut outputs = vec![];
+        let mut result_schema = vec![];
+
+        for column in select_columns.iter() {
+            match column {
+                SelectColumn::Column { name, alias } => {
+                    let position = group_columns
+                        .iter()
+                        .position(|group_column| group_column == name)
+                        .ok_or_else(|| format!("invalid column {}: not in GROUP BY", name))?;
+                    let col_info = schema.get(group_indices[position]).unwrap().1.clone();
+
+                    outputs.push(GroupOutput::Key(position));
+                    result_schema.push((alias.clone().unwrap_or(name.clone()), col_info));
+                }
+                SelectColumn::Function {
+                    name, args, alias, ..
+                } => {
+                    outputs.push(GroupOutput::Aggregate(
+                        name.clone(),
+                        _resolve_aggregate_args(schema, name, args)?,
+                    ));
+                    result_schema.push((
+                        alias.clone().unwrap_or(name.clone()),
+                        ColumnInformation::default(),
+                    ));
+                }
+            }
+        }
+
+        Ok(GroupPlan {
+            group_indices,
+            outputs,
+            schema: Schema::new(result_schema),
+        })
+    }
+
+    fn group_key(&self, row: &Row) -> Vec<Option<String>> {
+        self.group_indices
+            .iter()
+            .map(|&index| row.0[index].to_cell())
+            .collect()
+    }
+}
+
+fn _resolve_aggregate_args(
+    schema: &Schema,
+    name: &str,
+    args: &[FunctionArg],
+) -> Result<Vec<String>, String> {
+    //! Turn the arguments of the aggregator `name` into what the aggregators expect,
+    //! which is a wildcard or the index of the column inside the `schema`.
+    //!
+    //! A collated column is handed over as its index followed by the collation name,
+    //! and a distinct marker as the [`aggregators::DISTINCT_TOKEN`]. Literals, such
+    //! as a separator, may only follow a column and are quoted like scalar literals.
+    //! Numeric columns compare by number, see [`aggregators::numeric_collation`].
+
+    let column_index = |column: &String| {
+        schema
+            .column_index(column)
+            .map(|col_index| col_index.to_string())
+            .ok_or_else(|| schema.column_not_found(column))
+    };
+
+    let mut resolved = vec![];
+
+    for arg in args {
+        match arg {
+            FunctionArg::Wildcard => resolved.push("*".to_string()),
+            FunctionArg::Distinct => resolved.push(aggregators::DISTINCT_TOKEN.to_string()),
+            FunctionArg::Column(column) => resolved.push(column_index(column)?),
+            FunctionArg::Collated(column, collation) => {
+                resolved.push(column_index(column)?);
+                resolved.push(collation.clone());
+            }
+            FunctionArg::Literal(value) if resolved.is_empty() => {
+                return Err(format!(
+                    "invalid argument '{}': aggregators take in a column first",
+                    value
+                ));
+            }
+            FunctionArg::Literal(value) => resolved.push(scalars::literal_argument(value)),
+        }
+    }
+
+    if let Some(FunctionArg::Column(column) | FunctionArg::Collated(column, _)) = args.first()
+        && let Some((_, col_info)) = schema
+            .column_index(column)
+            .and_then(|col_index| schema.get(col_index))
+    {
+        aggregators::numeric_collation(name, col_info.datatype(), &mut resolved);
+    }
+
+    Ok(resolved)
 }
 
 /// Serializable interface for the [`Table`] struct.
@@ -663,22 +1910,73 @@ impl Table {
         TableData {
             name: self.name.clone(),
             schema: self.schema.read().unwrap().clone(),
-            rows: self.rows.read().unwrap().clone(),
+            rows: self.rows.read().unwrap().iter().map(Row::cells).collect(),
             primary_key_columns: self.primary_key_columns.clone(),
             is_indexed: self.is_indexed,
             index: self.index.clone(),
+            unique_indexes: self.unique_indexes.clone(),
+            ordered_indexes: self.ordered_indexes.clone(),
+            auto_increment: self.auto_increment,
+            deleted: self.deleted.clone(),
         }
     }
 
     pub fn from_data(data: TableData) -> Self {
-        Table {
+        //! Restore a table from its serialized form.
+        //!
+        //! The indexes are rebuilt from the rows, so files written with an older
+        //! key encoding load correctly.
+
+        let checks = Self::_compile_checks(&data.name, &data.schema).unwrap_or_else(|e| {
+            warn!("warn: checks of table {} dropped, {}", data.name, e);
+            vec![]
+        });
+
+        let rows = data
+            .rows
+            .into_iter()
+            .map(|cells| Row::parse(&data.schema, cells))
+            .collect();
+
+        let mut table = Table {
             name: data.name,
             schema: Arc::new(RwLock::new(data.schema)),
-            rows: Arc::new(RwLock::new(data.rows)),
+            rows: Arc::new(RwLock::new(rows)),
             primary_key_columns: data.primary_key_columns,
             is_indexed: data.is_indexed,
             index: data.index,
-        }
+            unique_indexes: data.unique_indexes,
+            ordered_indexes: data.ordered_indexes,
+            auto_increment: data.auto_increment,
+            deleted: data.deleted,
+            checks,
+            version: _next_version(),
+        };
+
+        table._rebuild_indexes();
+        table
+    }
+}
+
+fn _next_version() -> u64 {
+    //! Hand out a [`Table::version`] no table had before.
+
+    TABLE_VERSIONS.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+fn _ordered_value(row: &Row, col_index: usize) -> Option<i64> {
+    //! Get the key an ordered index stores for the cell of `row` at `col_index`.
+
+    row.get_str(col_index)?.parse::<i64>().ok()
+}
+
+pub(super) fn _empty_as_null(value: &str) -> Option<String> {
+    //! Read an empty value of the string based API as a NULL.
+
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
     }
 }
 
@@ -709,6 +2007,7 @@ impl TableReader {
         TableReader {
             schema: Arc::new(schema),
             rows: Arc::new(rows),
+            all_rows: None,
         }
     }
 
@@ -719,58 +2018,69 @@ impl TableReader {
     pub fn add_column(
         self,
         (col_name, col_info): (String, ColumnInformation),
-        value: String,
+        value: Option<String>,
     ) -> TableReader {
         //! Used by the aggregator to add a column to its temporary table.
+        //!
+        //! A [`None`] value adds a NULL cell.
 
         let mut schema = self.schema.write().unwrap();
         let mut rows = self.rows.write().unwrap();
 
-        println!("Adding {} to result row.", value);
+        if !is_quiet() {
+            println!(
+                "Adding {} to result row.",
+                value.as_deref().unwrap_or(NULL_TOKEN)
+            );
+        }
 
-        schema.get_vec_mut().push((col_name, col_info));
+        let value = Value::parse(col_info.datatype(), value.as_deref());
+        schema.push(col_name, col_info);
 
         if rows.len() == 0 {
             rows.push(Row(vec![]));
         }
 
         for row in rows.iter_mut() {
-            row.0.push(Some(value.clone()));
+            row.0.push(value.clone());
         }
 
         TableReader {
             schema: self.schema.clone(),
             rows: self.rows.clone(),
+            all_rows: None,
         }
     }
 
     pub fn add_column_scalar(
         self,
         (col_name, col_info): (String, ColumnInformation),
-        scalar: fn(&Vec<String>, &Row) -> Result<String, String>,
+        scalar: &dyn Scalar,
         args: &Vec<String>,
-    ) -> TableReader {
+    ) -> Result<TableReader, String> {
         //! Used by the scalar to add a column to its temporary table.
         //!
-        //! Takes a pointer to the scalar function and applies it over all
-        //! rows.
+        //! Takes a handle to the scalar and applies it over all rows. Fails
+        //! with the scalar's error on the first row it cannot compute.
 
         // TODO: FIX THIS. FIND A WAY TO RUN FUNCTIONS AND OBTAIN VALUES TO ADD.
 
         let mut schema = self.schema.write().unwrap();
         let mut rows = self.rows.write().unwrap();
 
-        schema.get_vec_mut().push((col_name, col_info));
+        let datatype = col_info.datatype().clone();
+        schema.push(col_name, col_info);
 
         for row in rows.iter_mut() {
-            let value = scalar(args, row);
-            row.0.push(Some(value.unwrap()));
+            let value = scalar.evaluate(args, row)?;
+            row.0.push(Value::parse(&datatype, value.as_deref()));
         }
 
-        TableReader {
+        Ok(TableReader {
             schema: self.schema.clone(),
             rows: self.rows.clone(),
-        }
+            all_rows: None,
+        })
     }
 
     pub fn scan(&self) -> Vec<Row> {
@@ -780,6 +2090,200 @@ impl TableReader {
         rows.clone()
     }
 
+    pub fn scan_named(&self) -> Vec<HashMap<String, Option<String>>> {
+        //! Returns a copy of all the rows, each as a map from column name to cell,
+        //! so callers don't have to track column positions.
+        //!
+        //! A NULL cell maps to [`None`].
+
+        let schema = self.schema.read().unwrap();
+        let rows = self.rows.read().unwrap();
+
+        rows.iter()
+            .map(|row| {
+                schema
+                    .get_vec()
+                    .iter()
+                    .map(|(col_name, _)| col_name.clone())
+                    .zip(row.0.iter().map(Value::to_cell))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn for_each<F>(&self, f: F)
+    where
+        F: FnMut(&Row),
+    {
+        //! Visit every row in order without copying them, unlike [`TableReader::scan`].
+        //!
+        //! The rows stay read locked while `f` runs, so `f` must not write to the table.
+
+        let rows = self.rows.read().unwrap();
+        rows.iter().for_each(f);
+    }
+
+    pub fn scan_where<F>(&self, columns: Vec<String>, pred: F) -> Result<Vec<Row>, String>
+    where
+        F: Fn(&Row) -> bool,
+    {
+        //! Return the `columns` of the rows matching `pred`, in a single pass.
+        //!
+        //! The predicate sees the full rows, before the projection. Same as
+        //! `filter(pred)?.select(columns)?.scan()`, but only the projected cells
+        //! of matching rows are copied.
+        //!
+        //! Fails if a column does not exist.
+
+        let indices = {
+            let schema = self.schema.read().unwrap();
+            columns
+                .iter()
+                .map(|column| {
+                    schema
+                        .column_index(column)
+                        .ok_or_else(|| schema.column_not_found(column))
+                })
+                .collect::<Result<Vec<usize>, EngineError>>()?
+        };
+
+        let rows = self.rows.read().unwrap();
+        Ok(rows
+            .iter()
+            .filter(|row| pred(row))
+            .map(|row| Row(indices.iter().map(|&index| row.0[index].clone()).collect()))
+            .collect())
+    }
+
+    pub fn borrow_rows(&self) -> RwLockReadGuard<'_, Vec<Row>> {
+        //! Borrow the rows without copying them. They stay read locked until the
+        //! returned guard is dropped.
+
+        self.rows.read().unwrap()
+    }
+
+    pub fn aggregate(&self, name: &str, args: Vec<String>) -> Result<String, String> {
+        //! Run the aggregator `name` over the reader's rows.
+        //!
+        //! The `args` are what the aggregator expects, where the column is given
+        //! by its name or its index inside this reader's schema.
+
+        let schema = self.schema.read().unwrap();
+        let rows = self.rows.read().unwrap();
+        aggregators::run_with_schema(&name.to_string(), &args, &schema, &rows)
+    }
+
+    pub fn grid(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        //! Extract the reader's data as plain strings, without any formatting.
+        //!
+        //! Returns the header names and a rectangular grid of cells, one vector
+        //! per row. NULL cells are rendered with the [`NULL_TOKEN`].
+
+        let schema = self.schema.read().unwrap();
+        let headers: Vec<String> = schema
+            .get_vec()
+            .iter()
+            .map(|(col, _)| col.clone())
+            .collect();
+
+        let rows = self.rows.read().unwrap();
+        let grid = rows
+            .iter()
+            .map(|row| {
+                (0..headers.len())
+                    .map(|index| match row.0.get(index) {
+                        Some(value) => value.to_string(),
+                        None => NULL_TOKEN.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        (headers, grid)
+    }
+
+    pub fn export_csv(&self, path: &Path) -> Result<(), String> {
+        //! Write the reader's data to a CSV file at `path`.
+        //!
+        //! The first line holds the column names from the schema, followed
+        //! by one line per row. NULL cells are written as empty fields and
+        //! values containing commas, quotes or line breaks are quoted.
+
+        let schema = self.schema.read().unwrap();
+        let headers: Vec<Option<&str>> = schema
+            .get_vec()
+            .iter()
+            .map(|(col, _)| Some(col.as_str()))
+            .collect();
+
+        let rows = self.rows.read().unwrap();
+        let mut records = vec![csv::encode_record(&headers)];
+
+        for row in rows.iter() {
+            let cells: Vec<Option<Cow<str>>> =
+                (0..headers.len()).map(|index| row.get_str(index)).collect();
+            let fields: Vec<Option<&str>> = cells.iter().map(|cell| cell.as_deref()).collect();
+            records.push(csv::encode_record(&fields));
+        }
+
+        csv::write_file(path, &records)
+    }
+
+    pub fn to_json(&self) -> String {
+        //! Serialize the reader's rows as a JSON array of objects keyed by
+        //! column name, keeping the schema's column order.
+        //!
+        //! NULL cells become `null` and cells of NUMBER columns are emitted
+        //! as JSON numbers; everything else is a JSON string, DECIMAL cells
+        //! included so they keep their exact digits, see [`super::Value::to_json`].
+
+        let schema = self.schema.read().unwrap();
+        let rows = self.rows.read().unwrap();
+
+        let objects: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = schema
+                    .get_vec()
+                    .iter()
+                    .zip(row.0.iter())
+                    .map(|((col, _), value)| {
+                        format!(
+                            "{}:{}",
+                            serde_json::Value::from(col.as_str()),
+                            value.to_json()
+                        )
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            })
+            .collect();
+
+        format!("[{}]", objects.join(","))
+    }
+
+    pub fn lazy(&self) -> LazyReader<'_> {
+        //! Start a [`LazyReader`] chain over the rows of this reader.
+
+        LazyReader::new(self)
+    }
+
+    pub fn with_deleted(self) -> TableReader {
+        //! Include the soft-deleted rows of the table this reader was taken from.
+        //!
+        //! Only a reader straight from [`Table::reader`] knows about them, the
+        //! readers chained from it return themselves unchanged.
+
+        match self.all_rows {
+            Some(all_rows) => TableReader {
+                schema: self.schema,
+                rows: all_rows,
+                all_rows: None,
+            },
+            None => self,
+        }
+    }
+
     pub fn filter<F>(self, filter: F) -> Result<TableReader, String>
     where
         F: Fn(&Row) -> bool,
@@ -795,10 +2299,38 @@ impl TableReader {
         Ok(TableReader {
             schema: self.schema,
             rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
         })
     }
 
-    pub fn select(self, fields: Vec<String>) -> Result<TableReader, String> {
+    pub fn alias(self, aliases: &[Option<String>]) -> Result<TableReader, String> {
+        //! Rename the leading columns of the reader, where the `aliases` hold the new
+        //! name of the column at the same position, or [`None`] to keep its name.
+        //!
+        //! Fails if there are more aliases than columns.
+
+        {
+            let mut schema = self.schema.write().unwrap();
+
+            if aliases.len() > schema.len() {
+                return Err(format!(
+                    "invalid aliases: {} given for {} columns",
+                    aliases.len(),
+                    schema.len()
+                ));
+            }
+
+            for (index, alias) in aliases.iter().enumerate() {
+                if let Some(alias) = alias {
+                    schema.rename(index, alias.clone());
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    pub fn select(self, fields: Vec<String>) -> Result<TableReader, EngineError> {
         //! Get specific columns from the table and return that table.
         //!
         //! Returns a table [`TableReader`] object as a projection of the current
@@ -806,16 +2338,14 @@ impl TableReader {
 
         let schema = self.schema.read().unwrap();
 
-        let indices: Vec<usize> = fields
+        let indices = fields
             .iter()
             .map(|field| {
                 schema
-                    .get_vec()
-                    .iter()
-                    .position(|(name, _)| name == field)
-                    .expect(format!("invalid column {}: does not exist", field).as_str())
+                    .column_index(field)
+                    .ok_or_else(|| schema.column_not_found(field))
             })
-            .collect();
+            .collect::<Result<Vec<usize>, EngineError>>()?;
 
         // TODO: Include alias into the schema, replace the original name, if possible.
         let new_schema: Schema = Schema::new(
@@ -839,6 +2369,7 @@ impl TableReader {
         Ok(TableReader {
             schema: Arc::new(RwLock::new(new_schema)),
             rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
         })
     }
 
@@ -846,7 +2377,7 @@ impl TableReader {
         //! Order the table by the index, in ascending or descending order.
         //!
         //! Takes in the sort index which is the index of the column, and the ascending
-        //! order flag.
+        //! order flag. Keys later in the list break ties of the ones before them.
         //!
         //! Returns a new [`TableReader`] with sorted rows, consuming itself.
 
@@ -854,12 +2385,34 @@ impl TableReader {
             let _rl = self.rows.write().unwrap();
             _rl.clone()
         };
+        let numeric: Vec<bool> = {
+            let schema = self.schema.read().unwrap();
+            sort_index
+                .iter()
+                .map(|(col_index, _)| {
+                    schema
+                        .get(*col_index)
+                        .is_some_and(|(_, col_info)| col_info.datatype.is_numeric())
+                })
+                .collect()
+        };
 
+        // A stable sort, so rows equal on every key keep their order
         rows.sort_by(|a, b| {
             let mut ordering = std::cmp::Ordering::Equal;
 
-            for (col_index, is_ascending) in sort_index.iter() {
-                let cmp = a.0.get(*col_index).cmp(&b.0.get(*col_index));
+            for ((col_index, is_ascending), numeric) in sort_index.iter().zip(&numeric) {
+                let (left, right) = (a.get_str(*col_index), b.get_str(*col_index));
+                // NULLs come first, and numbers compare by value
+                let cmp = match (&left, &right) {
+                    (Some(left), Some(right)) if *numeric => {
+                        match (left.parse::<f64>(), right.parse::<f64>()) {
+                            (Ok(left), Ok(right)) => left.total_cmp(&right),
+                            _ => left.cmp(right),
+                        }
+                    }
+                    _ => left.cmp(&right),
+                };
                 ordering = if *is_ascending { cmp } else { cmp.reverse() };
 
                 if ordering != std::cmp::Ordering::Equal {
@@ -873,6 +2426,7 @@ impl TableReader {
         return TableReader {
             schema: self.schema,
             rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
         };
     }
 
@@ -893,6 +2447,7 @@ impl TableReader {
             Ok(TableReader {
                 schema: self.schema,
                 rows: Arc::new(RwLock::new(limited_rows)),
+                all_rows: None,
             })
         }
     }
@@ -916,10 +2471,140 @@ impl TableReader {
             Ok(TableReader {
                 schema: self.schema,
                 rows: Arc::new(RwLock::new(offsetted_rows)),
+                all_rows: None,
             })
         }
     }
 
+    pub fn distinct(self) -> TableReader {
+        //! Drop the rows that repeat an earlier row on every column, keeping the
+        //! first one of each.
+        //!
+        //! Returns a new [`TableReader`] with the unique rows, in their order.
+
+        let mut seen: HashSet<Vec<Option<String>>> = HashSet::new();
+        let rows = self
+            .rows
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|row| seen.insert(row.cells()))
+            .cloned()
+            .collect();
+
+        TableReader {
+            schema: self.schema,
+            rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
+        }
+    }
+
+    pub fn group_by(
+        self,
+        group_columns: &[String],
+        select_columns: &[SelectColumn],
+    ) -> Result<TableReader, String> {
+        //! Partition the rows by the values of the `group_columns` and produce one
+        //! row per group.
+        //!
+        //! Every [`SelectColumn::Column`] in `select_columns` is expected to be a
+        //! group column and gets the value of its group, while every aggregator is
+        //! run over the rows of that group only. Groups appear in the order their
+        //! first row appears in the reader.
+        //!
+        //! This is the staged path, it collects a copy of every group's rows before
+        //! aggregating. See [`Table::perform_group_aggregate`] for the single pass.
+
+        let schema = self.schema.read().unwrap();
+        let plan = GroupPlan::new(&schema, group_columns, select_columns)?;
+
+        let mut groups: IndexMap<Vec<Option<String>>, Vec<Row>> = IndexMap::new();
+
+        for row in self.rows.read().unwrap().iter() {
+            groups
+                .entry(plan.group_key(row))
+                .or_default()
+                .push(row.clone());
+        }
+
+        let mut result_rows = vec![];
+
+        for (group_key, group_rows) in groups.iter() {
+            let mut cells = vec![];
+
+            for output in plan.outputs.iter() {
+                match output {
+                    GroupOutput::Key(position) => cells.push(group_key[*position].clone()),
+                    GroupOutput::Aggregate(name, args) => {
+                        cells.push(aggregators::evaluate(name, args, group_rows)?);
+                    }
+                }
+            }
+
+            result_rows.push(Row::parse(&plan.schema, cells));
+        }
+
+        Ok(TableReader {
+            schema: Arc::new(RwLock::new(plan.schema)),
+            rows: Arc::new(RwLock::new(result_rows)),
+            all_rows: None,
+        })
+    }
+
+    pub fn truncate(self, columns: usize) -> TableReader {
+        //! Keep only the first number of `columns` of the reader, dropping the rest.
+        //!
+        //! Returns a new [`TableReader`] with the remaining columns.
+
+        let mut schema = self.schema.read().unwrap().clone();
+        schema.truncate(columns);
+
+        let rows = self
+            .rows
+            .read()
+            .unwrap()
+            .iter()
+            .map(|row| Row(row.0.iter().take(columns).cloned().collect()))
+            .collect();
+
+        TableReader {
+            schema: Arc::new(RwLock::new(schema)),
+            rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
+        }
+    }
+
+    pub fn skip(self, columns: usize) -> TableReader {
+        //! Drop the first number of `columns` of the reader, keeping the rest.
+        //!
+        //! Returns a new [`TableReader`] with the remaining columns.
+
+        let schema = Schema::new(
+            self.schema
+                .read()
+                .unwrap()
+                .get_vec()
+                .iter()
+                .skip(columns)
+                .cloned()
+                .collect(),
+        );
+
+        let rows = self
+            .rows
+            .read()
+            .unwrap()
+            .iter()
+            .map(|row| Row(row.0.iter().skip(columns).cloned().collect()))
+            .collect();
+
+        TableReader {
+            schema: Arc::new(RwLock::new(schema)),
+            rows: Arc::new(RwLock::new(rows)),
+            all_rows: None,
+        }
+    }
+
     pub fn perform_function(self, func_vec: &Vec<SelectColumn>) -> Result<TableReader, String> {
         //! ~Takes the `name` for the column name, an `alias` for custom display names, if specified
         //! and the `args` which are either column names or a wildcard.~
@@ -928,6 +2613,9 @@ impl TableReader {
         //!
         //! Performs function on a particular argument column of the table and appends returns the
         //! rows with data appended.
+        //!
+        //! Column arguments are handed to the scalar as the column index while literals are
+        //! quoted, see [`scalars::literal_argument`].
 
         let schema = self.schema.clone();
         let mut result = self;
@@ -939,7 +2627,7 @@ impl TableReader {
             {
                 let mut sclr_args = vec![];
 
-                for (index, arg) in args.iter().enumerate() {
+                for arg in args.iter() {
                     match arg {
                         FunctionArg::Wildcard => {
                             // Process the command for all columns, no distinction
@@ -949,22 +2637,31 @@ impl TableReader {
                             ));
                         }
                         FunctionArg::Column(column) => {
-                            // Process the command for a set of columns
-                            if index == 0 {
-                                let col_index = {
-                                    let _s = schema.read().unwrap();
-                                    _s.get_vec()
-                                        .iter()
-                                        .position(|(col_name, _)| col_name == column)
-                                }
-                                .ok_or_else(|| {
-                                    format!("Column {} does not exist. Select it first.", &column)
-                                })?;
-
-                                sclr_args.push(col_index.to_string());
-                            } else {
-                                sclr_args.push(column.clone());
+                            // Columns are handed to the scalar as their index
+                            let col_index = {
+                                let _s = schema.read().unwrap();
+                                _s.column_index(column)
                             }
+                            .ok_or_else(|| {
+                                format!("Column {} does not exist. Select it first.", &column)
+                            })?;
+
+                            sclr_args.push(col_index.to_string());
+                        }
+                        FunctionArg::Literal(value) => {
+                            sclr_args.push(scalars::literal_argument(value));
+                        }
+                        FunctionArg::Distinct => {
+                            return Err(format!(
+                                "Invalid {}; DISTINCT not allowed inside scalars.",
+                                name
+                            ));
+                        }
+                        FunctionArg::Collated(column, _) => {
+                            return Err(format!(
+                                "Invalid {}; collations are only allowed inside aggregators, on {}.",
+                                name, column
+                            ));
                         }
                     }
                 }
@@ -974,9 +2671,9 @@ impl TableReader {
                         alias.clone().unwrap_or(name.clone()),
                         ColumnInformation::default(),
                     ),
-                    scalars::get_runner(name).unwrap(),
+                    scalars::get_runner(name)?,
                     &sclr_args,
-                );
+                )?;
             }
         }
 
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use super::decimal::Decimal;
+use super::row::NULL_TOKEN;
+use super::schema::DataType;
+
+/// A single cell of a [`super::Row`].
+///
+/// Values are written as text and checked against their column's [`DataType`],
+/// then converted once, when the row is stored, so numbers do not have to be
+/// parsed again by every consumer. [`Value::to_cell`] turns a value back into
+/// text, the form rows are saved and exported in.
+///
+/// # Issues
+/// - Aggregators and scalars still read their arguments as text, through
+///   [`super::Row::get_str`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Number(i64),
+    Float(f64),
+    Decimal(Decimal),
+    Text(String),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn parse(datatype: &DataType, cell: Option<&str>) -> Value {
+        //! Read a stored cell as a value of the column's `datatype`.
+        //!
+        //! Cells of a NUMBER column that do not fit an integer are read as floats,
+        //! and as text if they are not numbers at all. DECIMAL cells are read as a
+        //! [`Decimal`], which keeps their scale and every digit, and BOOL cells as
+        //! booleans. Dates stay text, which keeps them in their canonical, ordered
+        //! format.
+
+        let Some(cell) = cell else {
+            return Value::Null;
+        };
+
+        match datatype {
+            DataType::Decimal(..) => Decimal::parse(cell)
+                .map(Value::Decimal)
+                .unwrap_or_else(|| Value::Text(cell.to_string())),
+            DataType::Number => cell
+                .parse::<i64>()
+                .map(Value::Number)
+                .or_else(|_| cell.parse::<f64>().map(Value::Float))
+                .unwrap_or_else(|_| Value::Text(cell.to_string())),
+            DataType::Bool => cell
+                .parse::<bool>()
+                .map(Value::Bool)
+                .unwrap_or_else(|_| Value::Text(cell.to_string())),
+            DataType::Text
+            | DataType::Date
+            | DataType::Json
+            | DataType::Uuid
+            | DataType::Enum(_) => Value::Text(cell.to_string()),
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        //! Borrow the text of a [`Value::Text`]. Any other variant gives [`None`].
+
+        match self {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn to_cell(&self) -> Option<String> {
+        //! Turn the value back into the form a row stores it in.
+
+        match self {
+            Value::Null => None,
+            value => Some(value.to_string()),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        //! A [`Value::Decimal`] becomes a JSON string of its exact digits, like
+        //! `"5.00"`, since a JSON number is read back as a double by most parsers.
+
+        match self {
+            Value::Null => serde_json::Value::Null,
+            Value::Number(number) => serde_json::Value::from(*number),
+            Value::Float(float) => serde_json::Value::from(*float),
+            Value::Decimal(decimal) => serde_json::Value::from(decimal.to_string()),
+            Value::Text(text) => serde_json::Value::from(text.as_str()),
+            Value::Bool(boolean) => serde_json::Value::from(*boolean),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Null => f.pad(NULL_TOKEN),
+            Value::Number(number) => f.pad(&number.to_string()),
+            Value::Float(float) => f.pad(&float.to_string()),
+            Value::Decimal(decimal) => f.pad(&decimal.to_string()),
+            Value::Text(text) => f.pad(text),
+            Value::Bool(boolean) => f.pad(&boolean.to_string()),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+fn encode_field(field: Option<&str>) -> String {
+    //! Encode a single field, quoting it when it contains a delimiter,
+    //! a quote or a line break. Quotes inside the field are doubled.
+    //! A NULL field is written as an empty field.
+
+    match field {
+        None => String::new(),
+        Some(value) if value.contains([',', '"', '\n', '\r']) => {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        }
+        Some(value) => value.to_string(),
+    }
+}
+
+pub fn encode_record(fields: &[Option<&str>]) -> String {
+    //! Encode one record as a comma separated line, without the trailing newline.
+
+    fields
+        .iter()
+        .map(|field| encode_field(*field))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+pub fn write_record(writer: &mut impl Write, fields: &[Option<&str>]) -> Result<(), String> {
+    //! Encode one record and write it to `writer` as a line of its own.
+
+    writeln!(writer, "{}", encode_record(fields)).map_err(|e| format!("Failed to write csv: {}", e))
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    //! Parse CSV text into records of plain fields.
+    //!
+    //! Quoted fields may contain commas, doubled quotes and line breaks.
+    //! Both `\n` and `\r\n` line endings are accepted, and a trailing
+    //! newline at the end of the text does not produce an empty record.
+
+    parse_with(contents, ',')
+}
+
+pub fn parse_with(contents: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    //! Parse text whose fields are separated by `delimiter`, e.g. `'\t'` for
+    //! TSV, with the same quoting rules as [`parse`].
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = contents.chars().peekable();
+    let mut line = 1;
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(ch);
+                }
+                _ => field.push(ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '"' if field.is_empty() => in_quotes = true,
+            '"' => return Err(format!("invalid csv line {}: unexpected quote", line)),
+            ch if ch == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                line += 1;
+            }
+            _ => field.push(ch),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("invalid csv line {}: unterminated quote", line));
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+pub fn write_file(path: &Path, records: &[String]) -> Result<(), String> {
+    //! Write already encoded records to a file, one per line.
+
+    let mut file = File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
+
+    for record in records {
+        writeln!(file, "{}", record).map_err(|e| format!("Failed to write file: {}", e))?;
+    }
+
+    Ok(())
+}
+
+pub fn read_file(path: &Path) -> Result<Vec<Vec<String>>, String> {
+    //! Read and parse a CSV file into records of plain fields.
+
+    read_file_with(path, ',')
+}
+
+pub fn read_file_with(path: &Path, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    //! Read and parse a file whose fields are separated by `delimiter`.
+
+    if !path.exists() {
+        return Err(format!("File does not exist: {:?}", path));
+    }
+
+    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    parse_with(&contents, delimiter)
+}
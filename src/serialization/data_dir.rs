@@ -0,0 +1,104 @@
+//! A data directory keeps every database of a registry in a file of its own.
+//!
+//! Each database is saved as `<name>.fe` in the directory, in the same JSON
+//! format the registry file uses for its databases. At startup, the directory
+//! is scanned and every database file in it is loaded back into the registry.
+//!
+//! # Issues
+//! - Dropping a database does not delete its file, so it comes back on the
+//!   next start.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::persistence::{Database, DatabaseData, DatabaseRegistry};
+
+/// The extension of the database files in a data directory.
+pub const DATABASE_FILE_EXTENSION: &str = "fe";
+
+/// What happened while loading a data directory.
+#[derive(Default)]
+pub struct DataDirReport {
+    /// The names of the databases that were loaded.
+    pub loaded: Vec<String>,
+    /// A warning for every database file that was skipped.
+    pub skipped: Vec<String>,
+}
+
+pub fn save_database(database: &Database, path: &Path) -> Result<(), String> {
+    //! Write a single database to the file at `path`.
+
+    let json = serde_json::to_string_pretty(&database.to_data())
+        .map_err(|e| format!("Serialization failed: {}", e))?;
+
+    let mut file = File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
+
+    file.write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to write to file: {}", e))
+}
+
+pub fn load_database(path: &Path) -> Result<Database, String> {
+    //! Read a single database from the file at `path`.
+
+    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let data: DatabaseData =
+        serde_json::from_str(&contents).map_err(|e| format!("Deserialization failed: {}", e))?;
+
+    Ok(Database::from_data(data))
+}
+
+pub fn save_data_dir(registry: &DatabaseRegistry, dir: &Path) -> Result<(), String> {
+    //! Write every database of the `registry` to its own file in `dir`,
+    //! creating the directory if it is missing.
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    for name in registry.get_database_names() {
+        let database = registry.get_database(&name)?;
+        let path = dir.join(format!("{}.{}", name, DATABASE_FILE_EXTENSION));
+        save_database(&database.read().unwrap(), &path)?;
+    }
+
+    Ok(())
+}
+
+pub fn load_data_dir(registry: &mut DatabaseRegistry, dir: &Path) -> Result<DataDirReport, String> {
+    //! Load every database file in `dir` into the `registry`, creating the
+    //! directory if it is missing.
+    //!
+    //! A file that can't be loaded, or holds a database the registry already
+    //! has, is skipped with a warning instead of failing the whole load. The
+    //! files are loaded in the order of their names.
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let mut paths = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().and_then(|ext| ext.to_str()) == Some(DATABASE_FILE_EXTENSION)
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut report = DataDirReport::default();
+    for path in paths {
+        let loaded = load_database(&path).and_then(|database| registry.add_database(database));
+
+        match loaded {
+            Ok(database) => report.loaded.push(database.read().unwrap().name()),
+            Err(error) => report
+                .skipped
+                .push(format!("Skipped '{}': {}", path.display(), error)),
+        }
+    }
+
+    Ok(report)
+}
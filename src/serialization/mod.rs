@@ -1,5 +1,7 @@
 use std::path::Path;
 
+pub mod csv;
+pub mod data_dir;
 pub(crate) mod deserializers;
 pub(crate) mod serializers;
 
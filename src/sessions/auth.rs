@@ -0,0 +1,111 @@
+//! Users of the engine and checking their passwords.
+//!
+//! Users live in the internal [`USERS_TABLE`] of the [`SYSTEM_DATABASE`], so they
+//! are saved along with the registry. Every user gets a random salt, and only
+//! the Argon2 hash of the salted password is stored, never the password itself.
+//!
+//! Sessions can not list, select, create or drop the system database, so the
+//! users can only be read and added through this module. It is only as safe as
+//! the registry file or data directory it is saved to.
+
+use std::sync::{Arc, RwLock};
+
+use argon2::Argon2;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{Output, PasswordHasher, SaltString};
+
+use crate::persistence::{DatabaseRegistry, Table};
+
+pub const SYSTEM_DATABASE: &str = "system";
+pub const USERS_TABLE: &str = "users";
+
+/// The error for both an unknown user and a wrong password, so a failed login
+/// does not tell which users exist.
+const LOGIN_ERROR: &str = "invalid login: wrong username or password";
+
+fn _hash_password(password: &str, salt: &SaltString) -> Result<Output, String> {
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), salt)
+        .map_err(|e| format!("err: failed to hash password: {}", e))?;
+
+    hash.hash
+        .ok_or_else(|| "err: failed to hash password: no output".to_string())
+}
+
+fn _users_table(registry: &DatabaseRegistry) -> Option<Arc<RwLock<Table>>> {
+    if !registry.exists(SYSTEM_DATABASE) {
+        return None;
+    }
+
+    let database = registry.get_database(SYSTEM_DATABASE).ok()?;
+    let database = database.read().unwrap();
+    database.get_table(USERS_TABLE)
+}
+
+pub fn has_users(registry: &DatabaseRegistry) -> bool {
+    _users_table(registry).is_some_and(|table| !table.read().unwrap().is_empty())
+}
+
+pub fn create_user(
+    registry: &mut DatabaseRegistry,
+    username: &str,
+    password: &str,
+) -> Result<(), String> {
+    //! Add a user, creating the system database and its users table on first use.
+
+    if username.is_empty() || password.is_empty() {
+        return Err("invalid user: username and password can not be empty".to_string());
+    }
+
+    let database = registry.create_database(SYSTEM_DATABASE, true)?;
+    let mut database = database.write().unwrap();
+    if !database.contains_table(USERS_TABLE) {
+        database.create_table(
+            USERS_TABLE.to_string(),
+            vec![
+                "username txt pk".to_string(),
+                "salt txt".to_string(),
+                "password_hash txt".to_string(),
+            ],
+        )?;
+    }
+
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = _hash_password(password, &salt)?;
+
+    database.insert_into_table(
+        USERS_TABLE,
+        vec![username.to_string(), salt.to_string(), hash.to_string()],
+    )?;
+    Ok(())
+}
+
+pub fn verify(registry: &DatabaseRegistry, username: &str, password: &str) -> Result<(), String> {
+    //! Check the `password` of the user `username`.
+    //!
+    //! An unknown user still has a password hashed, against a throwaway salt,
+    //! so it takes as long to refuse as a wrong password.
+
+    let row = match _users_table(registry) {
+        Some(table) => table.read().unwrap().get_row(vec![username])?,
+        None => None,
+    };
+
+    let Some((salt, stored_hash)) = row
+        .as_ref()
+        .and_then(|row| Some((row.get_str(1)?, row.get_str(2)?)))
+    else {
+        _hash_password(password, &SaltString::generate(&mut OsRng))?;
+        return Err(LOGIN_ERROR.to_string());
+    };
+
+    let salt = SaltString::from_b64(&salt).map_err(|_| LOGIN_ERROR.to_string())?;
+    let stored_hash = Output::b64_decode(&stored_hash).map_err(|_| LOGIN_ERROR.to_string())?;
+
+    // Comparing two outputs takes constant time.
+    if _hash_password(password, &salt)? == stored_hash {
+        Ok(())
+    } else {
+        Err(LOGIN_ERROR.to_string())
+    }
+}
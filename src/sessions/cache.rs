@@ -0,0 +1,122 @@
+//! A cache of the results of recent queries of a session.
+//!
+//! Results are kept under the SQL of their query, the way the parser writes it
+//! back, so spacing and keyword case don't matter. Every result remembers the
+//! [`Table::version`] of the table it was read from, and is only reused while
+//! the table still has that version. Any change to the table, from any session,
+//! gives it a new version, so a cached result never outlives the rows it holds.
+//!
+//! The cache holds up to a set number of results and forgets the least
+//! recently used one first. A cache of no results is off.
+//!
+//! [`Table::version`]: crate::persistence::Table::version
+
+use std::sync::{Arc, RwLock};
+
+use indexmap::IndexMap;
+
+use crate::persistence::{Row, Schema, TableReader};
+
+/// The rows of a query, as of the version of its table.
+struct CachedResult {
+    version: u64,
+    schema: Schema,
+    rows: Vec<Row>,
+}
+
+pub struct ResultCache {
+    capacity: usize,
+    // The least recently used result comes first
+    results: IndexMap<String, CachedResult>,
+    hits: usize,
+}
+
+impl ResultCache {
+    pub fn new(capacity: usize) -> ResultCache {
+        ResultCache {
+            capacity,
+            results: IndexMap::new(),
+            hits: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        //! Hold up to `capacity` results from now on, forgetting the least
+        //! recently used ones that no longer fit.
+
+        self.capacity = capacity;
+        self._evict();
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    pub fn hits(&self) -> usize {
+        //! The number of times a cached result was reused.
+
+        self.hits
+    }
+
+    pub fn get(&mut self, sql: &str, version: u64) -> Option<TableReader> {
+        //! Get the result of the `sql` query, if it was read from the table at
+        //! its current `version`. A result of an older version is forgotten.
+        //!
+        //! Returns a new [`TableReader`] over a copy of the cached rows.
+
+        let index = self.results.get_index_of(sql)?;
+        if self.results[index].version != version {
+            self.results.shift_remove_index(index);
+            return None;
+        }
+
+        let last = self.results.len() - 1;
+        self.results.move_index(index, last);
+        self.hits += 1;
+
+        let result = &self.results[last];
+        Some(TableReader {
+            schema: Arc::new(RwLock::new(result.schema.clone())),
+            rows: Arc::new(RwLock::new(result.rows.clone())),
+            all_rows: None,
+        })
+    }
+
+    pub fn insert(&mut self, sql: String, version: u64, reader: &TableReader) {
+        //! Keep the rows of the `reader` as the result of the `sql` query, read
+        //! from its table at `version`.
+
+        if self.capacity == 0 {
+            return;
+        }
+
+        let result = CachedResult {
+            version,
+            schema: reader.schema.read().unwrap().clone(),
+            rows: reader.rows.read().unwrap().clone(),
+        };
+        self.results.shift_remove(&sql);
+        self.results.insert(sql, result);
+        self._evict();
+    }
+
+    pub fn clear(&mut self) {
+        self.results.clear();
+    }
+
+    fn _evict(&mut self) {
+        //! Forget the least recently used results until the cache fits.
+
+        while self.results.len() > self.capacity {
+            self.results.shift_remove_index(0);
+        }
+    }
+}
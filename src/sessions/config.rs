@@ -0,0 +1,72 @@
+//! Settings of a single session that the user can change while it runs,
+//! with `SET <key> = <value>`.
+//!
+//! # Keys
+//! - `prompt`: the text shown before the cursor in the REPL.
+//! - `color`: `on` or `off`, whether the terminal output is colored.
+//! - `timing`: `on` or `off`, whether statements report their execution time.
+//! - `default_database`: the database selected again after a session reset.
+//! - `result_cache`: how many query results to keep for reuse, see
+//!   [`super::cache`]. `0`, the default, turns the cache off.
+
+pub const DEFAULT_PROMPT: &str = "ferrum";
+
+pub struct SessionConfig {
+    pub prompt: String,
+    pub color: bool,
+    pub timing: bool,
+    pub default_database: Option<String>,
+    pub result_cache: usize,
+}
+
+impl SessionConfig {
+    pub fn new() -> SessionConfig {
+        SessionConfig {
+            prompt: DEFAULT_PROMPT.to_string(),
+            color: true,
+            timing: true,
+            default_database: None,
+            result_cache: 0,
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        //! Change the setting `key` to `value`, see the module docs for the keys.
+
+        match key.to_lowercase().as_str() {
+            "prompt" if value.is_empty() => {
+                return Err("invalid prompt: it can not be empty".to_string());
+            }
+            "prompt" => self.prompt = value.to_string(),
+            "color" => self.color = Self::_parse_switch(key, value)?,
+            "timing" => self.timing = Self::_parse_switch(key, value)?,
+            "default_database" if value.is_empty() => self.default_database = None,
+            "default_database" => self.default_database = Some(value.to_string()),
+            "result_cache" => {
+                self.result_cache = value.parse().map_err(|_| {
+                    format!(
+                        "invalid {} for {}: expected a number of results",
+                        value, key
+                    )
+                })?;
+            }
+            _ => return Err(format!("invalid setting {}: no such setting exists", key)),
+        }
+
+        Ok(())
+    }
+
+    fn _parse_switch(key: &str, value: &str) -> Result<bool, String> {
+        match value.to_lowercase().as_str() {
+            "on" | "true" | "1" => Ok(true),
+            "off" | "false" | "0" => Ok(false),
+            _ => Err(format!("invalid {} for {}: expected on or off", value, key)),
+        }
+    }
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
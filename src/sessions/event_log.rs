@@ -0,0 +1,79 @@
+//! A persisted log of what happened in a session.
+//!
+//! Every [`Event`] is appended to the log file as one JSON object per line,
+//! with the time it happened and the start time of its session, so entries of
+//! several sessions can share a file and still be told apart.
+//!
+//! # Example
+//! ```text
+//! {"time":"2024-02-09 10:00:01","session_start":"2024-02-09 10:00:00","event":"connection","user":"ana"}
+//! {"time":"2024-02-09 10:00:05","session_start":"2024-02-09 10:00:00","event":"statement","statement":"DELETE FROM t","rows":2}
+//! ```
+
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::Serialize;
+
+/// Something worth remembering about a session.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event {
+    /// The session connected, as `user` if it logged in.
+    Connection { user: Option<String> },
+    /// A statement ran and affected `rows` rows.
+    Statement { statement: String, rows: usize },
+    /// A statement failed with `error`.
+    Error { statement: String, error: String },
+}
+
+/// A single line of the log.
+#[derive(Serialize)]
+struct LogEntry<'a> {
+    time: String,
+    session_start: &'a str,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+pub struct EventLog {
+    path: PathBuf,
+}
+
+impl EventLog {
+    pub fn new(path: &Path) -> EventLog {
+        //! Log to the file at `path`, which is created on the first event.
+
+        EventLog {
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, session_start: &str, event: &Event) -> Result<(), String> {
+        //! Write the `event` as a new line at the end of the log file.
+
+        let entry = LogEntry {
+            time: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            session_start,
+            event,
+        };
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to encode log entry: {}", e))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open log file: {}", e))?;
+
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write log file: {}", e))
+    }
+}
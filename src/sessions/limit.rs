@@ -0,0 +1,124 @@
+//! A cap on the number of connections a server serves at once.
+//!
+//! Every connection takes a [`ConnectionPermit`] from the server's
+//! [`ConnectionLimit`] before its thread starts, and gives it back when the
+//! permit is dropped, once the connection closes. While all permits are
+//! taken, a new connection is either refused right away or queued until one
+//! is given back, see [`WhenFull`].
+//!
+//! # Issues
+//! - There is no server loop yet, see [`crate::cli::run_server`]. The limit
+//!   is ready for it, but nothing takes permits until then.
+
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+/// What a connection is told when the server has no room for it.
+pub const TOO_MANY_CONNECTIONS_MESSAGE: &str =
+    "too many connections: the server is at its limit, try again later";
+
+/// What happens to a connection that comes in while the server is full.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WhenFull {
+    /// Refuse it with [`TOO_MANY_CONNECTIONS_MESSAGE`].
+    Refuse,
+    /// Make it wait for a free permit, and refuse it if none frees up in time.
+    Queue(Duration),
+}
+
+struct Slots {
+    active: usize,
+    queued: usize,
+}
+
+pub struct ConnectionLimit {
+    max_connections: usize,
+    when_full: WhenFull,
+    slots: Mutex<Slots>,
+    freed: Condvar,
+}
+
+/// The right of a connection to be served, given back when dropped.
+pub struct ConnectionPermit {
+    limit: Arc<ConnectionLimit>,
+}
+
+impl ConnectionLimit {
+    pub fn new(max_connections: usize, when_full: WhenFull) -> Arc<ConnectionLimit> {
+        //! A limit of `max_connections` at once, shared by the connection threads.
+
+        Arc::new(ConnectionLimit {
+            max_connections,
+            when_full,
+            slots: Mutex::new(Slots {
+                active: 0,
+                queued: 0,
+            }),
+            freed: Condvar::new(),
+        })
+    }
+
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    pub fn when_full(&self) -> WhenFull {
+        self.when_full
+    }
+
+    pub fn active(&self) -> usize {
+        //! The number of permits currently taken.
+
+        self.slots.lock().unwrap().active
+    }
+
+    pub fn queued(&self) -> usize {
+        //! The number of connections waiting for a permit.
+
+        self.slots.lock().unwrap().queued
+    }
+
+    pub fn acquire(self: &Arc<Self>) -> Result<ConnectionPermit, String> {
+        //! Take a permit for a new connection.
+        //!
+        //! When all permits are taken, fails with
+        //! [`TOO_MANY_CONNECTIONS_MESSAGE`] right away with [`WhenFull::Refuse`],
+        //! or waits up to the queue timeout for one with [`WhenFull::Queue`].
+
+        let mut slots = self.slots.lock().unwrap();
+
+        if slots.active >= self.max_connections {
+            let WhenFull::Queue(timeout) = self.when_full else {
+                return Err(TOO_MANY_CONNECTIONS_MESSAGE.to_string());
+            };
+
+            slots.queued += 1;
+            slots = self
+                .freed
+                .wait_timeout_while(slots, timeout, |slots| slots.active >= self.max_connections)
+                .unwrap()
+                .0;
+            slots.queued -= 1;
+
+            if slots.active >= self.max_connections {
+                return Err(TOO_MANY_CONNECTIONS_MESSAGE.to_string());
+            }
+        }
+
+        slots.active += 1;
+        Ok(ConnectionPermit {
+            limit: Arc::clone(self),
+        })
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        //! Give the permit back, letting the first queued connection in.
+
+        self.limit.slots.lock().unwrap().active -= 1;
+        self.limit.freed.notify_one();
+    }
+}
@@ -1 +1,8 @@
+pub mod auth;
+pub mod cache;
+pub mod config;
+pub mod event_log;
+pub mod limit;
+pub mod pool;
 pub mod session;
+pub mod transaction;
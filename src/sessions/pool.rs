@@ -0,0 +1,110 @@
+//! The sessions of the connections to a server.
+//!
+//! Every connection gets a [`Session`] of its own, kept in a [`SessionPool`]
+//! under a connection id. A session that stays idle longer than the pool's
+//! idle timeout is expired by [`SessionPool::sweep_idle`], which whoever owns
+//! the pool has to call periodically. Expired sessions are ended and dropped,
+//! and the connection should be told [`SESSION_EXPIRED_MESSAGE`].
+//!
+//! # Issues
+//! - There is no server loop yet, see [`crate::cli::run_server`]. Nothing
+//!   owns a pool or calls `sweep_idle`, so no running mode expires idle
+//!   sessions until then. The client mode has a single session and no pool.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use crate::persistence::DatabaseRegistry;
+
+use super::session::Session;
+
+/// What a connection is told when it uses a session that has expired.
+pub const SESSION_EXPIRED_MESSAGE: &str =
+    "session expired: idle for too long, reconnect to continue";
+
+pub struct SessionPool {
+    database_registry: Arc<RwLock<DatabaseRegistry>>,
+    sessions: HashMap<usize, Arc<RwLock<Session>>>,
+    next_id: usize,
+    idle_timeout: Duration,
+}
+
+impl SessionPool {
+    pub fn new(db_reg: &Arc<RwLock<DatabaseRegistry>>, idle_timeout: Duration) -> SessionPool {
+        //! An empty pool whose sessions expire after `idle_timeout` of inactivity.
+
+        SessionPool {
+            database_registry: Arc::clone(db_reg),
+            sessions: HashMap::new(),
+            next_id: 0,
+            idle_timeout,
+        }
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn connect(&mut self) -> usize {
+        //! Open a session for a new connection and return its id.
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let session = Session::client(&self.database_registry);
+        self.sessions.insert(id, Arc::new(RwLock::new(session)));
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Result<Arc<RwLock<Session>>, String> {
+        //! Get the session of the connection `id`, marking it as active.
+        //!
+        //! Fails with [`SESSION_EXPIRED_MESSAGE`] if the session was swept.
+
+        let session = self
+            .sessions
+            .get(&id)
+            .ok_or(SESSION_EXPIRED_MESSAGE.to_string())?;
+        session.write().unwrap().touch();
+        Ok(Arc::clone(session))
+    }
+
+    pub fn disconnect(&mut self, id: usize) {
+        //! End and drop the session of the connection `id`.
+
+        if let Some(session) = self.sessions.remove(&id) {
+            session.write().unwrap().end();
+        }
+    }
+
+    pub fn sweep_idle(&mut self) -> Vec<usize> {
+        //! End and drop every session idle for longer than the idle timeout.
+        //!
+        //! Returns the ids of the expired connections, in ascending order.
+
+        let mut expired: Vec<usize> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.read().unwrap().idle_time() > self.idle_timeout)
+            .map(|(id, _)| *id)
+            .collect();
+        expired.sort();
+
+        for id in expired.iter() {
+            self.disconnect(*id);
+        }
+
+        expired
+    }
+}
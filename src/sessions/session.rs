@@ -14,7 +14,7 @@
 //! A session in client mode will deal with client specific attributes. Things
 //! like, but not exactly:
 //! - command history
-//! - event logs
+//! - event logs, see [`super::event_log`]
 //!
 //! ## Server Session
 //! A server session (reserved for later use) will allow a separate listener
@@ -27,15 +27,23 @@
 //! user data.
 
 use std::{
+    collections::HashMap,
     fmt::Display,
+    path::Path,
     sync::{Arc, RwLock},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 use chrono::{DateTime, Local};
 
 use crate::persistence::{Database, DatabaseRegistry};
 
+use super::auth;
+use super::cache::ResultCache;
+use super::config::SessionConfig;
+use super::event_log::{Event, EventLog};
+use super::transaction::Transaction;
+
 struct CommandHistory {
     command: String,
     command_time: SystemTime,
@@ -57,8 +65,15 @@ impl Display for CommandHistory {
 pub struct Session {
     command_history: Vec<CommandHistory>,
     start_time: SystemTime,
+    last_activity: SystemTime,
     active_database: Option<Arc<RwLock<Database>>>,
     database_registry: Arc<RwLock<DatabaseRegistry>>,
+    variables: HashMap<String, String>,
+    transaction: Option<Transaction>,
+    config: SessionConfig,
+    current_user: Option<String>,
+    event_log: Option<EventLog>,
+    result_cache: ResultCache,
 }
 
 impl Session {
@@ -68,8 +83,124 @@ impl Session {
         Session {
             command_history: vec![],
             start_time: SystemTime::now(),
+            last_activity: SystemTime::now(),
             active_database: None,
             database_registry: Arc::clone(db_reg),
+            variables: HashMap::new(),
+            transaction: None,
+            config: SessionConfig::new(),
+            current_user: None,
+            event_log: None,
+            result_cache: ResultCache::new(0),
+        }
+    }
+
+    pub fn detached(&self) -> Session {
+        //! A new session over a copy of the whole registry, with the same active
+        //! database, variables and user, for statements that must not change the
+        //! real data. Nothing done in it is ever seen outside of it.
+
+        let registry = self.database_registry.read().unwrap().to_data();
+        let registry = Arc::new(RwLock::new(DatabaseRegistry::from_data(registry)));
+
+        let mut session = Session::client(&registry);
+        session.variables = self.variables.clone();
+        session.current_user = self.current_user.clone();
+        if let Some(database) = self.active_database.as_ref() {
+            let db_name = database.read().unwrap().name();
+            let _ = session.use_database(&db_name);
+        }
+        session
+    }
+
+    pub fn reset(&mut self) {
+        //! Bring the session back to its defaults without restarting it.
+        //!
+        //! Clears the active database selection, the command history, all
+        //! session variables and cached results. An open transaction is rolled
+        //! back. The registry, the start time and the config are kept, and the
+        //! config's default database, if it still exists, is selected again.
+
+        if let Some(transaction) = self.transaction.take() {
+            transaction.rollback();
+        }
+
+        self.active_database = None;
+        self.command_history.clear();
+        self.variables.clear();
+        self.result_cache.clear();
+
+        if let Some(db_name) = self.config.default_database.clone() {
+            let _ = self.use_database(&db_name);
+        }
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, key: &str, value: &str) -> Result<(), String> {
+        //! Change a setting of the session, see [`SessionConfig::set`].
+
+        self.config.set(key, value)?;
+        self.result_cache.set_capacity(self.config.result_cache);
+        Ok(())
+    }
+
+    pub fn result_cache(&self) -> &ResultCache {
+        &self.result_cache
+    }
+
+    pub fn result_cache_mut(&mut self) -> &mut ResultCache {
+        &mut self.result_cache
+    }
+
+    pub fn set_variable(&mut self, name: &str, value: &str) {
+        self.variables.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(|value| value.as_str())
+    }
+
+    pub fn authenticate(&mut self, username: &str, password: &str) -> Result<(), String> {
+        //! Log in as `username`, checking the password against the users table.
+
+        {
+            let db_reg = self.database_registry.read().unwrap();
+            auth::verify(&db_reg, username, password)?;
+        }
+
+        self.current_user = Some(username.to_string());
+        Ok(())
+    }
+
+    pub fn create_user(&mut self, username: &str, password: &str) -> Result<(), String> {
+        let mut db_reg = self.database_registry.write().unwrap();
+        auth::create_user(&mut db_reg, username, password)
+    }
+
+    pub fn has_users(&self) -> bool {
+        let db_reg = self.database_registry.read().unwrap();
+        auth::has_users(&db_reg)
+    }
+
+    pub fn current_user(&self) -> Option<&str> {
+        self.current_user.as_deref()
+    }
+
+    pub fn set_event_log(&mut self, path: &Path) {
+        //! Start writing the events of this session to the log file at `path`.
+
+        self.event_log = Some(EventLog::new(path));
+    }
+
+    pub fn log_event(&self, event: Event) -> Result<(), String> {
+        //! Append the `event` to the event log, if one is set.
+
+        match self.event_log.as_ref() {
+            Some(event_log) => event_log.append(&self.start_time_string(), &event),
+            None => Ok(()),
         }
     }
 
@@ -77,6 +208,8 @@ impl Session {
         //! Set the currently active database connection for future
         //! querying.
 
+        Self::_check_reserved(db_name)?;
+
         let db_reg = self.database_registry.read().unwrap();
         let db = db_reg.get_database(db_name)?;
         self.active_database = Some(db);
@@ -90,13 +223,81 @@ impl Session {
         self.active_database.as_ref().map(Arc::clone)
     }
 
+    pub fn begin_transaction(&mut self) -> Result<(), String> {
+        //! Start a transaction over the active database.
+
+        if self.transaction.is_some() {
+            return Err("invalid begin: a transaction is already active".to_string());
+        }
+
+        let database = self
+            .active_database
+            .as_ref()
+            .ok_or("no database currently selected.")?;
+        self.transaction = Some(Transaction::begin(database));
+        Ok(())
+    }
+
+    pub fn commit_transaction(&mut self) -> Result<(), String> {
+        //! Keep the changes of the active transaction and end it.
+
+        let transaction = self
+            .transaction
+            .take()
+            .ok_or("invalid commit: no active transaction")?;
+        transaction.commit();
+        Ok(())
+    }
+
+    pub fn rollback_transaction(&mut self) -> Result<(), String> {
+        //! Undo the changes of the active transaction and end it.
+
+        let transaction = self
+            .transaction
+            .take()
+            .ok_or("invalid rollback: no active transaction")?;
+        transaction.rollback();
+        Ok(())
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
     pub fn add_to_command_history(&mut self, command: &str) {
+        self.touch();
         self.command_history.push(CommandHistory {
             command: command.to_string(),
             command_time: SystemTime::now(),
         });
     }
 
+    pub fn touch(&mut self) {
+        //! Mark the session as active right now, resetting its idle time.
+
+        self.last_activity = SystemTime::now();
+    }
+
+    pub fn idle_time(&self) -> Duration {
+        //! How long ago the session was last active.
+
+        self.last_activity.elapsed().unwrap_or_default()
+    }
+
+    pub fn end(&mut self) {
+        //! Let go of everything the session holds before it is discarded.
+        //!
+        //! An open transaction is rolled back and the active database is
+        //! released. The registry itself is left untouched.
+
+        if let Some(transaction) = self.transaction.take() {
+            transaction.rollback();
+        }
+
+        self.active_database = None;
+        self.variables.clear();
+    }
+
     pub fn start_time_string(&self) -> String {
         //! Conver the [`SystemTime`] object into a string representation
         //! to be more readable.
@@ -133,20 +334,32 @@ impl Session {
         db_name: &str,
         if_not_exists: bool,
     ) -> Result<Arc<RwLock<Database>>, String> {
+        Self::_check_reserved(db_name)?;
+
         let mut db_reg = self.database_registry.write().unwrap();
         db_reg.create_database(db_name, if_not_exists)
     }
 
     pub fn get_available_databases(&self) -> Vec<String> {
-        //! Returns a list of all available database names.
+        //! Returns a list of all available database names, leaving out the
+        //! [`auth::SYSTEM_DATABASE`].
 
         let db_reg = self.database_registry.read().unwrap();
-        db_reg.get_database_names()
+        db_reg
+            .get_database_names()
+            .into_iter()
+            .filter(|name| name != auth::SYSTEM_DATABASE)
+            .collect()
     }
 
-    pub fn drop_database(&mut self, db_name: &str) -> Result<Option<Arc<RwLock<Database>>>, String> {
+    pub fn drop_database(
+        &mut self,
+        db_name: &str,
+    ) -> Result<Option<Arc<RwLock<Database>>>, String> {
         //! Deletes the existing registry value of the registry.
 
+        Self::_check_reserved(db_name)?;
+
         if let Some(db) = self.active_database.as_ref() {
             if db.read().unwrap().name() == db_name {
                 return Err(format!("database is currently in use"));
@@ -156,4 +369,17 @@ impl Session {
         let mut db_reg = self.database_registry.write().unwrap();
         Ok(db_reg.drop_database(db_name))
     }
+
+    fn _check_reserved(db_name: &str) -> Result<(), String> {
+        //! Keep the [`auth::SYSTEM_DATABASE`] out of reach of the sessions, so
+        //! the users table can only be changed through [`auth`].
+
+        if db_name == auth::SYSTEM_DATABASE {
+            return Err(format!(
+                "invalid database {}: reserved for the engine",
+                db_name
+            ));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,44 @@
+//! A transaction groups several statements so they take effect together.
+//!
+//! On `BEGIN`, the [`Transaction`] takes a [`DatabaseSnapshot`] of the active
+//! database. Statements afterwards mutate the tables in place as usual. On
+//! `COMMIT` the snapshot is thrown away, and on `ROLLBACK` it is restored,
+//! undoing everything since `BEGIN`.
+//!
+//! # Issues
+//! - Only the database that was active on `BEGIN` is covered. Switching to
+//!   another database inside a transaction applies those changes right away.
+//! - Snapshots copy every table of the database, which gets expensive for
+//!   large databases.
+
+use std::sync::{Arc, RwLock};
+
+use crate::persistence::{Database, DatabaseSnapshot};
+
+pub struct Transaction {
+    database: Arc<RwLock<Database>>,
+    snapshot: DatabaseSnapshot,
+}
+
+impl Transaction {
+    pub fn begin(database: &Arc<RwLock<Database>>) -> Transaction {
+        //! Start a transaction over the `database`, remembering its current state.
+
+        let snapshot = database.read().unwrap().snapshot();
+
+        Transaction {
+            database: Arc::clone(database),
+            snapshot,
+        }
+    }
+
+    pub fn commit(self) {
+        //! Keep all changes made since the transaction began.
+    }
+
+    pub fn rollback(self) {
+        //! Undo all changes made since the transaction began.
+
+        self.database.write().unwrap().restore(self.snapshot);
+    }
+}
@@ -0,0 +1,1457 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, RwLock};
+
+use ferrum_engine::{
+    cli::{
+        ReplInput, SqlExecutor, SqlResult, dry_run_statements, execute_statements,
+        parsers::SqlParser, shutdown,
+    },
+    errors::EngineError,
+    persistence::DatabaseRegistry,
+    sessions::session::Session,
+};
+use sqlparser::dialect::MySqlDialect;
+
+fn _execute(session: &Arc<RwLock<Session>>, sql: &str) -> Result<SqlResult, String> {
+    let parser = SqlParser::new(Box::new(MySqlDialect {}));
+    let statement = parser.parse_single_sql(sql)?;
+    SqlExecutor::new(statement, session)
+        .execute()
+        .map_err(String::from)
+}
+
+fn _execute_error(session: &Arc<RwLock<Session>>, sql: &str) -> EngineError {
+    let parser = SqlParser::new(Box::new(MySqlDialect {}));
+    parser
+        .parse_single_sql(sql)
+        .and_then(|statement| SqlExecutor::new(statement, session).execute())
+        .err()
+        .expect("the statement should fail")
+}
+
+fn _prepare_session() -> Arc<RwLock<Session>> {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    let session = Arc::new(RwLock::new(Session::client(&registry)));
+
+    _execute(&session, "CREATE DATABASE test_db").unwrap();
+    _execute(&session, "USE test_db").unwrap();
+    _execute(
+        &session,
+        "CREATE TABLE products (id INT PRIMARY KEY, name VARCHAR(50), price INT)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO products VALUES (1, 'Pen', 20), (2, 'Lamp', 60), (3, 'Desk', 150)",
+    )
+    .unwrap();
+
+    session
+}
+
+fn _select_grid(session: &Arc<RwLock<Session>>, sql: &str) -> Vec<Vec<String>> {
+    let result = _execute(session, sql).unwrap();
+    let (_, grid) = result.table.unwrap().grid();
+    grid
+}
+
+#[test]
+fn select_where_arithmetic_on_column_side() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(&session, "SELECT * FROM products WHERE price * 2 > 100");
+    let names: Vec<&str> = grid.iter().map(|row| row[1].as_str()).collect();
+
+    assert_eq!(names, vec!["Lamp", "Desk"]);
+}
+
+#[test]
+fn select_where_arithmetic_on_text_column_fails() {
+    let session = _prepare_session();
+
+    let result = _execute(&session, "SELECT * FROM products WHERE name + 1 > 2");
+    assert!(result.is_err());
+}
+
+fn _prepare_inventory(session: &Arc<RwLock<Session>>) {
+    _execute(
+        session,
+        "CREATE TABLE inventory (id INT PRIMARY KEY, category VARCHAR(20), stock INT)",
+    )
+    .unwrap();
+    _execute(
+        session,
+        "INSERT INTO inventory VALUES (1, 'tools', 5), (2, 'toys', 3), (3, 'tools', 8), (4, 'books', 2), (5, 'tools', 1)",
+    )
+    .unwrap();
+}
+
+#[test]
+fn select_group_by_counts_per_group() {
+    let session = _prepare_session();
+    _prepare_inventory(&session);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT category, COUNT(*) FROM inventory GROUP BY category",
+    );
+
+    assert_eq!(
+        grid,
+        vec![
+            vec!["tools".to_string(), "3".to_string()],
+            vec!["toys".to_string(), "1".to_string()],
+            vec!["books".to_string(), "1".to_string()],
+        ]
+    );
+}
+
+#[test]
+fn select_group_by_ungrouped_column_fails() {
+    let session = _prepare_session();
+    _prepare_inventory(&session);
+
+    let result = _execute(
+        &session,
+        "SELECT category, stock, COUNT(*) FROM inventory GROUP BY category",
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn select_group_by_having_filters_small_groups() {
+    let session = _prepare_session();
+    _prepare_inventory(&session);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT category, SUM(stock) FROM inventory GROUP BY category HAVING COUNT(*) > 1",
+    );
+    assert_eq!(grid, vec![vec!["tools".to_string(), "14".to_string()]]);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT category FROM inventory GROUP BY category HAVING COUNT(*) < 2 AND category != 'toys'",
+    );
+    assert_eq!(grid, vec![vec!["books".to_string()]]);
+}
+
+#[test]
+fn select_group_by_having_ungrouped_column_fails() {
+    let session = _prepare_session();
+    _prepare_inventory(&session);
+
+    let result = _execute(
+        &session,
+        "SELECT category, COUNT(*) FROM inventory GROUP BY category HAVING stock > 2",
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn select_scalar_on_text_column_fails() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(&session, "SELECT price, ADD(price, 2) FROM products");
+    assert_eq!(grid[0], vec!["20".to_string(), "22".to_string()]);
+
+    let result = _execute(&session, "SELECT name, ADD(name, 2) FROM products");
+    assert!(result.is_err());
+}
+
+#[test]
+fn select_text_scalars() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(
+        &session,
+        "SELECT name, UPPER(name), LENGTH(name) FROM products WHERE name = 'Lamp'",
+    );
+    assert_eq!(
+        grid,
+        vec![vec![
+            "Lamp".to_string(),
+            "LAMP".to_string(),
+            "4".to_string()
+        ]]
+    );
+}
+
+#[test]
+fn select_concat_scalar() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(
+        &session,
+        "SELECT name, price, CONCAT(name, ': ', price) FROM products WHERE name = 'Pen'",
+    );
+    assert_eq!(grid[0][2], "Pen: 20");
+}
+
+#[test]
+fn select_date_scalars() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE members (id INT PRIMARY KEY, joined DATE)",
+    )
+    .unwrap();
+    _execute(&session, "INSERT INTO members VALUES (1, '2021-07-14')").unwrap();
+
+    let grid = _select_grid(
+        &session,
+        "SELECT joined, YEAR(joined), MONTH(joined), NOW() FROM members",
+    );
+    assert_eq!(grid[0][..3], ["2021-07-14", "2021", "7"]);
+}
+
+#[test]
+fn select_json_extract_scalar() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE profiles (id INT PRIMARY KEY, body JSON)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        r#"INSERT INTO profiles VALUES (1, '{"address": {"city": "Oslo"}}')"#,
+    )
+    .unwrap();
+
+    let grid = _select_grid(
+        &session,
+        "SELECT body, JSON_EXTRACT(body, '$.address.city') FROM profiles",
+    );
+    assert_eq!(grid[0], [r#"{"address":{"city":"Oslo"}}"#, "Oslo"]);
+    assert!(_execute(&session, "INSERT INTO profiles VALUES (2, 'plain text')").is_err());
+}
+
+#[test]
+fn create_table_with_check_constraint() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE members (id INT PRIMARY KEY, age INT CHECK (age >= 18 AND age < 130))",
+    )
+    .unwrap();
+
+    assert!(_execute(&session, "INSERT INTO members VALUES (1, 30)").is_ok());
+    assert!(_execute(&session, "INSERT INTO members VALUES (2, 12)").is_err());
+    assert!(_execute(&session, "UPDATE members SET age = 140 WHERE id = 1").is_err());
+}
+
+#[test]
+fn create_table_with_check_on_text_literal() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE tags (id INT PRIMARY KEY, name VARCHAR(20) CHECK (name <> '('))",
+    )
+    .unwrap();
+
+    assert!(_execute(&session, "INSERT INTO tags VALUES (1, '(')").is_err());
+    assert!(_execute(&session, "INSERT INTO tags VALUES (2, 'open (')").is_ok());
+}
+
+#[test]
+fn create_table_rejects_enum_values_with_spaces_or_commas() {
+    let session = _prepare_session();
+
+    for sql in [
+        "CREATE TABLE tickets (id INT PRIMARY KEY, status ENUM('open','on hold'))",
+        "CREATE TABLE tickets (id INT PRIMARY KEY, status ENUM('open','closed,late'))",
+    ] {
+        let Err(error) = _execute(&session, sql) else {
+            panic!("expected {} to fail", sql);
+        };
+        assert!(
+            error.contains("values can not hold spaces or commas"),
+            "{}",
+            error
+        );
+    }
+    assert!(_execute(&session, "SELECT * FROM tickets").is_err());
+
+    _execute(
+        &session,
+        "CREATE TABLE tickets (id INT PRIMARY KEY, status ENUM('open','on_hold'))",
+    )
+    .unwrap();
+    assert!(_execute(&session, "INSERT INTO tickets VALUES (1, 'on_hold')").is_ok());
+}
+
+#[test]
+fn drop_table_honors_if_exists() {
+    let session = _prepare_session();
+
+    assert!(_execute(&session, "DROP TABLE missing").is_err());
+    assert!(_execute(&session, "DROP TABLE IF EXISTS missing").is_ok());
+
+    let result = _execute(&session, "DROP TABLE products").unwrap();
+    assert_eq!(result.n_rows_processed, Some(3));
+    assert!(_execute(&session, "SELECT * FROM products").is_err());
+}
+
+#[test]
+fn drop_tables_checks_every_table_first() {
+    let session = _prepare_session();
+    _execute(&session, "CREATE TABLE notes (id INT PRIMARY KEY)").unwrap();
+    _execute(
+        &session,
+        "CREATE TABLE orders (id INT PRIMARY KEY, product_id INT, FOREIGN KEY (product_id) REFERENCES products(id))",
+    )
+    .unwrap();
+
+    assert!(_execute(&session, "DROP TABLE notes, missing").is_err());
+    assert!(_execute(&session, "DROP TABLE notes, products").is_err());
+    assert!(_execute(&session, "SELECT * FROM notes").is_ok());
+    assert!(_execute(&session, "SELECT * FROM products").is_ok());
+
+    let result = _execute(&session, "DROP TABLE IF EXISTS products, missing, orders").unwrap();
+    assert_eq!(result.n_rows_processed, Some(3));
+    assert!(_execute(&session, "SELECT * FROM products").is_err());
+    assert!(_execute(&session, "SELECT * FROM orders").is_err());
+}
+
+#[test]
+fn alter_table_renames_table_and_column() {
+    let session = _prepare_session();
+
+    _execute(&session, "ALTER TABLE products RENAME COLUMN name TO title").unwrap();
+    _execute(&session, "RENAME TABLE products TO items").unwrap();
+
+    let grid = _select_grid(&session, "SELECT title FROM items WHERE title = 'Desk'");
+    assert_eq!(grid, vec![vec!["Desk".to_string()]]);
+    assert!(_execute(&session, "ALTER TABLE items RENAME COLUMN title TO price").is_err());
+}
+
+#[test]
+fn database_sql_dump_replays_into_fresh_registry() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE writers (id INT PRIMARY KEY AUTO_INCREMENT, name VARCHAR(50), bio VARCHAR(50) NULL)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "CREATE TABLE books (id INT, writer_id INT, title VARCHAR(50), PRIMARY KEY (id), FOREIGN KEY (writer_id) REFERENCES writers(id))",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO writers VALUES (1, 'O''Brien', NULL), (2, 'Austen, Jane', 'novelist')",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO books VALUES (1, 1, 'Lamb'), (2, 2, 'Emma'), (3, 2, 'Persuasion')",
+    )
+    .unwrap();
+
+    let dump = {
+        let session = session.read().unwrap();
+        let database = session.get_active_database().unwrap();
+        let database = database.read().unwrap();
+        database.to_sql_dump()
+    };
+    assert!(dump.find("CREATE TABLE writers") < dump.find("CREATE TABLE books"));
+
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    let replayed = Arc::new(RwLock::new(Session::client(&registry)));
+    _execute(&replayed, "CREATE DATABASE test_db").unwrap();
+    _execute(&replayed, "USE test_db").unwrap();
+    for statement in dump.lines() {
+        _execute(&replayed, statement).unwrap();
+    }
+
+    for table in ["products", "writers", "books"] {
+        let sql = format!("SELECT * FROM {}", table);
+        assert_eq!(_select_grid(&replayed, &sql), _select_grid(&session, &sql));
+    }
+}
+
+#[test]
+fn transaction_rollback_restores_original_state() {
+    let session = _prepare_session();
+
+    _execute(&session, "BEGIN").unwrap();
+    _execute(
+        &session,
+        "INSERT INTO products VALUES (4, 'Lamp Shade', 35)",
+    )
+    .unwrap();
+    _execute(&session, "CREATE TABLE notes (id INT PRIMARY KEY)").unwrap();
+    assert!(_execute(&session, "UPDATE products SET price = 'cheap' WHERE id = 4").is_err());
+    _execute(&session, "ROLLBACK").unwrap();
+
+    let grid = _select_grid(&session, "SELECT id FROM products");
+    assert_eq!(grid, vec![vec!["1"], vec!["2"], vec!["3"]]);
+    assert!(_execute(&session, "SELECT * FROM notes").is_err());
+
+    // the primary key index is restored too, so the rolled back key is free again
+    _execute(&session, "INSERT INTO products VALUES (4, 'Shelf', 90)").unwrap();
+}
+
+#[test]
+fn transaction_rollback_restores_renamed_table() {
+    let session = _prepare_session();
+
+    _execute(&session, "BEGIN").unwrap();
+    _execute(&session, "ALTER TABLE products RENAME TO goods").unwrap();
+    _execute(&session, "ROLLBACK").unwrap();
+
+    let dump = {
+        let session = session.read().unwrap();
+        let database = session.get_active_database().unwrap();
+        let database = database.read().unwrap();
+        database.to_sql_dump()
+    };
+    assert!(dump.contains("CREATE TABLE products"));
+    assert!(!dump.contains("goods"));
+    assert!(_execute(&session, "SELECT * FROM goods").is_err());
+    assert_eq!(_select_grid(&session, "SELECT id FROM products").len(), 3);
+}
+
+#[test]
+fn transaction_commit_keeps_changes() {
+    let session = _prepare_session();
+
+    _execute(&session, "START TRANSACTION").unwrap();
+    _execute(&session, "INSERT INTO products VALUES (4, 'Shelf', 90)").unwrap();
+    _execute(&session, "INSERT INTO products VALUES (5, 'Chair', 75)").unwrap();
+    _execute(&session, "COMMIT").unwrap();
+
+    let grid = _select_grid(&session, "SELECT id FROM products");
+    assert_eq!(grid.len(), 5);
+    assert!(!session.read().unwrap().in_transaction());
+    assert!(_execute(&session, "ROLLBACK").is_err());
+}
+
+#[test]
+fn select_min_max_with_collation() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "INSERT INTO products VALUES (4, 'anchor', 10), (5, 'Zip', 10)",
+    )
+    .unwrap();
+
+    let grid = _select_grid(&session, "SELECT MIN(name), MAX(name) FROM products");
+    assert_eq!(grid, vec![vec!["Desk", "anchor"]]);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT MIN(name COLLATE nocase), MAX(name COLLATE nocase) FROM products",
+    );
+    assert_eq!(grid, vec![vec!["anchor", "Zip"]]);
+}
+
+#[test]
+fn select_min_max_compares_by_column_type() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE branches (id INT PRIMARY KEY, zip VARCHAR(10))",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO branches VALUES (1, '9'), (2, '02134'), (3, '10')",
+    )
+    .unwrap();
+
+    let grid = _select_grid(&session, "SELECT MIN(zip), MAX(zip) FROM branches");
+    assert_eq!(grid, vec![vec!["02134", "9"]]);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT MIN(price COLLATE nocase), MAX(price) FROM products",
+    );
+    assert_eq!(grid, vec![vec!["20", "150"]]);
+}
+
+#[test]
+fn select_count_distinct() {
+    let session = _prepare_session();
+    _prepare_inventory(&session);
+    _execute(
+        &session,
+        "CREATE TABLE tags (id INT PRIMARY KEY, tag VARCHAR(20) NULL)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO tags VALUES (1, 'red'), (2, NULL), (3, 'red'), (4, 'blue'), (5, NULL)",
+    )
+    .unwrap();
+
+    let grid = _select_grid(&session, "SELECT COUNT(tag), COUNT(DISTINCT tag) FROM tags");
+    assert_eq!(grid, vec![vec!["3", "2"]]);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT category, COUNT(DISTINCT stock) FROM inventory GROUP BY category",
+    );
+    assert_eq!(grid[0], vec!["tools", "3"]);
+
+    assert!(_execute(&session, "SELECT SUM(DISTINCT stock) FROM inventory").is_err());
+}
+
+#[test]
+fn select_group_concat_per_group() {
+    let session = _prepare_session();
+    _prepare_inventory(&session);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT category, GROUP_CONCAT(stock) FROM inventory GROUP BY category",
+    );
+    assert_eq!(grid[0], vec!["tools", "5,8,1"]);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT GROUP_CONCAT(category, '/') FROM inventory",
+    );
+    assert_eq!(grid, vec![vec!["tools/toys/tools/books/tools"]]);
+
+    assert!(_execute(&session, "SELECT GROUP_CONCAT('/') FROM inventory").is_err());
+}
+
+#[test]
+fn select_first_last_per_group() {
+    let session = _prepare_session();
+    _prepare_inventory(&session);
+
+    let grid = _select_grid(
+        &session,
+        "SELECT category, FIRST(stock), LAST(stock) FROM inventory GROUP BY category",
+    );
+    assert_eq!(
+        grid,
+        vec![
+            vec!["tools", "5", "1"],
+            vec!["toys", "3", "3"],
+            vec!["books", "2", "2"]
+        ]
+    );
+}
+
+#[test]
+fn select_function_arguments_resolve_by_name() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(&session, "SELECT SUM(price) FROM products");
+    assert_eq!(grid, vec![vec!["230"]]);
+
+    let grid = _select_grid(&session, "SELECT price, MUL(price, 3) FROM products");
+    assert_eq!(grid[0], vec!["20", "60"]);
+
+    let result = _execute(&session, "SELECT SUM(cost) FROM products");
+    assert_eq!(
+        result.err(),
+        Some("invalid column cost: does not exist".to_string())
+    );
+}
+
+#[test]
+fn select_scalars_without_columns() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(
+        &session,
+        "SELECT UPPER(name) FROM products WHERE price > 50",
+    );
+    assert_eq!(grid, vec![vec!["LAMP"], vec!["DESK"]]);
+
+    let grid = _select_grid(&session, "SELECT ADD(id, 1), MUL(price, 2) FROM products");
+    assert_eq!(grid[0], vec!["2", "40"]);
+
+    let grid = _select_grid(&session, "SELECT NOW() FROM products");
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid[0].len(), 1);
+
+    assert!(_execute(&session, "SELECT SUM(price), UPPER(name) FROM products").is_err());
+}
+
+#[test]
+fn select_function_names_ignore_case() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(&session, "SELECT sum(price) FROM products");
+    assert_eq!(grid, vec![vec!["230"]]);
+
+    let grid = _select_grid(&session, "SELECT price, mul(price, 3) FROM products");
+    assert_eq!(grid[0], vec!["20", "60"]);
+}
+
+fn _select_headers(session: &Arc<RwLock<Session>>, sql: &str) -> Vec<String> {
+    let result = _execute(session, sql).unwrap();
+    let (headers, _) = result.table.unwrap().grid();
+    headers
+}
+
+#[test]
+fn select_column_aliases_name_the_headers() {
+    let session = _prepare_session();
+
+    let sql = "SELECT id AS identifier, name FROM products WHERE id > 1 ORDER BY identifier DESC";
+    assert_eq!(_select_headers(&session, sql), vec!["identifier", "name"]);
+    assert_eq!(_select_grid(&session, sql)[0], vec!["3", "Desk"]);
+
+    let sql = "SELECT SUM(price) AS total FROM products";
+    assert_eq!(_select_headers(&session, sql), vec!["total"]);
+
+    let sql = "SELECT name AS label, UPPER(name) AS shout FROM products";
+    assert_eq!(_select_headers(&session, sql), vec!["label", "shout"]);
+}
+
+#[test]
+fn select_order_by_sorts_rows() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "INSERT INTO products VALUES (4, 'Lamp', 45), (5, 'Ink', 150)",
+    )
+    .unwrap();
+
+    let grid = _select_grid(
+        &session,
+        "SELECT id, price FROM products ORDER BY price DESC, id",
+    );
+    let ids: Vec<&str> = grid.iter().map(|row| row[0].as_str()).collect();
+    assert_eq!(ids, vec!["3", "5", "2", "4", "1"]);
+
+    let grid = _select_grid(&session, "SELECT name, id FROM products ORDER BY name");
+    let ids: Vec<&str> = grid.iter().map(|row| row[1].as_str()).collect();
+    assert_eq!(ids, vec!["3", "5", "2", "4", "1"]);
+
+    assert!(_execute(&session, "SELECT name FROM products ORDER BY UPPER(name)").is_err());
+    assert!(_execute(&session, "SELECT name FROM products ORDER BY nmae").is_err());
+}
+
+#[test]
+fn select_limit_offset_paginates() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "INSERT INTO products VALUES (4, 'Ink', 5), (5, 'Tape', 8)",
+    )
+    .unwrap();
+
+    let ids = |sql: &str| -> Vec<String> {
+        _select_grid(&session, sql)
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect()
+    };
+
+    let first = ids("SELECT id FROM products ORDER BY id LIMIT 3 OFFSET 0");
+    let second = ids("SELECT id FROM products ORDER BY id LIMIT 3 OFFSET 3");
+    assert_eq!(first, vec!["1", "2", "3"]);
+    assert_eq!(second, vec!["4", "5"]);
+    assert_eq!([first, second].concat(), ids("SELECT id FROM products"));
+
+    assert_eq!(ids("SELECT id FROM products LIMIT 10 OFFSET 4"), vec!["5"]);
+    assert!(_execute(&session, "SELECT id FROM products LIMIT 'all'").is_err());
+}
+
+#[test]
+fn select_distinct_dedupes_projection() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "INSERT INTO products VALUES (4, 'Pen', 25), (5, 'Lamp', 60), (6, 'Pen', 20)",
+    )
+    .unwrap();
+
+    let names: Vec<String> = _select_grid(&session, "SELECT DISTINCT name FROM products")
+        .into_iter()
+        .map(|row| row[0].clone())
+        .collect();
+    let unique: std::collections::HashSet<&String> = names.iter().collect();
+    assert_eq!(names.len(), 3);
+    assert_eq!(unique.len(), names.len());
+
+    let grid = _select_grid(
+        &session,
+        "SELECT DISTINCT name FROM products ORDER BY name DESC LIMIT 2",
+    );
+    assert_eq!(grid, vec![vec!["Pen"], vec!["Lamp"]]);
+}
+
+#[test]
+fn select_result_cache_hits_until_table_changes() {
+    let session = _prepare_session();
+    _execute(&session, "SET result_cache = 8").unwrap();
+    let hits = || session.read().unwrap().result_cache().hits();
+    let sql = "SELECT name, price FROM products WHERE price > 50";
+
+    let first = _select_grid(&session, sql);
+    assert_eq!(hits(), 0);
+    assert_eq!(
+        _select_grid(
+            &session,
+            "select name,price  from products where price > 50"
+        ),
+        first
+    );
+    assert_eq!(hits(), 1);
+
+    _execute(&session, "INSERT INTO products VALUES (4, 'Shelf', 90)").unwrap();
+    let after_insert = _select_grid(&session, sql);
+    assert_eq!(hits(), 1);
+    assert_eq!(after_insert.len(), first.len() + 1);
+
+    // Volatile scalars are never served from the cache
+    _select_grid(&session, "SELECT name, NOW() FROM products");
+    _select_grid(&session, "SELECT name, NOW() FROM products");
+    _select_grid(&session, "SELECT name, NOW () FROM products");
+    _select_grid(&session, "SELECT name, NOW () FROM products");
+    assert_eq!(hits(), 1);
+
+    // A literal that merely reads like a call is still cached
+    let literal = "SELECT name FROM products WHERE name <> 'snow()'";
+    _select_grid(&session, literal);
+    _select_grid(&session, literal);
+    assert_eq!(hits(), 2);
+}
+
+#[test]
+fn dry_run_flags_failing_statement_without_changes() {
+    let session = _prepare_session();
+    let script = "INSERT INTO products VALUES (4, 'Ink', 5); \
+                  UPDATE products SET price = 'cheap' WHERE id = 1; \
+                  DELETE FROM products WHERE id = 2; \
+                  DROP TABLE products";
+
+    let outcomes = dry_run_statements(script, &session).unwrap();
+    assert_eq!(outcomes.len(), 4);
+    let failing: Vec<usize> = outcomes
+        .iter()
+        .enumerate()
+        .filter(|(_, outcome)| outcome.is_err())
+        .map(|(position, _)| position + 1)
+        .collect();
+    assert_eq!(failing, vec![2]);
+
+    assert_eq!(
+        _select_grid(&session, "SELECT id, price FROM products"),
+        vec![vec!["1", "20"], vec!["2", "60"], vec!["3", "150"]]
+    );
+}
+
+#[test]
+fn select_qualified_column_names() {
+    let session = _prepare_session();
+
+    let grid = _select_grid(&session, "SELECT products.id, products.name FROM products");
+    assert_eq!(grid[0], vec!["1", "Pen"]);
+
+    let sql = "SELECT p.name AS item FROM products p WHERE name = 'Desk'";
+    assert_eq!(_select_headers(&session, sql), vec!["item"]);
+    assert_eq!(_select_grid(&session, sql), vec![vec!["Desk"]]);
+
+    assert!(_execute(&session, "SELECT orders.id FROM products").is_err());
+    assert!(_execute(&session, "SELECT db.products.id FROM products").is_err());
+}
+
+#[test]
+fn select_where_is_null_and_is_not_null() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE contacts (id INT PRIMARY KEY, phone VARCHAR(20) NULL)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO contacts VALUES (1, '555-01'), (2, NULL), (3, '555-03'), (4, NULL)",
+    )
+    .unwrap();
+
+    let nulls = _select_grid(&session, "SELECT * FROM contacts WHERE phone IS NULL");
+    let not_nulls = _select_grid(&session, "SELECT * FROM contacts WHERE phone IS NOT NULL");
+
+    let ids = |grid: &Vec<Vec<String>>| grid.iter().map(|row| row[0].clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&nulls), vec!["2", "4"]);
+    assert_eq!(ids(&not_nulls), vec!["1", "3"]);
+
+    let deleted = _execute(&session, "DELETE FROM contacts WHERE phone IS NULL").unwrap();
+    assert_eq!(deleted.n_rows_processed, Some(2));
+    assert!(_execute(&session, "SELECT * FROM contacts WHERE 1 IS NULL").is_err());
+}
+
+#[test]
+fn select_where_null_safe_equality() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE contacts (id INT PRIMARY KEY, phone VARCHAR(20) NULL, fax VARCHAR(20) NULL)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO contacts VALUES (1, '555-01', '555-01'), (2, NULL, NULL), (3, '555-03', NULL)",
+    )
+    .unwrap();
+
+    let ids = |sql: &str| {
+        _select_grid(&session, sql)
+            .iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<_>>()
+    };
+    assert!(ids("SELECT * FROM contacts WHERE phone = NULL").is_empty());
+    assert_eq!(
+        ids("SELECT * FROM contacts WHERE phone <=> NULL"),
+        vec!["2"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM contacts WHERE phone <=> fax"),
+        vec!["1", "2"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM contacts WHERE fax <=> '555-01'"),
+        vec!["1"]
+    );
+    assert!(_execute(&session, "SELECT * FROM contacts WHERE mobile <=> NULL").is_err());
+}
+
+#[test]
+fn insert_null_literal_honors_nullability() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE notes (id INT PRIMARY KEY, body VARCHAR(20) NULL, author VARCHAR(20))",
+    )
+    .unwrap();
+
+    _execute(&session, "INSERT INTO notes VALUES (1, NULL, 'ana')").unwrap();
+    let result = _execute(&session, "INSERT INTO notes VALUES (2, 'hi', NULL)");
+
+    assert_eq!(
+        result.err(),
+        Some("invalid NULL: not allowed on column 'author'".to_string())
+    );
+    let nulls = _select_grid(&session, "SELECT id, body FROM notes WHERE body IS NULL");
+    assert_eq!(nulls.len(), 1);
+    assert_eq!(nulls[0][0], "1");
+}
+
+#[test]
+fn insert_empty_text_is_not_null() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE notes (id INT PRIMARY KEY, body VARCHAR(20) NULL, stars INT NULL)",
+    )
+    .unwrap();
+
+    _execute(
+        &session,
+        "INSERT INTO notes VALUES (1, '', 3), (2, NULL, NULL)",
+    )
+    .unwrap();
+
+    let nulls = _select_grid(&session, "SELECT id, body FROM notes WHERE body IS NULL");
+    let empties = _select_grid(&session, "SELECT id, body FROM notes WHERE body = ''");
+    assert_eq!(nulls.len(), 1);
+    assert_eq!(nulls[0][0], "2");
+    assert_eq!(empties, vec![vec!["1".to_string(), "".to_string()]]);
+    assert!(_execute(&session, "INSERT INTO notes VALUES (3, 'x', '')").is_err());
+}
+
+#[test]
+fn select_where_like_patterns() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE files (id INT PRIMARY KEY, name VARCHAR(30) NULL)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO files VALUES (1, 'report.txt'), (2, 'Report.csv'), (3, 'notes.txt'), (4, NULL), (5, '100%_done'), (6, 'cat'), (7, 'cut')",
+    )
+    .unwrap();
+
+    let ids = |sql: &str| {
+        _select_grid(&session, sql)
+            .iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids("SELECT * FROM files WHERE name LIKE 'rep%'"), vec!["1"]);
+    assert_eq!(
+        ids("SELECT * FROM files WHERE name LIKE '%.txt'"),
+        vec!["1", "3"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM files WHERE name LIKE '%port%'"),
+        vec!["1", "2"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM files WHERE name LIKE 'c_t'"),
+        vec!["6", "7"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM files WHERE name LIKE '%\\%\\_%'"),
+        vec!["5"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM files WHERE name LIKE '%!%%' ESCAPE '!'"),
+        vec!["5"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM files WHERE name NOT LIKE '%.%'"),
+        vec!["5", "6", "7"]
+    );
+
+    assert!(_execute(&session, "SELECT * FROM files WHERE name LIKE 'a\\'").is_err());
+}
+
+#[test]
+fn select_where_between_and_in() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE stock (id INT PRIMARY KEY, code VARCHAR(10) NULL, qty INT NULL)",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO stock VALUES (1, 'a1', 5), (2, 'b2', 10), (3, NULL, 15), (4, 'c3', NULL), (5, 'a1', 100)",
+    )
+    .unwrap();
+
+    let ids = |sql: &str| {
+        _select_grid(&session, sql)
+            .iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        ids("SELECT * FROM stock WHERE qty BETWEEN 5 AND 15"),
+        vec!["1", "2", "3"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM stock WHERE qty NOT BETWEEN 5 AND 15"),
+        vec!["5"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM stock WHERE code BETWEEN 'a' AND 'b9'"),
+        vec!["1", "2", "5"]
+    );
+
+    assert_eq!(
+        ids("SELECT * FROM stock WHERE code IN ('a1', 'c3')"),
+        vec!["1", "4", "5"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM stock WHERE code NOT IN ('a1', 'c3')"),
+        vec!["2"]
+    );
+    assert_eq!(
+        ids("SELECT * FROM stock WHERE qty IN (10.0, 100)"),
+        vec!["2", "5"]
+    );
+
+    assert!(_execute(&session, "SELECT * FROM stock WHERE qty IN ('ten')").is_err());
+}
+
+#[test]
+fn select_where_not_follows_de_morgan() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "INSERT INTO products VALUES (4, 'Chair', 80), (5, 'x', 5)",
+    )
+    .unwrap();
+
+    let pairs = [
+        (
+            "SELECT * FROM products WHERE NOT (id < 3 OR name = 'x')",
+            "SELECT * FROM products WHERE NOT id < 3 AND NOT name = 'x'",
+        ),
+        (
+            "SELECT * FROM products WHERE NOT (price > 50 AND (id = 2 OR id = 4))",
+            "SELECT * FROM products WHERE NOT price > 50 OR (NOT id = 2 AND NOT id = 4)",
+        ),
+    ];
+
+    for (left, right) in pairs {
+        assert_eq!(_select_grid(&session, left), _select_grid(&session, right));
+    }
+
+    let grid = _select_grid(&session, pairs[0].0);
+    let names: Vec<&str> = grid.iter().map(|row| row[1].as_str()).collect();
+    assert_eq!(names, vec!["Desk", "Chair"]);
+
+    let grid = _select_grid(&session, "SELECT * FROM products WHERE NOT NOT ((id = 1))");
+    assert_eq!(grid[0][1], "Pen");
+}
+
+#[test]
+fn select_between_uses_ordered_index() {
+    let session = _prepare_session();
+    for id in 4..=40 {
+        let sql = format!(
+            "INSERT INTO products VALUES ({}, 'item{}', {})",
+            id,
+            id,
+            id * 10
+        );
+        _execute(&session, &sql).unwrap();
+    }
+
+    let sql = "SELECT id, name FROM products WHERE id BETWEEN 10 AND 13 AND name != 'item12'";
+    let unindexed = _select_grid(&session, sql);
+    _execute(&session, "CREATE INDEX products_id ON products (id)").unwrap();
+    let indexed = _select_grid(&session, sql);
+
+    assert_eq!(indexed, unindexed);
+    let ids: Vec<&str> = indexed.iter().map(|row| row[0].as_str()).collect();
+    assert_eq!(ids, vec!["10", "11", "13"]);
+
+    assert!(_execute(&session, "CREATE INDEX products_name ON products (name)").is_err());
+}
+
+#[test]
+fn explain_select_describes_the_plan() {
+    let session = _prepare_session();
+    let plan = |sql: &str| {
+        _select_grid(&session, sql)
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<_>>()
+    };
+
+    let sql = "EXPLAIN SELECT id, name FROM products WHERE id = 2 ORDER BY name DESC LIMIT 1";
+    let unindexed = plan(sql);
+    assert_eq!(
+        unindexed,
+        vec![
+            "Full scan on products",
+            "Filter: id = 2",
+            "Project: id, name",
+            "Order by: name DESC",
+            "Limit: 1",
+        ]
+    );
+
+    _execute(&session, "CREATE INDEX products_id ON products (id)").unwrap();
+    assert_eq!(plan(sql)[0], "Index lookup on products (id = 2)");
+    assert_eq!(
+        plan("EXPLAIN SELECT * FROM products WHERE name = 'Pen'")[0],
+        "Full scan on products"
+    );
+
+    let grid = _select_grid(&session, "SELECT id, name FROM products WHERE id = 2");
+    assert_eq!(grid, vec![vec!["2", "Lamp"]]);
+}
+
+#[test]
+fn execute_statements_runs_a_submission_in_order() {
+    let session = _prepare_session();
+    let sql = "CREATE TABLE pets (id INT PRIMARY KEY, name VARCHAR(20)); \
+        INSERT INTO pets VALUES (1, 'Rex'); \
+        INSERT INTO pets VALUES (2, 'Tom'); \
+        SELECT * FROM pets;";
+
+    let mut results = vec![];
+    let n_statements = execute_statements(sql, &session, |result, _| results.push(result)).unwrap();
+
+    assert_eq!(n_statements, 4);
+    assert_eq!(results.len(), 4);
+    let (_, grid) = results.pop().unwrap().table.unwrap().grid();
+    assert_eq!(grid, vec![vec!["1", "Rex"], vec!["2", "Tom"]]);
+}
+
+#[test]
+fn execute_statements_stops_at_the_first_failure() {
+    let session = _prepare_session();
+    let sql = "INSERT INTO products VALUES (4, 'Mug', 5); \
+        INSERT INTO missing VALUES (1); \
+        INSERT INTO products VALUES (5, 'Cup', 5);";
+
+    let mut n_results = 0;
+    let error = execute_statements(sql, &session, |_, _| n_results += 1).unwrap_err();
+
+    assert_eq!(n_results, 1);
+    assert!(error.contains("of 3 failed"));
+    assert_eq!(_select_grid(&session, "SELECT * FROM products").len(), 4);
+}
+
+#[test]
+fn execute_statements_writes_the_event_log() {
+    let session = _prepare_session();
+    let path = std::env::temp_dir().join("ferrum_execute_statements_writes_the_event_log.log");
+    let _ = std::fs::remove_file(&path);
+    session.write().unwrap().set_event_log(&path);
+    let sql = "DELETE FROM products WHERE price > 50; INSERT INTO missing VALUES (1);";
+
+    let _ = execute_statements(sql, &session, |_, _| {});
+
+    let session_start = session.read().unwrap().start_time_string();
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["event"], "statement");
+    assert_eq!(
+        lines[0]["statement"],
+        "DELETE FROM products WHERE price > 50"
+    );
+    assert_eq!(lines[0]["rows"], 2);
+    assert_eq!(lines[1]["event"], "error");
+    assert_eq!(lines[1]["statement"], "INSERT INTO missing VALUES (1)");
+    assert!(
+        lines[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("does not exist")
+    );
+    assert!(
+        lines
+            .iter()
+            .all(|line| line["session_start"] == session_start.as_str())
+    );
+}
+
+#[test]
+fn sql_result_displays_table_rows() {
+    let session = _prepare_session();
+
+    let display = _execute(
+        &session,
+        "SELECT name, price FROM products WHERE price < 100",
+    )
+    .unwrap()
+    .display();
+
+    assert!(display.starts_with("+-"));
+    assert!(display.contains("Pen"));
+    assert!(display.contains("Lamp"));
+    assert!(!display.contains("Desk"));
+}
+
+#[test]
+fn sql_result_displays_single_affected_row() {
+    let session = _prepare_session();
+
+    let result = _execute(&session, "INSERT INTO products VALUES (4, 'Mug', 5)").unwrap();
+    assert_eq!(result.n_rows_processed, Some(1));
+    assert!(result.display().contains("Mug"));
+
+    let result = _execute(
+        &session,
+        "INSERT INTO products VALUES (5, 'Cup', 5), (6, 'Jar', 7)",
+    )
+    .unwrap();
+    assert_eq!(result.display(), "2 row(s) affected");
+}
+
+#[test]
+fn set_statement_changes_session_config() {
+    let session = _prepare_session();
+
+    _execute(&session, "SET prompt = 'fe'").unwrap();
+    _execute(&session, "SET timing = off").unwrap();
+    _execute(&session, "SET color = 0").unwrap();
+    _execute(&session, "SET timing = on").unwrap();
+    _execute(&session, "SET timing = off").unwrap();
+
+    {
+        let session = session.read().unwrap();
+        assert_eq!(session.config().prompt, "fe");
+        assert!(!session.config().timing);
+        assert!(!session.config().color);
+    }
+
+    assert!(_execute(&session, "SET timing = maybe").is_err());
+    assert!(_execute(&session, "SET unknown = 1").is_err());
+}
+
+#[test]
+fn create_table_as_select_copies_the_query_result() {
+    let session = _prepare_session();
+
+    let result = _execute(
+        &session,
+        "CREATE TABLE pricey AS SELECT name, price FROM products WHERE price > 50",
+    )
+    .unwrap();
+    assert_eq!(result.n_rows_processed, Some(2));
+
+    let columns: Vec<(String, String)> = {
+        let session = session.read().unwrap();
+        let database = session.get_active_database().unwrap();
+        let database = database.read().unwrap();
+        let table = database.get_table("pricey").unwrap();
+        let reader = table.read().unwrap().reader();
+        let schema = reader.schema.read().unwrap();
+        schema
+            .get_vec()
+            .iter()
+            .map(|(name, info)| (name.clone(), info.datatype().to_string()))
+            .collect()
+    };
+    assert_eq!(
+        columns,
+        vec![
+            ("name".to_string(), "TXT".to_string()),
+            ("price".to_string(), "NUM".to_string())
+        ]
+    );
+    assert_eq!(
+        _select_grid(&session, "SELECT * FROM pricey"),
+        vec![vec!["Lamp", "60"], vec!["Desk", "150"]]
+    );
+    assert!(_execute(&session, "CREATE TABLE pricey AS SELECT name FROM products").is_err());
+}
+
+#[test]
+fn insert_select_copies_filtered_rows() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE cheap (id INT PRIMARY KEY, name VARCHAR(50), price INT)",
+    )
+    .unwrap();
+
+    let result = _execute(
+        &session,
+        "INSERT INTO cheap SELECT id, name, price FROM products WHERE price < 100",
+    )
+    .unwrap();
+
+    assert_eq!(result.n_rows_processed, Some(2));
+    assert_eq!(
+        _select_grid(&session, "SELECT name FROM cheap"),
+        vec![vec!["Pen"], vec!["Lamp"]]
+    );
+}
+
+#[test]
+fn insert_select_checks_every_row_before_writing() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE codes (id INT PRIMARY KEY, code VARCHAR(10))",
+    )
+    .unwrap();
+    _execute(&session, "INSERT INTO codes VALUES (1, '7'), (2, 'x')").unwrap();
+    _execute(
+        &session,
+        "CREATE TABLE numbers (id INT PRIMARY KEY, code INT)",
+    )
+    .unwrap();
+
+    let wrong_type = _execute(&session, "INSERT INTO numbers SELECT id, code FROM codes");
+    let wrong_count = _execute(&session, "INSERT INTO numbers SELECT id FROM codes");
+
+    assert_eq!(
+        wrong_type.err(),
+        Some("invalid x: not a whole number, on column 'code' (NUM)".to_string())
+    );
+    assert_eq!(
+        wrong_count.err(),
+        Some("invalid data: schema has 2 column(s), but 1 were provided".to_string())
+    );
+    assert!(_select_grid(&session, "SELECT * FROM numbers").is_empty());
+}
+
+#[test]
+fn shutdown_persists_the_registry() {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    let session = Arc::new(RwLock::new(Session::client(&registry)));
+    _execute(&session, "CREATE DATABASE shop").unwrap();
+    _execute(&session, "USE shop").unwrap();
+    _execute(
+        &session,
+        "CREATE TABLE items (id INT PRIMARY KEY, name VARCHAR(50))",
+    )
+    .unwrap();
+    _execute(
+        &session,
+        "INSERT INTO items VALUES (1, 'Anvil'), (2, 'Bellows')",
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join("ferrum_shutdown_persists_the_registry.json");
+    let _ = std::fs::remove_file(&path);
+
+    shutdown(&registry, Some(&path), None).unwrap();
+
+    let data = std::fs::read_to_string(&path).unwrap();
+    assert!(data.contains("items"));
+    assert!(data.contains("Anvil"));
+    assert!(data.contains("Bellows"));
+}
+
+#[test]
+fn shutdown_without_a_registry_file_keeps_data_in_memory() {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+
+    assert!(shutdown(&registry, None, None).is_ok());
+}
+
+#[test]
+fn repl_control_words_ignore_case() {
+    assert_eq!(ReplInput::parse("CORRODE\n"), ReplInput::Corrode);
+    assert_eq!(ReplInput::parse("  Help "), ReplInput::Help);
+    assert_eq!(ReplInput::parse("\\DUMP"), ReplInput::Dump);
+    assert_eq!(
+        ReplInput::parse(" SELECT Name FROM products\n"),
+        ReplInput::Sql("SELECT Name FROM products")
+    );
+}
+
+#[test]
+fn quiet_client_prints_only_results() {
+    let mut client = Command::new(env!("CARGO_BIN_EXE_ferrum_engine"))
+        .args(["client", "--quiet"])
+        .current_dir(std::env::temp_dir())
+        .env_remove("FERRUM_REGISTRY_FILE")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    client
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            b"CREATE DATABASE shop; USE shop; CREATE TABLE items (id INT, name VARCHAR(9)); \
+              INSERT INTO items VALUES (7, 'Gadget'); SELECT name FROM items;\ncorrode\n",
+        )
+        .unwrap();
+    let output = client.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("| Gadget"));
+    for chatter in ["███", "Version", "info", "parsed successfully", "Selecting"] {
+        assert!(
+            !stdout.contains(chatter),
+            "found '{}' in: {}",
+            chatter,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn prepared_statement_binds_params() {
+    let session = _prepare_session();
+    let parser = SqlParser::new(Box::new(MySqlDialect {}));
+    let insert = parser
+        .prepare("INSERT INTO products VALUES (?, ?, ?)")
+        .unwrap();
+
+    assert_eq!(insert.param_count(), 3);
+    insert
+        .execute(
+            vec![
+                "4".to_string(),
+                "It's a Chair".to_string(),
+                "80".to_string(),
+            ],
+            &session,
+        )
+        .unwrap();
+
+    let select = parser
+        .prepare("SELECT id, name FROM products WHERE id = $1")
+        .unwrap();
+    let result = select.execute(vec!["4".to_string()], &session).unwrap();
+    let rows = result.table.unwrap().scan();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get_str(1).as_deref(), Some("It's a Chair"));
+}
+
+#[test]
+fn prepared_statement_fails_on_missing_params() {
+    let session = _prepare_session();
+    let parser = SqlParser::new(Box::new(MySqlDialect {}));
+    let insert = parser
+        .prepare("INSERT INTO products VALUES (?, ?, ?)")
+        .unwrap();
+
+    assert_eq!(
+        insert
+            .execute(vec!["4".to_string(), "Chair".to_string()], &session)
+            .err(),
+        Some(EngineError::Other(
+            "invalid params: 3 expected, 2 provided".to_string()
+        ))
+    );
+    assert!(
+        parser
+            .prepare("SELECT * FROM products WHERE id = ? OR id = $2")
+            .is_err()
+    );
+}
+
+#[test]
+fn prepared_statement_fails_on_type_mismatch() {
+    let session = _prepare_session();
+    let parser = SqlParser::new(Box::new(MySqlDialect {}));
+    let insert = parser
+        .prepare("INSERT INTO products VALUES (?, ?, ?)")
+        .unwrap();
+
+    let result = insert.execute(
+        vec!["4".to_string(), "Chair".to_string(), "cheap".to_string()],
+        &session,
+    );
+
+    assert!(matches!(
+        result.err(),
+        Some(EngineError::TypeMismatch(message)) if message.contains("invalid cheap: not a whole number")
+    ));
+}
+
+#[test]
+fn execute_errors_tell_their_kind() {
+    let session = _prepare_session();
+    _execute(
+        &session,
+        "CREATE TABLE orders (id INT, product_id INT, PRIMARY KEY (id), FOREIGN KEY (product_id) REFERENCES products(id))",
+    )
+    .unwrap();
+
+    let code = |sql: &str| _execute_error(&session, sql).code();
+    assert_eq!(code("INSERT INTO missing VALUES (1)"), "table_not_found");
+    assert_eq!(code("SELECT id FROM missing"), "table_not_found");
+    assert_eq!(code("SELECT nmae FROM products"), "column_not_found");
+    assert_eq!(
+        code("INSERT INTO products VALUES (4, 'Chair', 'cheap')"),
+        "type_mismatch"
+    );
+    assert_eq!(
+        code("INSERT INTO products VALUES (4, NULL, 10)"),
+        "null_violation"
+    );
+    assert_eq!(
+        code("INSERT INTO orders VALUES (1, 9)"),
+        "foreign_key_violation"
+    );
+    assert_eq!(code("SELEC * FROM products"), "parse_error");
+    assert_eq!(code("ROLLBACK TO SAVEPOINT a"), "other");
+
+    assert_eq!(
+        _execute_error(&session, "INSERT INTO products VALUES (4, NULL, 10)"),
+        EngineError::NullViolation("invalid NULL: not allowed on column 'name'".to_string())
+    );
+    assert_eq!(
+        _execute(&session, "INSERT INTO products VALUES (4, NULL, 10)").err(),
+        Some("invalid NULL: not allowed on column 'name'".to_string())
+    );
+}
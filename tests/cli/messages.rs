@@ -0,0 +1,56 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use ferrum_engine::{
+    cli::{closest_name, database_list_message, format_duration, processed_message},
+    persistence::DatabaseRegistry,
+    sessions::session::Session,
+};
+
+#[test]
+fn format_duration_picks_a_fitting_unit() {
+    assert_eq!(format_duration(Duration::from_micros(820)), "820µs");
+    assert_eq!(format_duration(Duration::from_micros(1_820)), "1.82ms");
+    assert_eq!(format_duration(Duration::from_millis(2_310)), "2.31s");
+}
+
+#[test]
+fn processed_message_shows_timing_when_on() {
+    let started = Instant::now();
+    let message = processed_message(3, Some(started.elapsed()));
+
+    assert!(message.starts_with("3 row(s) in "));
+    assert!(message.ends_with("s"));
+    assert_eq!(processed_message(3, None), "3 row(s) processed!");
+}
+
+#[test]
+fn database_list_message_marks_the_active_database() {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    let mut session = Session::client(&registry);
+    session.create_database("shop", false).unwrap();
+    session.create_database("stock", false).unwrap();
+    session.use_database("stock").unwrap();
+
+    let message = database_list_message(&session.get_available_databases(), Some("stock"));
+    let lines: Vec<&str> = message.lines().collect();
+
+    assert_eq!(lines[0], "There are 2 databases in the registry.");
+    assert!(lines[1].starts_with("  ") && lines[1].contains("shop"));
+    assert!(lines[2].starts_with("* ") && lines[2].contains("stock"));
+    assert_eq!(
+        database_list_message(&[], None),
+        "There are no databases in the registry yet."
+    );
+}
+
+#[test]
+fn closest_name_suggests_near_names_only() {
+    let columns = ["id", "name", "price"];
+
+    assert_eq!(closest_name("nmae", columns), Some("name"));
+    assert_eq!(closest_name("prce", columns), Some("price"));
+    assert_eq!(closest_name("weight", columns), None);
+}
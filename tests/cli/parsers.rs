@@ -0,0 +1,10 @@
+use clap::Parser;
+use ferrum_engine::cli::parsers::CliParser;
+
+#[test]
+fn max_connections_rejects_zero() {
+    assert!(CliParser::try_parse_from(["ferrum", "server", "--max-connections", "0"]).is_err());
+
+    let args = CliParser::try_parse_from(["ferrum", "server", "--max-connections", "4"]).unwrap();
+    assert_eq!(args.max_connections, Some(4));
+}
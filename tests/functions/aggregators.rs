@@ -0,0 +1,291 @@
+use ferrum_engine::functions::aggregators::{
+    self, Accumulator, Aggregate, AggregateResolver, Collation,
+};
+use ferrum_engine::persistence::{ColumnInformation, DataType, Row, Schema, Value};
+
+struct Longest;
+
+impl Aggregate for Longest {
+    fn run(&self, args: &Vec<String>, rows: &Vec<Row>) -> Result<String, String> {
+        let col_index = args
+            .first()
+            .and_then(|arg| arg.parse::<usize>().ok())
+            .ok_or("LONGEST takes in a single column.".to_string())?;
+
+        rows.iter()
+            .filter_map(|row| row.get_str(col_index).map(String::from))
+            .max_by_key(|value| value.len())
+            .ok_or("LONGEST found no values.".to_string())
+    }
+}
+
+fn _rows(values: &[&str]) -> Vec<Row> {
+    values
+        .iter()
+        .map(|value| Row(vec![Value::Text(value.to_string())]))
+        .collect()
+}
+
+#[test]
+fn resolver_runs_registered_aggregator() {
+    let mut resolver = AggregateResolver::new();
+    let rows = _rows(&["ant", "beetle", "moth"]);
+
+    assert!(!resolver.is_registered(&"LONGEST".to_string()));
+    resolver.register("LONGEST".to_string(), Box::new(Longest));
+
+    let value = resolver
+        .run(&"LONGEST".to_string(), &vec!["0".to_string()], &rows)
+        .unwrap();
+    assert_eq!(value, "beetle");
+}
+
+#[test]
+fn resolver_unknown_aggregator_fails() {
+    let resolver = AggregateResolver::new();
+    let rows = _rows(&["ant"]);
+
+    assert!(
+        resolver
+            .run(&"COUNT".to_string(), &vec!["*".to_string()], &rows)
+            .is_err()
+    );
+}
+
+#[test]
+fn default_resolver_has_builtin_aggregators() {
+    let resolver = AggregateResolver::default();
+    let rows = _rows(&["3", "12", "5"]);
+    let args = vec!["0".to_string()];
+
+    assert_eq!(
+        resolver
+            .run(&"COUNT".to_string(), &vec!["*".to_string()], &rows)
+            .unwrap(),
+        "3"
+    );
+    assert_eq!(
+        resolver.run(&"SUM".to_string(), &args, &rows).unwrap(),
+        "20"
+    );
+    assert_eq!(
+        aggregators::run(&"sum".to_string(), &args, &rows).unwrap(),
+        "20"
+    );
+}
+
+#[test]
+fn min_max_text_collations() {
+    let rows = _rows(&["banana", "Cherry", "apple", "Banana"]);
+    let binary = vec!["0".to_string()];
+    let nocase = vec!["0".to_string(), "nocase".to_string()];
+
+    let run = |name: &str, args: &Vec<String>| aggregators::run(&name.to_string(), args, &rows);
+
+    assert_eq!(run("MIN", &binary).unwrap(), "Banana");
+    assert_eq!(run("MAX", &binary).unwrap(), "banana");
+    assert_eq!(run("MIN", &nocase).unwrap(), "apple");
+    assert_eq!(run("MAX", &nocase).unwrap(), "Cherry");
+
+    let mut accumulator = Accumulator::new("MAX", &nocase).unwrap();
+    for row in rows.iter() {
+        accumulator.update(row).unwrap();
+    }
+    assert_eq!(accumulator.finish().unwrap(), "Cherry");
+
+    assert_eq!(Collation::from_name("BINARY"), Ok(Collation::Binary));
+    assert!(run("MIN", &vec!["0".to_string(), "german".to_string()]).is_err());
+}
+
+#[test]
+fn count_distinct_skips_repeats_and_nulls() {
+    let rows = vec![
+        Row(vec![Value::Text("tools".to_string())]),
+        Row(vec![Value::Null]),
+        Row(vec![Value::Text("toys".to_string())]),
+        Row(vec![Value::Text("tools".to_string())]),
+        Row(vec![Value::Null]),
+    ];
+    let plain = vec!["0".to_string()];
+    let distinct = vec![aggregators::DISTINCT_TOKEN.to_string(), "0".to_string()];
+
+    let count = |args: &Vec<String>| aggregators::run(&"COUNT".to_string(), args, &rows);
+    assert_eq!(count(&plain).unwrap(), "3");
+    assert_eq!(count(&distinct).unwrap(), "2");
+
+    let mut accumulator = Accumulator::new("COUNT", &distinct).unwrap();
+    for row in rows.iter() {
+        accumulator.update(row).unwrap();
+    }
+    assert_eq!(accumulator.finish().unwrap(), "2");
+}
+
+#[test]
+fn group_concat_joins_non_null_values() {
+    let rows = vec![
+        Row(vec![Value::Text("ant".to_string())]),
+        Row(vec![Value::Null]),
+        Row(vec![Value::Text("moth".to_string())]),
+        Row(vec![Value::Text("bee".to_string())]),
+    ];
+    let default = vec!["0".to_string()];
+    let custom = vec!["0".to_string(), "' | '".to_string()];
+
+    let concat = |name: &str, args: &Vec<String>| aggregators::run(&name.to_string(), args, &rows);
+    assert_eq!(concat("GROUP_CONCAT", &default).unwrap(), "ant,moth,bee");
+    assert_eq!(concat("STRING_AGG", &custom).unwrap(), "ant | moth | bee");
+
+    let mut accumulator = Accumulator::new("GROUP_CONCAT", &custom).unwrap();
+    for row in rows.iter() {
+        accumulator.update(row).unwrap();
+    }
+    assert_eq!(accumulator.finish().unwrap(), "ant | moth | bee");
+}
+
+#[test]
+fn first_last_follow_row_order() {
+    let unordered = vec![
+        Row(vec![Value::Null]),
+        Row(vec![Value::Text("moth".to_string())]),
+        Row(vec![Value::Text("ant".to_string())]),
+        Row(vec![Value::Text("bee".to_string())]),
+        Row(vec![Value::Null]),
+    ];
+    let mut ordered = unordered.clone();
+    ordered.sort_by(|left, right| left.cells()[0].cmp(&right.cells()[0]));
+
+    let args = vec!["0".to_string()];
+    let evaluate = |name: &str, rows: &Vec<Row>| aggregators::evaluate(name, &args, rows);
+
+    assert_eq!(evaluate("FIRST", &unordered), Ok(Some("moth".to_string())));
+    assert_eq!(evaluate("LAST", &unordered), Ok(Some("bee".to_string())));
+    assert_eq!(evaluate("FIRST", &ordered), Ok(Some("ant".to_string())));
+    assert_eq!(evaluate("LAST", &ordered), Ok(Some("moth".to_string())));
+
+    let mut accumulator = Accumulator::new("LAST", &args).unwrap();
+    for row in unordered.iter() {
+        accumulator.update(row).unwrap();
+    }
+    assert_eq!(accumulator.evaluate(), Ok(Some("bee".to_string())));
+}
+
+#[test]
+fn first_last_over_nulls_is_null() {
+    let rows = vec![Row(vec![Value::Null]), Row(vec![Value::Null])];
+    let args = vec!["0".to_string()];
+
+    assert_eq!(aggregators::evaluate("FIRST", &args, &rows), Ok(None));
+    assert_eq!(aggregators::evaluate("LAST", &args, &vec![]), Ok(None));
+    assert!(aggregators::run(&"FIRST".to_string(), &args, &rows).is_err());
+    assert_eq!(
+        aggregators::evaluate("COUNT", &args, &rows),
+        Ok(Some("0".to_string()))
+    );
+}
+
+#[test]
+fn max_compares_numbers_by_value() {
+    let rows = _rows(&["2", "100", "10"]);
+    let args = vec!["0".to_string(), "numeric".to_string()];
+
+    assert_eq!(
+        aggregators::run(&"MAX".to_string(), &args, &rows).unwrap(),
+        "100"
+    );
+
+    let mut accumulator = Accumulator::new("MAX", &args).unwrap();
+    for row in rows.iter() {
+        accumulator.update(row).unwrap();
+    }
+    assert_eq!(accumulator.finish().unwrap(), "100");
+
+    assert!(aggregators::run(&"MAX".to_string(), &args, &vec![]).is_err());
+}
+
+#[test]
+fn min_max_compare_text_columns_as_text() {
+    let schema = Schema::new(vec![(
+        "code".to_string(),
+        ColumnInformation::from(DataType::Text, Some(10), false),
+    )]);
+    let rows = _rows(&["02134", "9", "2134", "10", "NaN"]);
+    let run = |name: &str, args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        aggregators::run_with_schema(&name.to_string(), &args, &schema, &rows)
+    };
+
+    assert_eq!(run("MIN", &["code"]).unwrap(), "02134");
+    assert_eq!(run("MAX", &["code"]).unwrap(), "NaN");
+    assert_eq!(run("MIN", &["code", "nocase"]).unwrap(), "02134");
+    assert_eq!(run("MAX", &["code", "nocase"]).unwrap(), "NaN");
+
+    let mut accumulator = Accumulator::new("MIN", &vec!["0".to_string()]).unwrap();
+    for row in _rows(&["9", "10", "02134", "2134"]).iter() {
+        accumulator.update(row).unwrap();
+    }
+    assert_eq!(accumulator.finish().unwrap(), "02134");
+}
+
+#[test]
+fn sum_count_reject_non_column_arguments() {
+    let rows = _rows(&["3", "12"]);
+    let quoted = vec!["'x'".to_string()];
+
+    for name in ["SUM", "COUNT"] {
+        let name = name.to_string();
+        assert_eq!(
+            aggregators::run(&name, &quoted, &rows).err(),
+            Some(format!("{} strictly allows a single column.", name))
+        );
+    }
+    assert!(aggregators::run(&"COUNT".to_string(), &vec![], &rows).is_err());
+}
+
+#[test]
+fn min_max_over_no_values_fail() {
+    let args = vec!["0".to_string()];
+    let all_null = vec![Row(vec![Value::Null]), Row(vec![Value::Null])];
+
+    for name in ["MIN", "MAX"] {
+        let name = name.to_string();
+        assert_eq!(
+            aggregators::run(&name, &args, &vec![]).err(),
+            Some(format!("{} found no values to compare.", name))
+        );
+        assert!(aggregators::run(&name, &args, &all_null).is_err());
+
+        let mut accumulator = Accumulator::new(&name, &args).unwrap();
+        for row in all_null.iter() {
+            accumulator.update(row).unwrap();
+        }
+        assert!(accumulator.finish().is_err());
+    }
+}
+
+#[test]
+fn run_with_schema_resolves_column_names() {
+    let schema = Schema::new(vec![
+        (
+            "id".to_string(),
+            ColumnInformation::from(DataType::Number, None, false),
+        ),
+        (
+            "price".to_string(),
+            ColumnInformation::from(DataType::Number, None, false),
+        ),
+    ]);
+    let rows = vec![
+        Row(vec![Value::Number(1), Value::Number(30)]),
+        Row(vec![Value::Number(2), Value::Number(12)]),
+    ];
+    let run = |name: &str, args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        aggregators::run_with_schema(&name.to_string(), &args, &schema, &rows)
+    };
+
+    assert_eq!(run("SUM", &["price"]).unwrap(), "42");
+    assert_eq!(run("MAX", &["1"]).unwrap(), "30");
+    assert_eq!(run("COUNT", &["DISTINCT", "id"]).unwrap(), "2");
+    assert_eq!(run("COUNT", &["*"]).unwrap(), "2");
+    assert!(run("SUM", &["cost"]).is_err());
+}
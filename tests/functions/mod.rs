@@ -0,0 +1,2 @@
+mod aggregators;
+mod scalars;
@@ -0,0 +1,363 @@
+use ferrum_engine::functions::scalars::{self, Scalar, ScalarResolver};
+use ferrum_engine::persistence::{Row, Value};
+
+struct Repeat;
+
+impl Scalar for Repeat {
+    fn run(&self, args: &Vec<String>, row: &Row) -> Result<String, String> {
+        let col_index = args
+            .first()
+            .and_then(|arg| arg.parse::<usize>().ok())
+            .ok_or("REPEAT takes in a column and a count.".to_string())?;
+        let times = args
+            .get(1)
+            .and_then(|arg| arg.parse::<usize>().ok())
+            .ok_or("REPEAT takes in a column and a count.".to_string())?;
+
+        let value = row
+            .get_str(col_index)
+            .ok_or("REPEAT found a NULL value.".to_string())?;
+
+        Ok(value.repeat(times))
+    }
+}
+
+fn _row(values: &[&str]) -> Row {
+    Row(values
+        .iter()
+        .map(|value| Value::Text(value.to_string()))
+        .collect())
+}
+
+#[test]
+fn resolver_runs_registered_scalar() {
+    let mut resolver = ScalarResolver::new();
+    let row = _row(&["1", "ab"]);
+    let args = vec!["1".to_string(), "3".to_string()];
+
+    assert!(!resolver.is_registered(&"REPEAT".to_string()));
+    resolver.register("REPEAT".to_string(), Box::new(Repeat));
+
+    let value = resolver.run(&"REPEAT".to_string(), &args, &row).unwrap();
+    assert_eq!(value, "ababab");
+}
+
+#[test]
+fn resolver_unknown_scalar_fails() {
+    let resolver = ScalarResolver::new();
+    let row = _row(&["1"]);
+
+    assert!(
+        resolver
+            .run(&"ADD".to_string(), &vec!["0".to_string()], &row)
+            .is_err()
+    );
+}
+
+#[test]
+fn default_resolver_has_add() {
+    let row = _row(&["1", "40"]);
+    let args = vec!["1".to_string(), "'2'".to_string()];
+
+    assert_eq!(scalars::run(&"ADD".to_string(), &args, &row).unwrap(), "42");
+    assert!(scalars::get_runner(&"NOPE".to_string()).is_err());
+}
+
+fn _run(name: &str, args: &[&str], row: &Row) -> Result<String, String> {
+    let args = args.iter().map(|arg| arg.to_string()).collect();
+    scalars::run(&name.to_string(), &args, row)
+}
+
+fn _evaluate(name: &str, args: &[&str], row: &Row) -> Result<Option<String>, String> {
+    let args = args.iter().map(|arg| arg.to_string()).collect();
+    scalars::evaluate(&name.to_string(), &args, row)
+}
+
+#[test]
+fn add_missing_arguments_fails() {
+    let row = _row(&["1", "40"]);
+
+    assert!(_run("ADD", &[], &row).is_err());
+    assert!(_run("ADD", &["1"], &row).is_err());
+    assert!(_run("ADD", &["name", "2"], &row).is_err());
+}
+
+#[test]
+fn add_non_numeric_value_fails() {
+    let row = _row(&["1", "40"]);
+
+    assert!(_run("ADD", &["1", "'2.5.1'"], &row).is_err());
+    assert!(_run("ADD", &["1", "'two'"], &row).is_err());
+}
+
+#[test]
+fn add_invalid_cell_fails() {
+    let row = Row(vec![Value::Number(1), Value::Null, Value::Text("pen".to_string())]);
+
+    assert!(_run("ADD", &["1", "'2'"], &row).is_err());
+    assert!(_run("ADD", &["2", "'2'"], &row).is_err());
+    assert!(_run("ADD", &["5", "'2'"], &row).is_err());
+}
+
+#[test]
+fn sub_subtracts_value() {
+    let row = _row(&["1", "40"]);
+
+    assert_eq!(_run("SUB", &["1", "'15'"], &row).unwrap(), "25");
+    assert_eq!(_run("SUB", &["1", "'41'"], &row).unwrap(), "-1");
+}
+
+#[test]
+fn arithmetic_overflow_fails() {
+    let row = _row(&["1", &i64::MAX.to_string(), &i64::MIN.to_string()]);
+
+    assert_eq!(
+        _run("ADD", &["1", "'1'"], &row).err(),
+        Some("ADD overflowed the numeric limit.".to_string())
+    );
+    assert_eq!(
+        _run("SUB", &["2", "'1'"], &row).err(),
+        Some("SUB overflowed the numeric limit.".to_string())
+    );
+    assert_eq!(
+        _run("DIV", &["2", "'-1'"], &row).err(),
+        Some("DIV overflowed the numeric limit.".to_string())
+    );
+}
+
+#[test]
+fn mul_multiplies_value() {
+    let row = _row(&["1", "40"]);
+
+    assert_eq!(_run("MUL", &["1", "'3'"], &row).unwrap(), "120");
+    assert!(_run("MUL", &["1", "'x'"], &row).is_err());
+}
+
+#[test]
+fn div_divides_value() {
+    let row = _row(&["1", "40"]);
+
+    assert_eq!(_run("DIV", &["1", "'3'"], &row).unwrap(), "13");
+    assert!(_run("DIV", &["1", "'0'"], &row).is_err());
+}
+
+#[test]
+fn mod_gives_remainder() {
+    let row = _row(&["1", "40"]);
+
+    assert_eq!(_run("MOD", &["1", "'3'"], &row).unwrap(), "1");
+    assert!(_run("MOD", &["1", "'0'"], &row).is_err());
+}
+
+#[test]
+fn upper_converts_case() {
+    let row = _row(&["1", "Desk Lamp"]);
+
+    assert_eq!(_run("UPPER", &["1"], &row).unwrap(), "DESK LAMP");
+}
+
+#[test]
+fn lower_converts_case() {
+    let row = _row(&["1", "Desk Lamp"]);
+
+    assert_eq!(_run("LOWER", &["1"], &row).unwrap(), "desk lamp");
+}
+
+#[test]
+fn length_counts_characters() {
+    let row = _row(&["1", "café"]);
+
+    assert_eq!(_run("LENGTH", &["1"], &row).unwrap(), "4");
+}
+
+#[test]
+fn trim_removes_whitespace() {
+    let row = _row(&["1", "  pen \t"]);
+
+    assert_eq!(_run("TRIM", &["1"], &row).unwrap(), "pen");
+}
+
+#[test]
+fn text_scalars_fail_on_null() {
+    let row = Row(vec![Value::Number(1), Value::Null]);
+
+    for name in ["UPPER", "LOWER", "LENGTH", "TRIM"] {
+        assert!(_run(name, &["1"], &row).is_err());
+    }
+}
+
+#[test]
+fn add_column_to_column() {
+    let row = _row(&["1", "40", "2"]);
+
+    assert_eq!(_run("ADD", &["1", "2"], &row).unwrap(), "42");
+}
+
+#[test]
+fn concat_joins_columns() {
+    let row = _row(&["1", "Desk", "Lamp"]);
+
+    assert_eq!(_run("CONCAT", &["1", "2"], &row).unwrap(), "DeskLamp");
+}
+
+#[test]
+fn concat_joins_column_with_literal() {
+    let row = _row(&["1", "Desk"]);
+
+    assert_eq!(
+        _run("CONCAT", &["1", "' - '", "0"], &row).unwrap(),
+        "Desk - 1"
+    );
+}
+
+#[test]
+fn concat_skips_null() {
+    let row = Row(vec![Value::Number(1), Value::Null, Value::Text("Lamp".to_string())]);
+
+    assert_eq!(
+        _run("CONCAT", &["1", "'Desk '", "2"], &row).unwrap(),
+        "Desk Lamp"
+    );
+    assert!(_run("CONCAT", &[], &row).is_err());
+}
+
+#[test]
+fn coalesce_skips_null_column() {
+    let row = Row(vec![Value::Null, Value::Text("backup".to_string())]);
+
+    assert_eq!(
+        _evaluate("COALESCE", &["0", "1"], &row).unwrap(),
+        Some("backup".to_string())
+    );
+}
+
+#[test]
+fn coalesce_all_null_is_null() {
+    let row = Row(vec![Value::Null, Value::Null]);
+
+    assert_eq!(_evaluate("COALESCE", &["0", "1"], &row).unwrap(), None);
+}
+
+#[test]
+fn coalesce_literal_fallback() {
+    let row = Row(vec![Value::Null, Value::Null]);
+
+    assert_eq!(
+        _evaluate("COALESCE", &["0", "1", "'default'"], &row).unwrap(),
+        Some("default".to_string())
+    );
+}
+
+#[test]
+fn round_with_and_without_precision() {
+    let row = _row(&["1", "-2.567"]);
+
+    assert_eq!(_run("ROUND", &["1"], &row).unwrap(), "-3");
+    assert_eq!(_run("ROUND", &["1", "'2'"], &row).unwrap(), "-2.57");
+    assert_eq!(_run("ROUND", &["1", "'0'"], &row).unwrap(), "-3");
+    assert!(_run("ROUND", &["1", "'-1'"], &row).is_err());
+}
+
+#[test]
+fn ceil_and_floor_negative_values() {
+    let row = _row(&["1", "-2.5"]);
+
+    assert_eq!(_run("CEIL", &["1"], &row).unwrap(), "-2");
+    assert_eq!(_run("FLOOR", &["1"], &row).unwrap(), "-3");
+}
+
+#[test]
+fn abs_negative_value() {
+    let row = _row(&["1", "-40"]);
+
+    assert_eq!(_run("ABS", &["1"], &row).unwrap(), "40");
+}
+
+#[test]
+fn numeric_scalars_fail_on_non_numeric_and_null() {
+    let text_row = _row(&["1", "pen"]);
+    let null_row = Row(vec![Value::Number(1), Value::Null]);
+
+    for name in ["ROUND", "CEIL", "FLOOR", "ABS"] {
+        assert!(_run(name, &["1"], &text_row).is_err());
+        assert!(_run(name, &["1"], &null_row).is_err());
+    }
+}
+
+#[test]
+fn date_scalars_extract_components() {
+    let row = _row(&["1", "2024-02-09"]);
+
+    assert_eq!(_run("YEAR", &["1"], &row).unwrap(), "2024");
+    assert_eq!(_run("MONTH", &["1"], &row).unwrap(), "2");
+    assert_eq!(_run("DAY", &["1"], &row).unwrap(), "9");
+}
+
+#[test]
+fn date_scalars_fail_on_non_date() {
+    let row = _row(&["1", "yesterday"]);
+
+    for name in ["YEAR", "MONTH", "DAY"] {
+        assert!(_run(name, &["1"], &row).is_err());
+    }
+}
+
+#[test]
+fn now_returns_parseable_timestamp() {
+    let row = _row(&["1"]);
+
+    let now = _run("NOW", &[], &row).unwrap();
+    assert!(chrono::NaiveDateTime::parse_from_str(&now, scalars::TIMESTAMP_FORMAT).is_ok());
+    assert!(_run("NOW", &["0"], &row).is_err());
+}
+
+#[test]
+fn json_extract_navigates_the_document() {
+    let row = _row(&[
+        "1",
+        r#"{"name":"Jansen","address":{"cities":["Lahore","Oslo"],"zip":54000}}"#,
+    ]);
+    let extract = |path: &str| {
+        _evaluate(
+            "JSON_EXTRACT",
+            &["1", &scalars::literal_argument(path)],
+            &row,
+        )
+    };
+
+    assert_eq!(extract("$.name").unwrap(), Some("Jansen".to_string()));
+    assert_eq!(
+        extract("$.address.cities[1]").unwrap(),
+        Some("Oslo".to_string())
+    );
+    assert_eq!(extract("$.address.zip").unwrap(), Some("54000".to_string()));
+    assert_eq!(
+        extract("$.address.cities").unwrap(),
+        Some(r#"["Lahore","Oslo"]"#.to_string())
+    );
+    assert_eq!(extract("$.address.country").unwrap(), None);
+    assert!(extract("address").is_err());
+}
+
+#[test]
+fn uuid_returns_parseable_value() {
+    let row = _row(&["1"]);
+
+    let first = _run("UUID", &[], &row).unwrap();
+    let second = _run("UUID", &[], &row).unwrap();
+
+    let uuid = uuid::Uuid::parse_str(&first).unwrap();
+    assert_eq!(uuid.get_version_num(), 4);
+    assert_ne!(first, second);
+    assert!(_run("UUID", &["0"], &row).is_err());
+}
+
+#[test]
+fn arithmetic_preserves_decimal_scale() {
+    let row = _row(&["5.25", "2", "0.10"]);
+
+    assert_eq!(_run("ADD", &["0", "1"], &row).unwrap(), "7.25");
+    assert_eq!(_run("SUB", &["2", "1"], &row).unwrap(), "-1.90");
+    assert_eq!(_run("MUL", &["0", "1"], &row).unwrap(), "10.50");
+    assert_eq!(_run("ADD", &["1", "1"], &row).unwrap(), "4");
+}
@@ -1 +1,5 @@
+mod cli;
+mod functions;
 mod persistence;
+mod serialization;
+mod sessions;
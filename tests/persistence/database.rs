@@ -1,6 +1,11 @@
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
-use ferrum_engine::persistence::{Database, Row, Table};
+use ferrum_engine::errors::EngineError;
+use ferrum_engine::persistence::{
+    ColumnChange, ColumnDiff, Database, DatabaseConfig, DatabaseRegistry, LoadOptions,
+    LoadProgress, OnError, Row, Table, TableDiff,
+};
 
 fn _prepare_database() -> Database {
     let db_name = "test_db".to_string();
@@ -92,6 +97,190 @@ fn database_create_table_with_fk_fail() {
     _create_table(&mut database, "test_tb2".to_string(), columns, values).unwrap();
 }
 
+#[test]
+fn database_create_table_with_self_referencing_fk() {
+    let mut database = _prepare_database();
+    let columns = vec![
+        "id num pk".to_string(),
+        "manager_id num fk employees.id null".to_string(),
+    ];
+    let values = [("1", ""), ("2", "1"), ("3", "1")]
+        .iter()
+        .map(|(id, manager_id)| vec![id.to_string(), manager_id.to_string()])
+        .collect();
+
+    let employees = _create_table(&mut database, "employees".to_string(), columns, values).unwrap();
+    let dangling = database.insert_into_table("employees", vec!["4".to_string(), "9".to_string()]);
+
+    assert_eq!(employees.read().unwrap().row_count(), 3);
+    assert_eq!(
+        dangling.err(),
+        Some(EngineError::ForeignKeyViolation(
+            "err: does not exist: `9` in `employees.id`".to_string()
+        ))
+    );
+}
+
+#[test]
+fn database_create_table_with_missing_fk_parent_fails() {
+    let mut database = _prepare_database();
+    database
+        .create_table(
+            "parents".to_string(),
+            vec!["id num pk".to_string(), "name txt".to_string()],
+        )
+        .unwrap();
+
+    let missing_table = database.create_table(
+        "children".to_string(),
+        vec![
+            "id num pk".to_string(),
+            "parent_id num fk guardians.id".to_string(),
+        ],
+    );
+    let missing_column = database.create_table(
+        "children".to_string(),
+        vec![
+            "id num pk".to_string(),
+            "parent_id num fk parents.uid".to_string(),
+        ],
+    );
+
+    assert_eq!(
+        missing_table.err(),
+        Some("invalid foreign key on guardians; table doesn't exist".to_string())
+    );
+    assert!(missing_column.is_err());
+    assert!(!database.contains_table("children"));
+}
+
+#[test]
+fn database_insert_checks_fk_in_parent_index() {
+    let mut database = _prepare_database();
+    let parent_columns = vec!["id num pk".to_string(), "code txt unique".to_string()];
+    let parent_values = vec![vec!["1".to_string(), "ab".to_string()]];
+    _create_table(
+        &mut database,
+        "parents".to_string(),
+        parent_columns,
+        parent_values,
+    )
+    .unwrap();
+    database
+        .create_table(
+            "children".to_string(),
+            vec![
+                "id num pk".to_string(),
+                "parent_id num fk parents.id".to_string(),
+                "parent_code txt fk parents.code".to_string(),
+            ],
+        )
+        .unwrap();
+    let mut insert = |values: [&str; 3]| {
+        database.insert_into_table(
+            "children",
+            values.iter().map(|value| value.to_string()).collect(),
+        )
+    };
+
+    assert!(insert(["1", "1", "ab"]).is_ok());
+    assert_eq!(
+        insert(["2", "2", "ab"]).err(),
+        Some(EngineError::ForeignKeyViolation(
+            "err: does not exist: `2` in `parents.id`".to_string()
+        ))
+    );
+    assert_eq!(
+        insert(["3", "1", "cd"]).err(),
+        Some(EngineError::ForeignKeyViolation(
+            "err: does not exist: `cd` in `parents.code`".to_string()
+        ))
+    );
+}
+
+#[test]
+fn database_insert_checks_fk_against_soft_deleted_parent() {
+    let mut database = _prepare_database();
+    let parent_columns = vec!["id num pk".to_string(), "code txt unique".to_string()];
+    let parent_values = vec![
+        vec!["1".to_string(), "ab".to_string()],
+        vec!["2".to_string(), "cd".to_string()],
+    ];
+    let parents = _create_table(
+        &mut database,
+        "parents".to_string(),
+        parent_columns,
+        parent_values,
+    )
+    .unwrap();
+    database
+        .create_table(
+            "children".to_string(),
+            vec![
+                "id num pk".to_string(),
+                "parent_id num fk parents.id".to_string(),
+                "parent_code txt fk parents.code".to_string(),
+            ],
+        )
+        .unwrap();
+    parents.write().unwrap().delete_soft(vec!["1"]).unwrap();
+    let mut insert = |values: [&str; 3]| {
+        database.insert_into_table(
+            "children",
+            values.iter().map(|value| value.to_string()).collect(),
+        )
+    };
+
+    assert_eq!(
+        insert(["1", "1", "cd"]).err(),
+        Some(EngineError::ForeignKeyViolation(
+            "err: does not exist: `1` in `parents.id`".to_string()
+        ))
+    );
+    assert_eq!(
+        insert(["2", "2", "ab"]).err(),
+        Some(EngineError::ForeignKeyViolation(
+            "err: does not exist: `ab` in `parents.code`".to_string()
+        ))
+    );
+    assert!(insert(["3", "2", "cd"]).is_ok());
+}
+
+#[test]
+fn database_insert_checks_fk_after_parent_rename() {
+    let mut database = _prepare_database();
+    let parent_values = vec![vec!["1".to_string()]];
+    _create_table(
+        &mut database,
+        "parents".to_string(),
+        vec!["id num pk".to_string()],
+        parent_values,
+    )
+    .unwrap();
+    database
+        .create_table(
+            "children".to_string(),
+            vec![
+                "id num pk".to_string(),
+                "parent_id num fk parents.id".to_string(),
+            ],
+        )
+        .unwrap();
+
+    database.rename_table("parents", "guardians").unwrap();
+
+    assert!(
+        database
+            .insert_into_table("children", vec!["1".to_string(), "1".to_string()])
+            .is_ok()
+    );
+    assert!(
+        database
+            .insert_into_table("children", vec!["2".to_string(), "5".to_string()])
+            .is_err()
+    );
+}
+
 #[test]
 fn database_delete_from_table_with_pk() {
     let mut database = _prepare_database();
@@ -106,7 +295,13 @@ fn database_delete_from_table_with_pk() {
     let deleted_row = _delete_from_table(&mut database, "test_tb1", vec!["1"]);
     assert_eq!(deleted_row.is_ok(), true);
     assert_eq!(
-        deleted_row.unwrap().0.get(0).unwrap().clone().unwrap(),
+        deleted_row
+            .unwrap()
+            .cells()
+            .get(0)
+            .unwrap()
+            .clone()
+            .unwrap(),
         "1".to_string()
     );
 
@@ -114,6 +309,21 @@ fn database_delete_from_table_with_pk() {
     assert_eq!(table.read().unwrap()._rows(), 2);
 }
 
+#[test]
+fn database_get_table_shares_the_table() {
+    let mut database = _prepare_database();
+    let columns = vec!["id num pk".to_string(), "name txt".to_string()];
+    let values = vec![vec!["1".to_string(), "Jansen".to_string()]];
+    let created = _create_table(&mut database, "test_tb1".to_string(), columns, values).unwrap();
+
+    let table = database.get_table("test_tb1").unwrap();
+    assert!(Arc::ptr_eq(&table, &created));
+    drop(database);
+    assert_eq!(table.read().unwrap()._rows(), 1);
+
+    assert!(_prepare_database().get_table("test_tb2").is_none());
+}
+
 #[test]
 fn database_delete_many_from_table_with_pk() {
     let mut database = _prepare_database();
@@ -133,3 +343,427 @@ fn database_delete_many_from_table_with_pk() {
     let table = database.get_table("test_tb1").unwrap();
     assert_eq!(table.read().unwrap()._rows(), 1);
 }
+
+fn _prepare_parent_and_child(database: &mut Database) {
+    let columns = vec!["id num pk".to_string(), "name txt".to_string()];
+    let values = vec![vec!["1".to_string(), "Jansen".to_string()]];
+    _create_table(database, "parents".to_string(), columns, values).unwrap();
+
+    let columns = vec![
+        "id num pk".to_string(),
+        "parent_id num fk parents.id".to_string(),
+    ];
+    let values = vec![vec!["1".to_string(), "1".to_string()]];
+    _create_table(database, "children".to_string(), columns, values).unwrap();
+}
+
+fn _child_rows(ids: &[(&str, &str)]) -> Vec<Vec<String>> {
+    ids.iter()
+        .map(|(id, parent_id)| vec![id.to_string(), parent_id.to_string()])
+        .collect()
+}
+
+#[test]
+fn database_insert_many_into_table() {
+    let mut database = _prepare_database();
+    _prepare_parent_and_child(&mut database);
+
+    let rows = _child_rows(&[("2", "1"), ("3", "1")]);
+    assert_eq!(database.insert_many_into_table("children", rows), Ok(2));
+
+    let children = database.get_table("children").unwrap();
+    assert_eq!(children.read().unwrap()._rows(), 3);
+}
+
+#[test]
+fn database_insert_many_with_missing_foreign_key_inserts_nothing() {
+    let mut database = _prepare_database();
+    _prepare_parent_and_child(&mut database);
+
+    let rows = _child_rows(&[("2", "1"), ("3", "7"), ("4", "1")]);
+    assert_eq!(
+        database.insert_many_into_table("children", rows),
+        Err(EngineError::ForeignKeyViolation(
+            "invalid row 2: err: does not exist: `7` in `parents.id`".to_string()
+        ))
+    );
+
+    let children = database.get_table("children").unwrap();
+    assert_eq!(children.read().unwrap()._rows(), 1);
+    // The index was put back too, so the first row's key is free again
+    let rows = _child_rows(&[("2", "1")]);
+    assert_eq!(database.insert_many_into_table("children", rows), Ok(1));
+}
+
+#[test]
+fn database_drop_table() {
+    let mut database = _prepare_database();
+    _prepare_parent_and_child(&mut database);
+
+    let dropped_table = database.drop_table("children").unwrap().unwrap();
+    assert_eq!(dropped_table.read().unwrap()._rows(), 1);
+    assert!(!database.contains_table("children"));
+
+    assert!(database.drop_table("children").unwrap().is_none());
+    assert!(database.drop_table("parents").unwrap().is_some());
+}
+
+#[test]
+#[should_panic(expected = "invalid drop parents: referenced by foreign keys in children")]
+fn database_drop_referenced_table_fails() {
+    let mut database = _prepare_database();
+    _prepare_parent_and_child(&mut database);
+
+    database.drop_table("parents").unwrap();
+}
+
+#[test]
+fn database_rename_table_updates_foreign_keys() {
+    let mut database = _prepare_database();
+    _prepare_parent_and_child(&mut database);
+
+    database.rename_table("parents", "guardians").unwrap();
+
+    assert!(!database.contains_table("parents"));
+    assert_eq!(
+        database
+            .get_table("guardians")
+            .unwrap()
+            .read()
+            .unwrap()
+            .name(),
+        "guardians"
+    );
+
+    let children = database
+        .get_table("children")
+        .unwrap()
+        .read()
+        .unwrap()
+        .reader();
+    let schema = children.schema.read().unwrap();
+    assert_eq!(
+        schema.get(1).unwrap().1.foreign_key(),
+        Some(("guardians", "id"))
+    );
+
+    database
+        .insert_into_table("children", vec!["2".to_string(), "1".to_string()])
+        .unwrap();
+    assert!(database.drop_table("guardians").is_err());
+}
+
+#[test]
+fn database_rename_table_collision_fails() {
+    let mut database = _prepare_database();
+    _prepare_parent_and_child(&mut database);
+
+    assert!(database.rename_table("parents", "children").is_err());
+    assert!(database.rename_table("missing", "others").is_err());
+}
+
+#[test]
+fn database_rename_column_updates_foreign_keys() {
+    let mut database = _prepare_database();
+    _prepare_parent_and_child(&mut database);
+
+    database
+        .rename_column("parents", "id", "parent_key")
+        .unwrap();
+    assert!(
+        database
+            .rename_column("parents", "name", "parent_key")
+            .is_err()
+    );
+
+    let children = database
+        .get_table("children")
+        .unwrap()
+        .read()
+        .unwrap()
+        .reader();
+    let schema = children.schema.read().unwrap();
+    let (_, col_info) = schema.get(1).unwrap();
+
+    assert_eq!(col_info.foreign_key(), Some(("parents", "parent_key")));
+}
+
+fn _write_csv(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn database_import_csv() {
+    let mut database = _prepare_database();
+    let columns = vec![
+        "id num pk".to_string(),
+        "name txt".to_string(),
+        "note txt null".to_string(),
+    ];
+    let table = _create_table(&mut database, "people".to_string(), columns, vec![]).unwrap();
+
+    let path = _write_csv(
+        "ferrum_database_import_csv.csv",
+        "id,name,note\n1,\"Jansen, Bo\",\n2,Bonega,\"said \"\"hi\"\"\"\n",
+    );
+    let imported = database.import_csv("people", &path, true);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(imported, Ok(2));
+
+    let rows = table.read().unwrap().reader().scan();
+    assert_eq!(rows[0].cells()[1], Some("Jansen, Bo".to_string()));
+    assert_eq!(rows[0].cells()[2], None);
+    assert_eq!(rows[1].cells()[2], Some("said \"hi\"".to_string()));
+}
+
+#[test]
+fn database_import_csv_reports_failing_row() {
+    let mut database = _prepare_database();
+    let columns = vec!["id num pk".to_string(), "stock num".to_string()];
+    let table = _create_table(&mut database, "items".to_string(), columns, vec![]).unwrap();
+
+    let path = _write_csv(
+        "ferrum_database_import_csv_reports_failing_row.csv",
+        "1,10\n2,many\n3,30\n",
+    );
+    let imported = database.import_csv("items", &path, false);
+    std::fs::remove_file(&path).unwrap();
+
+    let message = imported.unwrap_err();
+    assert!(message.starts_with("invalid csv row 2:"), "{}", message);
+    assert_eq!(table.read().unwrap().reader().count_rows(), 1);
+}
+
+#[test]
+fn database_bulk_load_skips_and_reports_bad_rows() {
+    let mut database = _prepare_database();
+    let columns = vec!["id num pk".to_string(), "stock num".to_string()];
+    let table = _create_table(&mut database, "items".to_string(), columns, vec![]).unwrap();
+
+    let path = _write_csv(
+        "ferrum_database_bulk_load_skips_and_reports_bad_rows.tsv",
+        "id\tstock\n1\t10\n2\tmany\n3\t30\n3\t31\n4\t40\n",
+    );
+    let options = LoadOptions {
+        on_error: OnError::Skip,
+        progress_every: 2,
+        ..LoadOptions::tsv()
+    };
+    let mut reports: Vec<LoadProgress> = Vec::new();
+    let loaded = database.bulk_load("items", &path, &options, |progress| {
+        reports.push(progress.clone())
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    let progress = loaded.unwrap();
+    assert_eq!((progress.records, progress.loaded), (5, 3));
+    assert_eq!(progress.errors.len(), 2);
+    assert!(
+        progress.errors[0].starts_with("invalid csv row 3:"),
+        "{}",
+        progress.errors[0]
+    );
+    assert!(
+        progress.errors[1].starts_with("invalid csv row 5:"),
+        "{}",
+        progress.errors[1]
+    );
+    assert_eq!(
+        reports.iter().map(|r| r.records).collect::<Vec<usize>>(),
+        vec![2, 4, 5]
+    );
+    assert_eq!(
+        progress.to_string(),
+        "5 record(s) read, 3 loaded, 2 error(s)"
+    );
+
+    let ids: Vec<String> = table
+        .read()
+        .unwrap()
+        .reader()
+        .rows
+        .read()
+        .unwrap()
+        .iter()
+        .map(|row| row.get_str(0).unwrap().to_string())
+        .collect();
+    assert_eq!(ids, vec!["1", "3", "4"]);
+}
+
+#[test]
+fn database_bulk_load_aborts_on_first_bad_row() {
+    let mut database = _prepare_database();
+    let columns = vec!["id num pk".to_string(), "stock num".to_string()];
+    let table = _create_table(&mut database, "items".to_string(), columns, vec![]).unwrap();
+
+    let path = _write_csv(
+        "ferrum_database_bulk_load_aborts_on_first_bad_row.csv",
+        "1,10\n2,many\n3,30\n",
+    );
+    let options = LoadOptions {
+        has_header: false,
+        ..LoadOptions::new()
+    };
+    let mut last = None;
+    let loaded = database.bulk_load("items", &path, &options, |progress| {
+        last = Some(progress.clone())
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    let message = loaded.unwrap_err();
+    assert!(message.starts_with("invalid csv row 2:"), "{}", message);
+    assert_eq!(last.map(|progress| progress.loaded), Some(1));
+    assert_eq!(table.read().unwrap().reader().count_rows(), 1);
+}
+
+#[test]
+fn database_diff_reports_added_column_and_dropped_table() {
+    let mut before = _prepare_database();
+    let mut after = Database::new("test_db_v2".to_string());
+    for (database, columns) in [
+        (&mut before, vec!["id num pk", "name txt"]),
+        (&mut after, vec!["id num pk", "name txt null", "stock num"]),
+    ] {
+        let columns = columns.iter().map(|c| c.to_string()).collect();
+        _create_table(database, "items".to_string(), columns, vec![]).unwrap();
+    }
+    _create_table(
+        &mut before,
+        "legacy".to_string(),
+        vec!["id num pk".to_string()],
+        vec![],
+    )
+    .unwrap();
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.removed_tables, vec!["legacy"]);
+    assert!(diff.added_tables.is_empty());
+    assert_eq!(
+        diff.changed_tables,
+        vec![TableDiff {
+            table: "items".to_string(),
+            added_columns: vec![("stock".to_string(), "NUM NOT NULL".to_string())],
+            removed_columns: vec![],
+            changed_columns: vec![ColumnDiff {
+                column: "name".to_string(),
+                changes: vec![ColumnChange::Nullability(false, true)],
+            }],
+        }]
+    );
+    assert_eq!(
+        diff.to_string(),
+        "- table legacy\n~ table items\n    + column stock NUM NOT NULL\n    ~ column name: NOT NULL -> NULL\n"
+    );
+
+    let reverse = after.diff(&before);
+    assert_eq!(reverse.added_tables, vec!["legacy"]);
+    assert_eq!(reverse.changed_tables[0].removed_columns, vec!["stock"]);
+    assert!(before.diff(&before).is_empty());
+}
+
+#[test]
+fn database_default_text_limit_applies_to_plain_txt_columns() {
+    let config = DatabaseConfig {
+        default_text_limit: 200,
+    };
+    let mut database = Database::with_config("test_db".to_string(), config);
+    let columns = vec![
+        "id num pk".to_string(),
+        "bio txt".to_string(),
+        "code txt(5)".to_string(),
+    ];
+    database.create_table("users".to_string(), columns).unwrap();
+    let bio = "a".repeat(150);
+
+    database
+        .insert_into_table(
+            "users",
+            vec!["1".to_string(), bio.clone(), "ab".to_string()],
+        )
+        .unwrap();
+    assert!(
+        database
+            .insert_into_table(
+                "users",
+                vec!["2".to_string(), "a".repeat(201), "ab".to_string()]
+            )
+            .is_err()
+    );
+    assert!(
+        database
+            .insert_into_table(
+                "users",
+                vec!["3".to_string(), bio.clone(), "abcdef".to_string()]
+            )
+            .is_err()
+    );
+
+    // The limit counts characters, not bytes
+    database
+        .insert_into_table(
+            "users",
+            vec!["4".to_string(), "é".repeat(200), "ñandú".to_string()],
+        )
+        .unwrap();
+
+    let mut default = _prepare_database();
+    let columns = vec!["id num pk".to_string(), "bio txt".to_string()];
+    default.create_table("users".to_string(), columns).unwrap();
+    assert!(
+        default
+            .insert_into_table("users", vec!["1".to_string(), bio])
+            .is_err()
+    );
+}
+
+#[test]
+fn database_get_table_by_ref() {
+    let mut database = _prepare_database();
+    let columns = vec!["id num pk".to_string()];
+    _create_table(&mut database, "items".to_string(), columns, vec![]).unwrap();
+
+    let table = database.get_table_by_ref("items").unwrap();
+    assert_eq!(table.read().unwrap().column_count(), 1);
+    assert!(database.get_table_by_ref("missing").is_none());
+}
+
+#[test]
+fn registry_create_duplicate_database() {
+    let mut registry = DatabaseRegistry::new();
+    let shop = registry.create_database("shop", false).unwrap();
+
+    assert_eq!(
+        registry.create_database("shop", false).err(),
+        Some("Integrity violation; database shop already exists".to_string())
+    );
+    let existing = registry.create_database("shop", true).unwrap();
+    assert!(Arc::ptr_eq(&shop, &existing));
+    assert_eq!(registry.get_database_names(), vec!["shop".to_string()]);
+}
+
+#[test]
+fn registry_get_and_drop_database() {
+    let mut registry = DatabaseRegistry::new();
+    registry.create_database("shop", false).unwrap();
+
+    assert_eq!(
+        registry
+            .get_database("shop")
+            .unwrap()
+            .read()
+            .unwrap()
+            .name(),
+        "shop"
+    );
+    assert_eq!(
+        registry.get_database("shops").err(),
+        Some("invalid database shops: does not exist, did you mean 'shop'?".to_string())
+    );
+
+    assert!(registry.drop_database("shop").is_some());
+    assert!(registry.drop_database("shop").is_none());
+    assert!(registry.get_database("shop").is_err());
+}
@@ -0,0 +1,131 @@
+use ferrum_engine::errors::EngineError;
+use ferrum_engine::persistence::{Row, Table};
+
+fn _create_table() -> Table {
+    let mut table = Table::new(
+        "items".to_string(),
+        vec![
+            "id num pk".to_string(),
+            "name txt".to_string(),
+            "price num".to_string(),
+        ],
+    )
+    .unwrap();
+
+    for id in 1..=20 {
+        table
+            .insert(vec![
+                id.to_string(),
+                format!("item{}", id),
+                (id * 5 % 7).to_string(),
+            ])
+            .unwrap();
+    }
+
+    table
+}
+
+fn _is_cheap(row: &Row) -> bool {
+    row.get_str(1).unwrap().parse::<i64>().unwrap() < 4
+}
+
+#[test]
+fn lazy_chain_matches_eager_chain() {
+    let table = _create_table();
+    let fields = vec!["name".to_string(), "price".to_string()];
+
+    let eager = table
+        .reader()
+        .select(fields.clone())
+        .unwrap()
+        .filter(_is_cheap)
+        .unwrap()
+        .offset(Some(1))
+        .unwrap()
+        .limit(Some(3))
+        .unwrap();
+
+    let reader = table.reader();
+    let lazy = reader
+        .lazy()
+        .select(fields)
+        .unwrap()
+        .filter(_is_cheap)
+        .unwrap()
+        .offset(Some(1))
+        .unwrap()
+        .limit(Some(3))
+        .unwrap()
+        .collect();
+
+    assert_eq!(lazy.count_rows(), 3);
+    assert_eq!(lazy.grid(), eager.grid());
+}
+
+#[test]
+fn lazy_filter_sees_the_columns_of_earlier_steps() {
+    let table = _create_table();
+    let reader = table.reader();
+
+    let before_select = reader
+        .lazy()
+        .filter(|row| row.get_str(0).as_deref() == Some("3"))
+        .unwrap()
+        .select(vec!["name".to_string()])
+        .unwrap()
+        .scan();
+    let after_select = reader
+        .lazy()
+        .select(vec!["name".to_string()])
+        .unwrap()
+        .filter(|row| row.get_str(0).as_deref() == Some("item3"))
+        .unwrap()
+        .scan();
+
+    let cells = |rows: Vec<Row>| rows.into_iter().map(|row| row.cells()).collect::<Vec<_>>();
+    assert_eq!(cells(before_select), vec![vec![Some("item3".to_string())]]);
+    assert_eq!(cells(after_select), vec![vec![Some("item3".to_string())]]);
+}
+
+#[test]
+fn lazy_limit_before_filter_counts_unfiltered_rows() {
+    let table = _create_table();
+    let reader = table.reader();
+
+    let rows = reader
+        .lazy()
+        .limit(Some(5))
+        .unwrap()
+        .filter(|row| row.get_str(2).as_deref() == Some("3"))
+        .unwrap()
+        .scan();
+
+    // Only ids 1 to 5 are considered, and only id 2 is priced 3 among them.
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get_str(0).as_deref(), Some("2"));
+}
+
+#[test]
+fn lazy_select_rejects_unknown_columns() {
+    let table = _create_table();
+    let reader = table.reader();
+
+    let result = reader.lazy().select(vec!["weight".to_string()]);
+
+    assert_eq!(
+        result.err(),
+        Some(EngineError::ColumnNotFound(
+            "invalid column weight: does not exist".to_string()
+        ))
+    );
+}
+
+#[test]
+fn lazy_empty_chain_keeps_every_row() {
+    let table = _create_table();
+    let reader = table.reader();
+
+    let collected = reader.lazy().collect();
+
+    assert_eq!(collected.grid(), table.reader().grid());
+}
@@ -1,2 +1,6 @@
 mod database;
+mod lazy;
+mod row;
+mod schema;
 mod table;
+mod value;
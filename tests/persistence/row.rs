@@ -0,0 +1,57 @@
+use ferrum_engine::persistence::{Row, Value};
+
+fn _row() -> Row {
+    Row(vec![
+        Value::Number(1),
+        Value::Null,
+        Value::Text("Pen".to_string()),
+    ])
+}
+
+#[test]
+fn row_get_reads_cells() {
+    let row = _row();
+
+    assert_eq!(row.get(0), Some(&Value::Number(1)));
+    assert_eq!(row.get(1), Some(&Value::Null));
+    assert_eq!(row.get(3), None);
+}
+
+#[test]
+fn row_get_str_reads_values() {
+    let row = _row();
+
+    assert_eq!(row.get_str(2).as_deref(), Some("Pen"));
+    assert_eq!(row.get_str(1).as_deref(), None);
+    assert_eq!(row.get_str(3).as_deref(), None);
+}
+
+#[test]
+fn row_is_null_only_for_null_cells() {
+    let row = _row();
+
+    assert!(row.is_null(1));
+    assert!(!row.is_null(0));
+    assert!(!row.is_null(3));
+}
+
+#[test]
+fn row_format_with_quotes_ambiguous_values() {
+    let row = Row(vec![
+        Value::Text("a | b".to_string()),
+        Value::Null,
+        Value::Text("NIL".to_string()),
+        Value::Text("say \"hi\"".to_string()),
+        Value::Text("plain".to_string()),
+    ]);
+
+    assert_eq!(
+        row.format_with(" | "),
+        "\"a | b\" | NIL | \"NIL\" | \"say \"\"hi\"\"\" | plain"
+    );
+    assert_eq!(
+        row.format_with(","),
+        "a | b,NIL,\"NIL\",\"say \"\"hi\"\"\",plain"
+    );
+    assert_eq!(_row().format_with("\t"), "1\tNIL\tPen");
+}
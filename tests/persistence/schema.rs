@@ -0,0 +1,121 @@
+use ferrum_engine::persistence::{ColumnInformation, DataType, Schema};
+
+fn _schema(columns: &[&str]) -> Schema {
+    Schema::new(
+        columns
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    ColumnInformation::from(DataType::Text, None, true),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn _linear_column_index(schema: &Schema, column_name: &str) -> Option<usize> {
+    schema
+        .get_vec()
+        .iter()
+        .position(|(name, _)| name == column_name)
+}
+
+#[test]
+fn schema_column_index_matches_linear_scan() {
+    for columns in [
+        ["id", "name", "price", "stock"],
+        ["stock", "price", "id", "name"],
+    ] {
+        let schema = _schema(&columns);
+
+        for name in ["id", "name", "price", "stock", "weight"] {
+            assert_eq!(
+                schema.column_index(name),
+                _linear_column_index(&schema, name)
+            );
+        }
+    }
+}
+
+#[test]
+fn schema_column_index_follows_changes() {
+    let mut schema = _schema(&["id", "name", "price"]);
+
+    schema.rename(1, "title".to_string());
+    schema.push("stock".to_string(), ColumnInformation::default());
+
+    assert_eq!(schema.column_index("name"), None);
+    assert_eq!(schema.column_index("title"), Some(1));
+    assert_eq!(schema.column_index("stock"), Some(3));
+
+    schema.truncate(2);
+
+    assert_eq!(schema.column_index("price"), None);
+    assert_eq!(schema.column_index("stock"), None);
+    assert_eq!(schema.column_index("title"), Some(1));
+}
+
+#[test]
+fn schema_column_index_survives_serialization() {
+    let schema = _schema(&["stock", "price", "id"]);
+
+    let json = serde_json::to_string(&schema).unwrap();
+    let restored: Schema = serde_json::from_str(&json).unwrap();
+
+    assert!(json.starts_with("[[\"stock\""));
+    assert_eq!(restored.column_index("id"), Some(2));
+}
+
+#[test]
+fn datatype_coerce_allowed_values() {
+    let coerce = |datatype: DataType, value: &str| datatype.coerce(value).unwrap();
+
+    assert_eq!(coerce(DataType::Text, "42"), "42");
+    assert_eq!(coerce(DataType::Text, "3.0"), "3.0");
+    assert_eq!(coerce(DataType::Number, "+042"), "42");
+    assert_eq!(coerce(DataType::Number, "3.0"), "3");
+    assert_eq!(coerce(DataType::Number, "-7.000"), "-7");
+    assert_eq!(coerce(DataType::Decimal(5, 2), "5"), "5.00");
+    assert_eq!(coerce(DataType::Date, "2024-2-9"), "2024-02-09");
+    assert_eq!(coerce(DataType::Json, "{ \"a\": 1 }"), "{\"a\":1}");
+    assert_eq!(coerce(DataType::Bool, "TRUE"), "true");
+    assert_eq!(coerce(DataType::Bool, "0"), "false");
+    assert_eq!(
+        coerce(DataType::Uuid, "67E55044-10B1-426F-9247-BB680E5FE0C8"),
+        "67e55044-10b1-426f-9247-bb680e5fe0c8"
+    );
+    assert_eq!(
+        coerce(DataType::Enum(vec!["open".to_string()]), "open"),
+        "open"
+    );
+}
+
+#[test]
+fn datatype_coerce_rejects_other_values() {
+    assert_eq!(
+        DataType::Number.coerce("3.5").err(),
+        Some("not a whole number".to_string())
+    );
+    assert!(DataType::Number.coerce("1e3").is_err());
+    assert!(DataType::Number.coerce("").is_err());
+    assert!(DataType::Decimal(5, 2).coerce("seven").is_err());
+    assert!(DataType::Date.coerce("2024-02-30").is_err());
+    assert_eq!(
+        DataType::Bool.coerce("yes").err(),
+        Some("not true or false".to_string())
+    );
+    assert!(
+        DataType::Enum(vec!["open".to_string()])
+            .coerce("Open")
+            .is_err()
+    );
+}
+
+#[test]
+fn schema_format_with_joins_column_names() {
+    let schema = _schema(&["id", "name", "a,b"]);
+
+    assert_eq!(schema.format_with(" | "), "id | name | a,b");
+    assert_eq!(schema.format_with(","), "id,name,\"a,b\"");
+}
@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
-use ferrum_engine::persistence::{Row, Table};
+use chrono::NaiveDateTime;
+use ferrum_engine::cli::{FunctionArg, FunctionType, SelectColumn};
+use ferrum_engine::errors::EngineError;
+use ferrum_engine::functions::scalars::TIMESTAMP_FORMAT;
+use ferrum_engine::persistence::{DataType, NULL_TOKEN, Row, Table, Value};
+use ferrum_engine::serialization::csv;
 
 fn _create_table(columns: Vec<&str>) -> Result<Table, String> {
     let name = "test_table".to_string();
@@ -25,6 +30,32 @@ fn table_does_not_create_with_improper_types() {
     _create_table(columns).unwrap();
 }
 
+#[test]
+fn table_insert_stores_typed_values() {
+    let mut table =
+        _create_table(vec!["id num pk", "name txt", "price num null", "sold bool"]).unwrap();
+
+    let row = table
+        .insert_values(vec![
+            Some("1".to_string()),
+            Some("Pen".to_string()),
+            None,
+            Some("TRUE".to_string()),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        row.0,
+        vec![
+            Value::Number(1),
+            Value::Text("Pen".to_string()),
+            Value::Null,
+            Value::Bool(true),
+        ]
+    );
+    assert_eq!(row.get_str(3).as_deref(), Some("true"));
+}
+
 #[test]
 fn table_reader_scan_nonempty() {
     let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
@@ -48,7 +79,7 @@ fn table_reader_scan_nonempty() {
 
     let check_name = "Jansen".to_string();
     assert_eq!(
-        rows.get(0).unwrap().0.get(1).unwrap().as_ref(),
+        rows.get(0).unwrap().cells().get(1).unwrap().as_ref(),
         Some(&check_name)
     );
 }
@@ -62,6 +93,32 @@ fn table_reader_scan_empty() {
     assert_eq!(rows.len(), 0);
 }
 
+#[test]
+fn table_reader_scan_named() {
+    let mut table = _create_table(vec!["id num pk", "name txt null"]).unwrap();
+    table
+        .insert(vec!["1".to_string(), "Jansen".to_string()])
+        .unwrap();
+    table.insert(vec!["2".to_string(), "".to_string()]).unwrap();
+
+    let rows = table.reader().scan_named();
+
+    assert_eq!(
+        rows[0],
+        HashMap::from([
+            ("id".to_string(), Some("1".to_string())),
+            ("name".to_string(), Some("Jansen".to_string())),
+        ])
+    );
+    assert_eq!(
+        rows[1],
+        HashMap::from([
+            ("id".to_string(), Some("2".to_string())),
+            ("name".to_string(), None)
+        ])
+    );
+}
+
 #[test]
 fn table_reader_filter_nonempty() {
     let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
@@ -81,7 +138,7 @@ fn table_reader_filter_nonempty() {
     let reader = table.reader();
 
     // filtering all items with id >= 2 (should be 3 rows id 2, 3, and 4)
-    let filter = |row: &Row| match row.0.get(0) {
+    let filter = |row: &Row| match row.cells().get(0) {
         Some(Some(value)) => value.parse::<u32>().unwrap() >= 2,
         _ => false,
     };
@@ -91,7 +148,7 @@ fn table_reader_filter_nonempty() {
 
     let check_name = "Bonega".to_string();
     assert_eq!(
-        rows.get(0).unwrap().0.get(1).unwrap().as_ref(),
+        rows.get(0).unwrap().cells().get(1).unwrap().as_ref(),
         Some(&check_name),
     );
 }
@@ -104,7 +161,7 @@ fn table_reader_filter_returns_empty() {
     let reader = table.reader();
     let rows = reader
         .filter(|row| {
-            row.0[0]
+            row.cells()[0]
                 .as_ref()
                 .and_then(|s| s.parse::<u32>().ok())
                 .map_or(false, |id| id > 100)
@@ -122,7 +179,10 @@ fn table_reader_filter_handles_null_values() {
     table.insert(vec!["1".to_string()]).unwrap();
 
     let reader = table.reader();
-    let rows = reader.filter(|row| row.0[0].is_some()).unwrap().scan();
+    let rows = reader
+        .filter(|row| row.cells()[0].is_some())
+        .unwrap()
+        .scan();
 
     assert_eq!(rows.len(), 1);
 }
@@ -147,10 +207,28 @@ fn table_reader_select_single_column() {
     let ids_only = reader.select(vec!["id".to_string()]).unwrap();
 
     for (row, (id, _)) in ids_only.scan().iter().zip(values) {
-        assert_eq!(row.0.get(0).unwrap().as_deref(), Some(id))
+        assert_eq!(row.cells().get(0).unwrap().as_deref(), Some(id))
     }
 }
 
+#[test]
+fn table_reader_select_suggests_closest_column() {
+    let table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+
+    assert_eq!(
+        table.reader().select(vec!["nmae".to_string()]).err(),
+        Some(EngineError::ColumnNotFound(
+            "invalid column nmae: does not exist, did you mean 'name'?".to_string()
+        ))
+    );
+    assert_eq!(
+        table.reader().select(vec!["weight".to_string()]).err(),
+        Some(EngineError::ColumnNotFound(
+            "invalid column weight: does not exist".to_string()
+        ))
+    );
+}
+
 #[test]
 fn table_reader_select_multiple_columns() {
     let mut table = _create_table(vec!["id num pk", "name txt", "age num"]).unwrap();
@@ -167,8 +245,8 @@ fn table_reader_select_multiple_columns() {
     let results = selected.scan();
 
     // Schema should be reordered: name, id (not id, name)
-    assert_eq!(results[0].0[0].as_ref().unwrap(), "Alice");
-    assert_eq!(results[0].0[1].as_ref().unwrap(), "1");
+    assert_eq!(results[0].cells()[0].as_ref().unwrap(), "Alice");
+    assert_eq!(results[0].cells()[1].as_ref().unwrap(), "1");
 }
 
 #[test]
@@ -206,6 +284,62 @@ fn table_insert_many_error() {
     assert_eq!(table.reader().scan().len(), 3);
 }
 
+#[test]
+fn table_insert_negative_number() {
+    let mut table = _create_table(vec!["id num pk", "balance num"]).unwrap();
+
+    let row = table
+        .insert(vec!["1".to_string(), "-250".to_string()])
+        .unwrap();
+
+    assert_eq!(row.get_str(1).as_deref(), Some("-250"));
+}
+
+#[test]
+fn table_insert_number_errors_name_the_problem() {
+    let mut table = _create_table(vec!["id num pk", "balance num"]).unwrap();
+    let mut insert = |balance: &str| {
+        table
+            .insert(vec!["1".to_string(), balance.to_string()])
+            .err()
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(
+        insert("9223372036854775808"),
+        "invalid 9223372036854775808: above the maximum of 9223372036854775807, on column 'balance' (NUM)"
+    );
+    assert_eq!(
+        insert("-9223372036854775809"),
+        "invalid -9223372036854775809: below the minimum of -9223372036854775808, on column 'balance' (NUM)"
+    );
+    assert_eq!(
+        insert("2.5"),
+        "invalid 2.5: not a whole number, on column 'balance' (NUM)"
+    );
+}
+
+#[test]
+fn table_insert_coerces_values() {
+    let mut table = _create_table(vec!["id num pk", "code txt", "stock num"]).unwrap();
+    table
+        .insert(vec!["1".to_string(), "42".to_string(), "3.0".to_string()])
+        .unwrap();
+
+    let rows = table.reader().scan();
+    assert_eq!(rows[0].get_str(1).as_deref(), Some("42"));
+    assert_eq!(rows[0].get_str(2).as_deref(), Some("3"));
+    assert_eq!(
+        table
+            .insert(vec!["2".to_string(), "43".to_string(), "3.5".to_string()])
+            .err(),
+        Some(EngineError::TypeMismatch(
+            "invalid 3.5: not a whole number, on column 'stock' (NUM)".to_string()
+        ))
+    );
+}
+
 #[test]
 fn table_insert_row_count() {
     let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
@@ -247,7 +381,10 @@ fn table_update_noerror() {
     let rows = reader.scan();
 
     assert_eq!(cols_updated, 1);
-    assert_eq!(rows[3].0[1].as_ref().unwrap(), updates.get("name").unwrap());
+    assert_eq!(
+        rows[3].cells()[1].as_ref().unwrap(),
+        updates.get("name").unwrap()
+    );
 }
 
 #[test]
@@ -275,7 +412,10 @@ fn table_update_error() {
     let rows = reader.scan();
 
     assert_eq!(cols_updated, 1);
-    assert_eq!(rows[3].0[1].as_ref().unwrap(), updates.get("name").unwrap());
+    assert_eq!(
+        rows[3].cells()[1].as_ref().unwrap(),
+        updates.get("name").unwrap()
+    );
 }
 
 #[test]
@@ -296,10 +436,10 @@ fn table_delete_not_indexed() {
     let deletion_pk = vec!["1"];
 
     let deleted_row = table.delete(deletion_pk).unwrap();
-    assert_eq!(deleted_row.0[0], Some("1".to_string()));
+    assert_eq!(deleted_row.cells()[0], Some("1".to_string()));
 
     let reader = table.reader();
-    assert_eq!(reader.scan()[1].0[0], Some("3".to_string()));
+    assert_eq!(reader.scan()[1].cells()[0], Some("3".to_string()));
 }
 
 #[test]
@@ -320,8 +460,1084 @@ fn table_delete_indexed() {
     let deletion_pk = vec!["1"];
 
     let deleted_row = table.delete(deletion_pk).unwrap();
-    assert_eq!(deleted_row.0[0], Some("1".to_string()));
+    assert_eq!(deleted_row.cells()[0], Some("1".to_string()));
 
     let reader = table.reader();
-    assert_eq!(reader.scan()[1].0[0], Some("3".to_string()));
+    assert_eq!(reader.scan()[1].cells()[0], Some("3".to_string()));
+}
+
+#[test]
+fn table_reader_grid_dimensions_and_nulls() {
+    let mut table = _create_table(vec!["id num pk", "name txt", "age num"]).unwrap();
+    table
+        .insert(vec!["1".to_string(), "Alice".to_string(), "30".to_string()])
+        .unwrap();
+
+    let reader = table.reader();
+    reader
+        .rows
+        .write()
+        .unwrap()
+        .push(Row(vec![Value::Number(2), Value::Null, Value::Null]));
+
+    let (headers, grid) = reader.grid();
+
+    assert_eq!(headers, vec!["id", "name", "age"]);
+    assert_eq!(grid.len(), 2);
+    assert!(grid.iter().all(|row| row.len() == headers.len()));
+    assert_eq!(grid[1], vec!["2", NULL_TOKEN, NULL_TOKEN]);
+}
+
+#[test]
+#[should_panic(expected = "duplicate value on unique column 'email'")]
+fn table_insert_unique_duplicate_rejected() {
+    let mut table = _create_table(vec!["id num pk", "email txt unique"]).unwrap();
+
+    table
+        .insert(vec!["1".to_string(), "a@fe.io".to_string()])
+        .unwrap();
+    table
+        .insert(vec!["2".to_string(), "a@fe.io".to_string()])
+        .unwrap();
+}
+
+#[test]
+fn table_insert_unique_allows_repeated_nulls() {
+    let mut table = _create_table(vec!["id num pk", "email txt unique null"]).unwrap();
+    let values = [("1", "a@fe.io"), ("2", ""), ("3", ""), ("4", "b@fe.io")]
+        .iter()
+        .map(|(id, email)| vec![id.to_string(), email.to_string()])
+        .collect();
+
+    let num_insertions = table.insert_many(values);
+    assert_eq!(num_insertions.unwrap(), 4);
+    assert_eq!(table.reader().scan()[2].cells()[1], None);
+}
+
+#[test]
+fn table_insert_auto_increment_sequential() {
+    let mut table = _create_table(vec!["id num pk auto", "name txt"]).unwrap();
+
+    for name in ["Jansen", "Bonega", "Lorem"] {
+        table
+            .insert(vec!["".to_string(), name.to_string()])
+            .unwrap();
+    }
+    table.delete(vec!["3"]).unwrap();
+    table
+        .insert(vec!["".to_string(), "Ipsum".to_string()])
+        .unwrap();
+
+    let ids: Vec<Option<String>> = table
+        .reader()
+        .scan()
+        .iter()
+        .map(|row| row.cells()[0].clone())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            Some("1".to_string()),
+            Some("2".to_string()),
+            Some("4".to_string())
+        ]
+    );
+}
+
+#[test]
+fn table_insert_auto_increment_mixed_explicit() {
+    let mut table = _create_table(vec!["id num pk auto", "name txt"]).unwrap();
+
+    table
+        .insert(vec!["".to_string(), "Jansen".to_string()])
+        .unwrap();
+    table
+        .insert(vec!["10".to_string(), "Bonega".to_string()])
+        .unwrap();
+    table
+        .insert(vec!["5".to_string(), "Lorem".to_string()])
+        .unwrap();
+    let row = table
+        .insert(vec!["".to_string(), "Ipsum".to_string()])
+        .unwrap();
+
+    assert_eq!(row.cells()[0], Some("11".to_string()));
+}
+
+#[test]
+fn table_composite_key_lookup_and_delete() {
+    let mut table = _create_table(vec!["user_id num pk", "order_id num pk", "amount num"]).unwrap();
+    let values = [("1", "1", "100"), ("1", "2", "250"), ("2", "1", "75")]
+        .iter()
+        .map(|(user_id, order_id, amount)| {
+            vec![
+                user_id.to_string(),
+                order_id.to_string(),
+                amount.to_string(),
+            ]
+        })
+        .collect();
+
+    table.insert_many(values).unwrap();
+
+    let row = table.get_row(vec!["1", "2"]).unwrap().unwrap();
+    assert_eq!(row.cells()[2], Some("250".to_string()));
+    assert!(table.get_row(vec!["2", "2"]).unwrap().is_none());
+    assert!(table.get_row(vec!["1"]).is_err());
+
+    let deleted_row = table.delete(vec!["1", "1"]).unwrap();
+    assert_eq!(deleted_row.cells()[2], Some("100".to_string()));
+
+    let row = table.get_row(vec!["2", "1"]).unwrap().unwrap();
+    assert_eq!(row.cells()[2], Some("75".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "duplicate primary key")]
+fn table_composite_key_duplicate_rejected() {
+    let mut table = _create_table(vec!["user_id num pk", "order_id num pk", "amount num"]).unwrap();
+
+    table
+        .insert(vec!["1".to_string(), "2".to_string(), "100".to_string()])
+        .unwrap();
+    table
+        .insert(vec!["1".to_string(), "2".to_string(), "300".to_string()])
+        .unwrap();
+}
+
+#[test]
+fn table_composite_key_values_do_not_collide() {
+    let mut table = _create_table(vec!["first txt pk", "second txt pk"]).unwrap();
+
+    table
+        .insert(vec!["a:b".to_string(), "c".to_string()])
+        .unwrap();
+    table
+        .insert(vec!["a".to_string(), "b:c".to_string()])
+        .unwrap();
+
+    assert_eq!(table._rows(), 2);
+}
+
+fn _aggregate(name: &str, arg: FunctionArg) -> SelectColumn {
+    SelectColumn::Function {
+        name: name.to_string(),
+        args: vec![arg],
+        function_type: FunctionType::Aggregator,
+        alias: None,
+    }
+}
+
+#[test]
+fn table_fused_group_aggregate_matches_staged() {
+    let mut table = _create_table(vec!["id num pk", "region txt", "sales num null"]).unwrap();
+    let values = [
+        ("1", "north", "10"),
+        ("2", "south", "4"),
+        ("3", "north", ""),
+        ("4", "east", "7"),
+        ("5", "south", "12"),
+        ("6", "north", "30"),
+        ("7", "east", "1"),
+    ]
+    .iter()
+    .map(|(id, region, sales)| vec![id.to_string(), region.to_string(), sales.to_string()])
+    .collect();
+    table.insert_many(values).unwrap();
+
+    let group_columns = vec!["region".to_string()];
+    let select_columns = vec![
+        SelectColumn::Column {
+            name: "region".to_string(),
+            alias: None,
+        },
+        _aggregate("SUM", FunctionArg::Column("sales".to_string())),
+        _aggregate("COUNT", FunctionArg::Column("sales".to_string())),
+        _aggregate("COUNT", FunctionArg::Wildcard),
+        _aggregate("MAX", FunctionArg::Column("id".to_string())),
+    ];
+    let filter = |row: &Row| row.cells()[0].as_ref().unwrap() != "7";
+
+    let staged = table
+        .reader()
+        .filter(filter)
+        .unwrap()
+        .group_by(&group_columns, &select_columns)
+        .unwrap();
+    let fused = table
+        .perform_group_aggregate(Some(&filter), &group_columns, &select_columns)
+        .unwrap();
+
+    assert_eq!(staged.grid(), fused.grid());
+    assert_eq!(
+        fused.grid().1,
+        vec![
+            vec!["north", "40", "2", "3", "6"],
+            vec!["south", "16", "2", "2", "5"],
+            vec!["east", "7", "1", "1", "4"],
+        ]
+    );
+}
+
+#[test]
+fn table_date_column_accepts_valid_date() {
+    let mut table = _create_table(vec!["id num pk", "joined date"]).unwrap();
+
+    table
+        .insert(vec!["1".to_string(), "2023-02-28".to_string()])
+        .unwrap();
+    let row = table
+        .insert(vec!["2".to_string(), "2024-2-9".to_string()])
+        .unwrap();
+
+    assert_eq!(row.cells()[1], Some("2024-02-09".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "invalid 2023-13-40: not a YYYY-MM-DD date, on column 'joined' (DATE)")]
+fn table_date_column_rejects_out_of_range_date() {
+    let mut table = _create_table(vec!["id num pk", "joined date"]).unwrap();
+
+    table
+        .insert(vec!["1".to_string(), "2023-13-40".to_string()])
+        .unwrap();
+}
+
+#[test]
+fn date_datatype_display() {
+    assert_eq!(DataType::Date.to_string(), "DATE");
+}
+
+#[test]
+fn table_rename_column() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    table
+        .insert(vec!["1".to_string(), "Jansen".to_string()])
+        .unwrap();
+
+    table.rename_column("name", "full_name").unwrap();
+    assert!(table.rename_column("full_name", "id").is_err());
+    assert!(table.rename_column("missing", "other").is_err());
+    assert!(table.rename_column("full_name", "pk").is_err());
+
+    let reader = table
+        .reader()
+        .select(vec!["full_name".to_string()])
+        .unwrap();
+    assert_eq!(reader.grid().1, vec![vec!["Jansen".to_string()]]);
+}
+
+#[test]
+fn table_export_csv_round_trips_rows() {
+    let mut table = _create_table(vec!["id num pk", "name txt", "note txt null"]).unwrap();
+    table
+        .insert(vec![
+            "1".to_string(),
+            "Jansen, Bo".to_string(),
+            "said \"hi\"".to_string(),
+        ])
+        .unwrap();
+    table
+        .insert(vec!["2".to_string(), "Bonega".to_string(), "".to_string()])
+        .unwrap();
+
+    let path = std::env::temp_dir().join("ferrum_table_export_csv_round_trips_rows.csv");
+    table.export_csv(&path).unwrap();
+
+    let records = csv::read_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let expected: Vec<Vec<String>> = table
+        .reader()
+        .scan()
+        .into_iter()
+        .map(|row| {
+            row.cells()
+                .into_iter()
+                .map(|cell| cell.unwrap_or_default())
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(records[0], vec!["id", "name", "note"]);
+    assert_eq!(records[1..].to_vec(), expected);
+    assert_eq!(records[1][1], "Jansen, Bo");
+    assert_eq!(records[2][2], "");
+}
+
+#[test]
+fn table_export_csv_streaming_round_trips_rows() {
+    let mut table = _create_table(vec!["id num pk", "name txt", "note txt null"]).unwrap();
+    table
+        .insert(vec![
+            "1".to_string(),
+            "Jansen, Bo".to_string(),
+            "said \"hi\"".to_string(),
+        ])
+        .unwrap();
+    table
+        .insert(vec!["2".to_string(), "Bonega".to_string(), "".to_string()])
+        .unwrap();
+
+    let mut buffer = Vec::new();
+    table.export_csv_streaming(&mut buffer).unwrap();
+
+    let records = csv::parse(&String::from_utf8(buffer).unwrap()).unwrap();
+    assert_eq!(
+        records,
+        vec![
+            vec!["id", "name", "note"],
+            vec!["1", "Jansen, Bo", "said \"hi\""],
+            vec!["2", "Bonega", ""],
+        ]
+    );
+}
+
+/// A writer that only remembers how much it was handed at once.
+struct WriteSizes {
+    total: usize,
+    largest: usize,
+}
+
+impl std::io::Write for WriteSizes {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.total += buf.len();
+        self.largest = self.largest.max(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn table_export_csv_streaming_writes_large_table_row_by_row() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    let rows = (0..20_000)
+        .map(|id| vec![id.to_string(), format!("name {}", id)])
+        .collect();
+    table.insert_many(rows).unwrap();
+
+    let mut sizes = WriteSizes {
+        total: 0,
+        largest: 0,
+    };
+    table.export_csv_streaming(&mut sizes).unwrap();
+
+    // Every write holds a single record, never the whole export
+    assert!(sizes.total > 200_000);
+    assert!(sizes.largest < 32);
+}
+
+#[test]
+fn table_reader_to_json() {
+    let mut table = _create_table(vec!["id num pk", "name txt null"]).unwrap();
+    table
+        .insert(vec!["1".to_string(), "Jansen \"Bo\"".to_string()])
+        .unwrap();
+    table.insert(vec!["2".to_string(), "".to_string()]).unwrap();
+
+    assert_eq!(
+        table.reader().to_json(),
+        r#"[{"id":1,"name":"Jansen \"Bo\""},{"id":2,"name":null}]"#
+    );
+}
+
+#[test]
+fn table_reader_to_json_keeps_decimal_digits() {
+    let mut table = _create_table(vec!["id num pk", "price dec(10,2)", "total dec(18,2)"]).unwrap();
+    table
+        .insert(vec![
+            "1".to_string(),
+            "5".to_string(),
+            "9876543210123456.78".to_string(),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        table.reader().to_json(),
+        r#"[{"id":1,"price":"5.00","total":"9876543210123456.78"}]"#
+    );
+}
+
+#[test]
+fn table_counts_for_many_columns() {
+    let definitions: Vec<String> = (0..200)
+        .map(|index| format!("c{} num null", index))
+        .collect();
+    let mut table = _create_table(definitions.iter().map(|def| def.as_str()).collect()).unwrap();
+
+    assert_eq!(table.column_count(), 200);
+    assert_eq!(table.row_count(), 0);
+    assert_eq!(table.reader().rows.read().unwrap().capacity(), 0);
+
+    table.insert(vec!["1".to_string(); 200]).unwrap();
+    assert_eq!(table.row_count(), 1);
+}
+
+#[test]
+fn table_rows_after_inserts_and_deletes() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    assert!(table.is_empty());
+
+    for (id, name) in [("1", "Jansen"), ("2", "Bonega"), ("3", "Lorem")] {
+        table
+            .insert(vec![id.to_string(), name.to_string()])
+            .unwrap();
+    }
+    assert_eq!(table.rows(), 3);
+
+    table.delete(vec!["2"]).unwrap();
+    assert_eq!(table.rows(), 2);
+    assert!(!table.is_empty());
+
+    table.delete_all();
+    assert_eq!(table.rows(), 0);
+    assert!(table.is_empty());
+}
+
+#[test]
+fn table_reader_aggregate_over_filtered_rows() {
+    let mut table = _create_table(vec!["id num pk", "name txt", "price num"]).unwrap();
+    for (id, name, price) in [
+        ("1", "Pen", "20"),
+        ("2", "Lamp", "60"),
+        ("3", "Desk", "150"),
+    ] {
+        table
+            .insert(vec![id.to_string(), name.to_string(), price.to_string()])
+            .unwrap();
+    }
+
+    let filter = |row: &Row| match row.cells().get(2) {
+        Some(Some(value)) => value.parse::<u64>().unwrap() > 50,
+        _ => false,
+    };
+    let reader = table.reader().filter(filter).unwrap();
+
+    assert_eq!(
+        reader.aggregate("SUM", vec!["2".into()]),
+        Ok("210".to_string())
+    );
+    assert_eq!(
+        reader.aggregate("count", vec!["*".into()]),
+        Ok("2".to_string())
+    );
+    assert!(reader.aggregate("MEDIAN", vec!["2".into()]).is_err());
+}
+
+fn _range_ids(table: &Table, low: i64, high: i64) -> Vec<String> {
+    table
+        .range_reader("score", low, high)
+        .unwrap()
+        .scan()
+        .iter()
+        .map(|row| row.get_str(0).unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn table_ordered_index_serves_range_lookups() {
+    let mut table = _create_table(vec!["id num pk", "score num null"]).unwrap();
+    for id in 1..=30 {
+        let score = if id == 15 {
+            String::new()
+        } else {
+            (id * 2).to_string()
+        };
+        table.insert(vec![id.to_string(), score]).unwrap();
+    }
+
+    assert!(table.range_reader("score", 0, 100).is_none());
+    table.create_ordered_index("score").unwrap();
+
+    assert_eq!(
+        _range_ids(&table, 20, 30),
+        vec!["10", "11", "12", "13", "14"]
+    );
+    assert_eq!(_range_ids(&table, 29, 33), vec!["16"]);
+    assert!(_range_ids(&table, 30, 20).is_empty());
+
+    table.delete(vec!["11"]).unwrap();
+    let updates = HashMap::from([("score".to_string(), "1".to_string())]);
+    table.update(vec!["30"], &updates).unwrap();
+    table
+        .insert(vec!["31".to_string(), "24".to_string()])
+        .unwrap();
+
+    assert_eq!(
+        _range_ids(&table, 20, 30),
+        vec!["10", "12", "13", "14", "31"]
+    );
+    assert_eq!(_range_ids(&table, 0, 2), vec!["1", "30"]);
+}
+
+#[test]
+fn table_ordered_index_only_on_numeric_columns() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+
+    assert!(table.create_ordered_index("name").is_err());
+    assert!(table.create_ordered_index("missing").is_err());
+}
+
+#[test]
+fn table_reader_for_each_visits_every_row() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    for id in 1..=5 {
+        table
+            .insert(vec![id.to_string(), format!("name{}", id)])
+            .unwrap();
+    }
+
+    let reader = table.reader();
+    let mut ids = vec![];
+    reader.for_each(|row| ids.push(row.get_str(0).unwrap().to_string()));
+
+    assert_eq!(ids, vec!["1", "2", "3", "4", "5"]);
+    assert_eq!(reader.borrow_rows().len(), 5);
+}
+
+#[test]
+fn table_reader_for_each_on_large_table() {
+    let mut table = _create_table(vec!["id num pk", "amount num"]).unwrap();
+    let n_rows = 20_000;
+    for id in 0..n_rows {
+        table.insert(vec![id.to_string(), "2".to_string()]).unwrap();
+    }
+    let reader = table.reader();
+
+    let started = std::time::Instant::now();
+    let mut total = 0;
+    reader.for_each(|row| total += row.get_str(1).unwrap().parse::<u64>().unwrap());
+    let borrowed = started.elapsed();
+
+    let started = std::time::Instant::now();
+    let scanned: u64 = reader
+        .scan()
+        .iter()
+        .map(|row| row.get_str(1).unwrap().parse::<u64>().unwrap())
+        .sum();
+    let cloned = started.elapsed();
+
+    assert_eq!(total, 2 * n_rows);
+    assert_eq!(scanned, total);
+    println!("for_each: {:?}, scan: {:?}", borrowed, cloned);
+}
+
+#[test]
+fn table_reader_scan_where_matches_filter_select_scan() {
+    let mut table = _create_table(vec!["id num pk", "name txt", "age num"]).unwrap();
+    for (id, name, age) in [("1", "ana", "31"), ("2", "bob", "17"), ("3", "cid", "45")] {
+        table
+            .insert(vec![id.to_string(), name.to_string(), age.to_string()])
+            .unwrap();
+    }
+    let adult = |row: &Row| row.get_str(2).unwrap().parse::<i64>().unwrap() >= 18;
+    let columns = vec!["name".to_string(), "id".to_string()];
+
+    let shortcut = table.reader().scan_where(columns.clone(), adult).unwrap();
+    let chained = table
+        .reader()
+        .filter(adult)
+        .unwrap()
+        .select(columns)
+        .unwrap()
+        .scan();
+
+    let cells = |rows: Vec<Row>| rows.into_iter().map(|row| row.0).collect::<Vec<_>>();
+    assert_eq!(cells(shortcut.clone()), cells(chained));
+    assert_eq!(shortcut[1].get_str(0).as_deref(), Some("cid"));
+}
+
+#[test]
+fn table_reader_scan_where_rejects_unknown_columns() {
+    let table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+
+    let result = table.reader().scan_where(vec!["age".to_string()], |_| true);
+
+    assert_eq!(
+        result.err(),
+        Some("invalid column age: does not exist".to_string())
+    );
+}
+
+#[test]
+fn table_delete_many_scattered_keys() {
+    let mut table = _create_table(vec!["id num pk", "code txt unique", "score num"]).unwrap();
+    table.create_ordered_index("score").unwrap();
+    for id in 1..=10 {
+        table
+            .insert(vec![
+                id.to_string(),
+                format!("c{}", id),
+                (id * 10).to_string(),
+            ])
+            .unwrap();
+    }
+
+    let deleted = table.delete_many(vec![vec!["9"], vec!["2"], vec!["5"], vec!["6"]]);
+
+    assert_eq!(deleted, Ok(4));
+    let ids: Vec<String> = table
+        .reader()
+        .scan()
+        .iter()
+        .map(|row| row.get_str(0).unwrap().to_string())
+        .collect();
+    assert_eq!(ids, vec!["1", "3", "4", "7", "8", "10"]);
+    for id in ["1", "3", "4", "7", "8", "10"] {
+        let row = table.get_row(vec![id]).unwrap().unwrap();
+        assert_eq!(row.get_str(0).as_deref(), Some(id));
+    }
+    assert!(table.get_row(vec!["5"]).unwrap().is_none());
+    assert!(
+        table
+            .insert(vec!["11".to_string(), "c5".to_string(), "55".to_string()])
+            .is_ok()
+    );
+    assert!(
+        table
+            .insert(vec!["12".to_string(), "c7".to_string(), "1".to_string()])
+            .is_err()
+    );
+    let ranged: Vec<String> = table
+        .range_reader("score", 30, 80)
+        .unwrap()
+        .scan()
+        .iter()
+        .map(|row| row.get_str(0).unwrap().to_string())
+        .collect();
+    assert_eq!(ranged, vec!["3", "4", "7", "8", "11"]);
+}
+
+#[test]
+fn table_delete_many_stops_at_a_missing_key() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    for id in 1..=4 {
+        table.insert(vec![id.to_string(), "x".to_string()]).unwrap();
+    }
+
+    let missing = table.delete_many(vec![vec!["1"], vec!["7"], vec!["3"]]);
+    let repeated = table.delete_many(vec![vec!["2"], vec!["2"]]);
+
+    assert!(missing.is_err());
+    assert!(repeated.is_err());
+    assert_eq!(table.row_count(), 2);
+    assert!(table.get_row(vec!["3"]).unwrap().is_some());
+    assert!(table.get_row(vec!["4"]).unwrap().is_some());
+}
+
+#[test]
+fn table_delete_many_on_large_table() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    let n_rows = 20_000;
+    for id in 0..n_rows {
+        table.insert(vec![id.to_string(), "x".to_string()]).unwrap();
+    }
+    let even_ids: Vec<String> = (0..n_rows).step_by(2).map(|id| id.to_string()).collect();
+
+    let started = std::time::Instant::now();
+    let deleted = table.delete_many(even_ids.iter().map(|id| vec![id.as_str()]).collect());
+    println!(
+        "delete_many of {} rows: {:?}",
+        even_ids.len(),
+        started.elapsed()
+    );
+
+    assert_eq!(deleted, Ok(n_rows / 2));
+    assert_eq!(table.row_count(), n_rows / 2);
+    assert!(table.get_row(vec!["19999"]).unwrap().is_some());
+    assert!(table.get_row(vec!["19998"]).unwrap().is_none());
+}
+
+#[test]
+fn table_timestamps_are_maintained_on_insert_and_update() {
+    let mut table = _create_table(vec![
+        "id num pk",
+        "name txt",
+        "created_at txt created",
+        "updated_at txt updated",
+    ])
+    .unwrap();
+    let inserted = table
+        .insert(vec![
+            "1".to_string(),
+            "Jansen".to_string(),
+            "".to_string(),
+            "".to_string(),
+        ])
+        .unwrap();
+    let created_at = inserted.cells()[2].clone().unwrap();
+    assert!(NaiveDateTime::parse_from_str(&created_at, TIMESTAMP_FORMAT).is_ok());
+    assert_eq!(inserted.cells()[3], inserted.cells()[2]);
+
+    std::thread::sleep(std::time::Duration::from_millis(1_100));
+    table.reader().scan();
+    table.get_row(vec!["1"]).unwrap();
+    assert_eq!(table.reader().scan()[0].0, inserted.0);
+
+    let updates = HashMap::from([("name".to_string(), "Bonega".to_string())]);
+    table.update(vec!["1"], &updates).unwrap();
+
+    let row = table.get_row(vec!["1"]).unwrap().unwrap();
+    assert_eq!(row.cells()[2], Some(created_at.clone()));
+    assert!(row.cells()[3].clone().unwrap() > created_at);
+}
+
+#[test]
+fn table_timestamps_reject_explicit_updates() {
+    let mut table = _create_table(vec!["id num pk", "created_at txt created"]).unwrap();
+    table
+        .insert(vec!["1".to_string(), "1999-01-01 00:00:00".to_string()])
+        .unwrap();
+
+    let updates = HashMap::from([("created_at".to_string(), "2000-01-01".to_string())]);
+
+    assert_eq!(
+        table.update(vec!["1"], &updates).err(),
+        Some("invalid column created_at: maintained by the engine".to_string())
+    );
+    assert_ne!(
+        table.reader().scan()[0].cells()[1],
+        Some("1999-01-01 00:00:00".to_string())
+    );
+    assert!(_create_table(vec!["id num pk created"]).is_err());
+}
+
+fn _names(rows: Vec<Row>) -> Vec<String> {
+    rows.iter()
+        .map(|row| row.get_str(1).unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn table_delete_soft_hides_rows_from_readers() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    for (id, name) in [("1", "Jansen"), ("2", "Bonega"), ("3", "Lorem")] {
+        table
+            .insert(vec![id.to_string(), name.to_string()])
+            .unwrap();
+    }
+
+    let deleted = table.delete_soft(vec!["2"]).unwrap();
+
+    assert_eq!(deleted.get_str(1).as_deref(), Some("Bonega"));
+    assert_eq!(table.row_count(), 2);
+    assert_eq!(_names(table.reader().scan()), vec!["Jansen", "Lorem"]);
+    assert_eq!(
+        _names(table.reader().filter(|_| true).unwrap().scan()),
+        vec!["Jansen", "Lorem"]
+    );
+    assert!(table.get_row(vec!["2"]).unwrap().is_none());
+    assert!(table.delete_soft(vec!["2"]).is_err());
+    assert_eq!(
+        _names(table.reader().with_deleted().scan()),
+        vec!["Jansen", "Bonega", "Lorem"]
+    );
+}
+
+#[test]
+fn table_update_soft_deleted_row_fails() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    for (id, name) in [("1", "Jansen"), ("2", "Bonega")] {
+        table
+            .insert(vec![id.to_string(), name.to_string()])
+            .unwrap();
+    }
+    table.delete_soft(vec!["2"]).unwrap();
+    let updates = HashMap::from([("name".to_string(), "Lorem".to_string())]);
+
+    assert_eq!(
+        table.update(vec!["2"], &updates).err(),
+        Some("err: invalid key; no match for this index".to_string())
+    );
+    assert_eq!(table.update(vec!["1"], &updates), Ok(1));
+}
+
+#[test]
+fn table_delete_soft_rows_are_recoverable() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+    for (id, name) in [("1", "Jansen"), ("2", "Bonega"), ("3", "Lorem")] {
+        table
+            .insert(vec![id.to_string(), name.to_string()])
+            .unwrap();
+    }
+    table.delete_soft(vec!["2"]).unwrap();
+    table.delete(vec!["1"]).unwrap();
+
+    assert!(table.recover(vec!["3"]).is_err());
+    let recovered = table.recover(vec!["2"]).unwrap();
+
+    assert_eq!(recovered.get_str(1).as_deref(), Some("Bonega"));
+    assert_eq!(_names(table.reader().scan()), vec!["Bonega", "Lorem"]);
+    assert_eq!(
+        table
+            .get_row(vec!["2"])
+            .unwrap()
+            .unwrap()
+            .get_str(1)
+            .as_deref(),
+        Some("Bonega")
+    );
+}
+
+#[test]
+fn table_vacuum_reclaims_soft_deleted_rows() {
+    let mut table = _create_table(vec!["id num pk", "name txt unique"]).unwrap();
+    for id in 1..=6 {
+        table
+            .insert(vec![id.to_string(), format!("name{}", id)])
+            .unwrap();
+    }
+    for id in ["2", "4", "5"] {
+        table.delete_soft(vec![id]).unwrap();
+    }
+
+    assert_eq!(table.vacuum(), 3);
+
+    assert_eq!(table.reader().with_deleted().count_rows(), 3);
+    assert_eq!(
+        _names(table.reader().scan()),
+        vec!["name1", "name3", "name6"]
+    );
+    for id in ["1", "3", "6"] {
+        let row = table.get_row(vec![id]).unwrap().unwrap();
+        assert_eq!(row.get_str(0).as_deref(), Some(id));
+    }
+    table
+        .insert(vec!["4".to_string(), "name4".to_string()])
+        .unwrap();
+    assert!(
+        table
+            .insert(vec!["7".to_string(), "name6".to_string()])
+            .is_err()
+    );
+    assert_eq!(table.vacuum(), 0);
+}
+
+#[test]
+fn table_restore_reverts_to_snapshot() {
+    let mut table = _create_table(vec!["id num pk auto", "name txt unique", "score num"]).unwrap();
+    table.create_ordered_index("score").unwrap();
+    for (name, score) in [("Jansen", "30"), ("Bonega", "40"), ("Lorem", "50")] {
+        table
+            .insert(vec!["".to_string(), name.to_string(), score.to_string()])
+            .unwrap();
+    }
+    table.delete_soft(vec!["3"]).unwrap();
+    let snapshot = table.snapshot();
+    let before = table.reader().with_deleted().grid();
+
+    table
+        .insert(vec!["".to_string(), "Ipsum".to_string(), "60".to_string()])
+        .unwrap();
+    table.delete(vec!["1"]).unwrap();
+    table.recover(vec!["3"]).unwrap();
+    table.rename_column("name", "full_name").unwrap();
+    let mut updates = HashMap::new();
+    updates.insert("full_name".to_string(), "Dolor".to_string());
+    table.update(vec!["2"], &updates).unwrap();
+
+    let version = table.version();
+    table.restore(snapshot);
+
+    assert_ne!(table.version(), version);
+    assert_eq!(table.reader().with_deleted().grid(), before);
+    assert_eq!(_names(table.reader().scan()), vec!["Jansen", "Bonega"]);
+    assert!(table.get_row(vec!["3"]).unwrap().is_none());
+    assert_eq!(
+        table
+            .get_row(vec!["1"])
+            .unwrap()
+            .unwrap()
+            .get_str(1)
+            .as_deref(),
+        Some("Jansen")
+    );
+    assert_eq!(_range_ids(&table, 30, 40), vec!["1", "2"]);
+    assert!(
+        table
+            .insert(vec!["".to_string(), "Bonega".to_string(), "70".to_string()])
+            .is_err()
+    );
+    let row = table
+        .insert(vec!["".to_string(), "Ipsum".to_string(), "60".to_string()])
+        .unwrap();
+    assert_eq!(row.get_str(0).as_deref(), Some("4"));
+}
+
+#[test]
+fn table_insert_validates_json() {
+    let mut table = _create_table(vec!["id num pk", "body json"]).unwrap();
+
+    let row = table
+        .insert(vec![
+            "1".to_string(),
+            r#"{ "name": "Jansen",  "tags": [1, 2] }"#.to_string(),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        row.get_str(1).as_deref(),
+        Some(r#"{"name":"Jansen","tags":[1,2]}"#)
+    );
+    assert_eq!(
+        table
+            .insert(vec!["2".to_string(), "{ name: Jansen".to_string()])
+            .err(),
+        Some(EngineError::TypeMismatch(
+            "invalid { name: Jansen: not a JSON document, on column 'body' (JSON)".to_string()
+        ))
+    );
+}
+
+#[test]
+fn table_insert_validates_uuid() {
+    let mut table = _create_table(vec!["id uuid pk", "name txt"]).unwrap();
+
+    let row = table
+        .insert(vec![
+            "67E55044-10B1-426F-9247-BB680E5FE0C8".to_string(),
+            "Jansen".to_string(),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        row.get_str(0).as_deref(),
+        Some("67e55044-10b1-426f-9247-bb680e5fe0c8")
+    );
+    for malformed in [
+        "67e55044-10b1-426f-9247",
+        "67e5504410b1426f9247bb680e5fe0c8",
+        "67e55044-10b1-426f-9247-bb680e5fe0cz",
+    ] {
+        assert_eq!(
+            table
+                .insert(vec![malformed.to_string(), "Bonega".to_string()])
+                .err(),
+            Some(EngineError::TypeMismatch(format!(
+                "invalid {}: not a canonical UUID, on column 'id' (UUID)",
+                malformed
+            )))
+        );
+    }
+}
+
+#[test]
+fn table_insert_normalizes_decimal() {
+    let mut table = _create_table(vec!["id num pk", "price dec(5,2)"]).unwrap();
+
+    let row = table
+        .insert(vec!["1".to_string(), "123.4".to_string()])
+        .unwrap();
+    assert_eq!(row.get_str(1).as_deref(), Some("123.40"));
+
+    let row = table
+        .insert(vec!["2".to_string(), "5".to_string()])
+        .unwrap();
+    assert_eq!(row.get_str(1).as_deref(), Some("5.00"));
+
+    assert_eq!(
+        table
+            .insert(vec!["3".to_string(), "1234.5".to_string()])
+            .err(),
+        Some(EngineError::TypeMismatch(
+            "invalid 1234.5: more than 3 digit(s) before the point, on column 'price' (DEC(5,2))"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        table
+            .insert(vec!["3".to_string(), "1.234".to_string()])
+            .err(),
+        Some(EngineError::TypeMismatch(
+            "invalid 1.234: more than 2 decimal place(s), on column 'price' (DEC(5,2))".to_string()
+        ))
+    );
+    assert!(_create_table(vec!["id num pk", "price dec(2,3)"]).is_err());
+}
+
+#[test]
+fn table_insert_validates_enum() {
+    let mut table = _create_table(vec!["id num pk", "status enum(open,closed,pending)"]).unwrap();
+
+    let row = table
+        .insert(vec!["1".to_string(), "closed".to_string()])
+        .unwrap();
+    assert_eq!(row.get_str(1).as_deref(), Some("closed"));
+
+    assert_eq!(
+        table
+            .insert(vec!["2".to_string(), "archived".to_string()])
+            .err(),
+        Some(EngineError::TypeMismatch(
+            "invalid archived: expected one of open, closed, pending, on column 'status' (ENUM)"
+                .to_string()
+        ))
+    );
+    assert!(_create_table(vec!["id num pk", "status enum(open,,closed)"]).is_err());
+}
+
+#[test]
+fn table_check_constraint_on_insert_and_update() {
+    let mut table = Table::new(
+        "members".to_string(),
+        vec!["id num pk".to_string(), "age num".to_string()],
+    )
+    .unwrap();
+    table.add_check("age", "age >= 0").unwrap();
+
+    table
+        .insert(vec!["1".to_string(), "30".to_string()])
+        .unwrap();
+    assert_eq!(
+        table.insert(vec!["2".to_string(), "-1".to_string()]).err(),
+        Some(EngineError::Other(
+            "invalid -1: violates check constraint members_age_check (age >= 0), on column 'age'"
+                .to_string()
+        ))
+    );
+
+    let updates = HashMap::from([("age".to_string(), "31".to_string())]);
+    assert_eq!(table.update(vec!["1"], &updates).unwrap(), 1);
+
+    let updates = HashMap::from([("age".to_string(), "-5".to_string())]);
+    assert_eq!(
+        table.update(vec!["1"], &updates).err(),
+        Some(
+            "invalid -5: violates check constraint members_age_check (age >= 0), on column 'age'"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        table
+            .get_row(vec!["1"])
+            .unwrap()
+            .unwrap()
+            .get_str(1)
+            .as_deref(),
+        Some("31")
+    );
+}
+
+#[test]
+fn table_check_constraint_keeps_literals_whole() {
+    let mut table = _create_table(vec!["id num pk", "name txt"]).unwrap();
+
+    table.add_check("name", "name <> '( x )'").unwrap();
+    assert!(
+        table
+            .insert(vec!["1".to_string(), "( x )".to_string()])
+            .is_err()
+    );
+    assert!(table.insert(vec!["2".to_string(), "x".to_string()]).is_ok());
+
+    assert_eq!(
+        table.add_check("name", "name <> ''").err(),
+        Some("invalid check on column name: only one check allowed".to_string())
+    );
+    assert!(table.add_check("id", "id >").is_err());
+    assert!(table.add_check("id", "id > 0) OR (id < 0").is_err());
+    assert!(table.add_check("missing", "missing > 0").is_err());
 }
@@ -0,0 +1,48 @@
+use ferrum_engine::persistence::{DataType, Decimal, NULL_TOKEN, Value};
+
+fn _round_trip(datatype: &DataType, value: Value) -> Value {
+    let cell = value.to_cell();
+    Value::parse(datatype, cell.as_deref())
+}
+
+#[test]
+fn value_round_trips_through_cells() {
+    let cases = [
+        (DataType::Number, Value::Null),
+        (DataType::Number, Value::Number(42)),
+        (DataType::Number, Value::Float(2.5)),
+        (DataType::Text, Value::Text("Pen".to_string())),
+        (DataType::Bool, Value::Bool(true)),
+        (DataType::Bool, Value::Bool(false)),
+        (DataType::Date, Value::Text("2024-02-09".to_string())),
+        (
+            DataType::Decimal(10, 2),
+            Value::Decimal(Decimal::parse("5.00").unwrap()),
+        ),
+    ];
+
+    for (datatype, value) in cases {
+        assert_eq!(_round_trip(&datatype, value.clone()), value);
+    }
+}
+
+#[test]
+fn value_parses_by_column_type() {
+    assert_eq!(
+        Value::parse(&DataType::Number, Some("20")),
+        Value::Number(20)
+    );
+    assert_eq!(
+        Value::parse(&DataType::Text, Some("20")),
+        Value::Text("20".to_string())
+    );
+    assert_eq!(Value::parse(&DataType::Text, None), Value::Null);
+    assert_eq!(
+        Value::parse(&DataType::Bool, Some("true")),
+        Value::Bool(true)
+    );
+
+    assert_eq!(Value::Text("Pen".to_string()).as_str(), Some("Pen"));
+    assert_eq!(Value::Number(20).as_str(), None);
+    assert_eq!(Value::Null.to_string(), NULL_TOKEN);
+}
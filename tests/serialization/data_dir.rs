@@ -0,0 +1,56 @@
+use ferrum_engine::{
+    persistence::{DatabaseRegistry, Value},
+    serialization::data_dir::{load_data_dir, save_data_dir},
+};
+
+fn _fresh_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn load_data_dir_skips_corrupt_files() {
+    let dir = _fresh_dir("ferrum_load_data_dir_skips_corrupt_files");
+    let mut registry = DatabaseRegistry::new();
+    let shop = registry.create_database("shop", false).unwrap();
+    {
+        let mut shop = shop.write().unwrap();
+        shop.create_table(
+            "items".to_string(),
+            vec!["id num pk".to_string(), "name txt".to_string()],
+        )
+        .unwrap();
+        shop.insert_into_table("items", vec!["1".to_string(), "Anvil".to_string()])
+            .unwrap();
+    }
+    save_data_dir(&registry, &dir).unwrap();
+    std::fs::write(dir.join("broken.fe"), "{ not a database").unwrap();
+
+    let mut loaded = DatabaseRegistry::new();
+    let report = load_data_dir(&mut loaded, &dir).unwrap();
+
+    assert_eq!(report.loaded, vec!["shop".to_string()]);
+    assert_eq!(report.skipped.len(), 1);
+    assert!(report.skipped[0].contains("broken.fe"));
+    assert_eq!(loaded.get_database_names(), vec!["shop".to_string()]);
+    let shop = loaded.get_database("shop").unwrap();
+    let items = shop.read().unwrap().get_table("items").unwrap();
+    let rows = items.read().unwrap().reader().scan();
+    assert_eq!(
+        rows[0].0,
+        vec![Value::Number(1), Value::Text("Anvil".to_string())]
+    );
+}
+
+#[test]
+fn load_data_dir_creates_a_missing_directory() {
+    let dir = _fresh_dir("ferrum_load_data_dir_creates_a_missing_directory");
+    let mut registry = DatabaseRegistry::new();
+
+    let report = load_data_dir(&mut registry, &dir).unwrap();
+
+    assert!(dir.is_dir());
+    assert!(report.loaded.is_empty());
+    assert!(registry.get_database_names().is_empty());
+}
@@ -0,0 +1,25 @@
+use ferrum_engine::persistence::Table;
+use ferrum_engine::sessions::cache::ResultCache;
+
+#[test]
+fn result_cache_forgets_least_recently_used() {
+    let table = Table::new("items".to_string(), vec!["id num pk".to_string()]).unwrap();
+    let reader = table.reader();
+    let mut cache = ResultCache::new(2);
+
+    cache.insert("a".to_string(), 1, &reader);
+    cache.insert("b".to_string(), 1, &reader);
+    assert!(cache.get("a", 1).is_some());
+    cache.insert("c".to_string(), 1, &reader);
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("b", 1).is_none());
+    assert!(cache.get("a", 1).is_some());
+
+    // A result of another version is stale, and forgotten
+    assert!(cache.get("c", 2).is_none());
+    assert_eq!(cache.len(), 1);
+
+    cache.set_capacity(0);
+    assert!(cache.is_empty());
+}
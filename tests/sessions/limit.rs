@@ -0,0 +1,50 @@
+use std::{thread, time::Duration};
+
+use ferrum_engine::sessions::limit::{ConnectionLimit, TOO_MANY_CONNECTIONS_MESSAGE, WhenFull};
+
+#[test]
+fn connection_limit_refuses_overflow() {
+    let limit = ConnectionLimit::new(2, WhenFull::Refuse);
+    let first = limit.acquire().unwrap();
+    let _second = limit.acquire().unwrap();
+
+    assert_eq!(
+        limit.acquire().err(),
+        Some(TOO_MANY_CONNECTIONS_MESSAGE.to_string())
+    );
+    assert_eq!(limit.active(), 2);
+
+    drop(first);
+    assert!(limit.acquire().is_ok());
+}
+
+#[test]
+fn connection_limit_queues_overflow_until_a_permit_frees() {
+    let limit = ConnectionLimit::new(1, WhenFull::Queue(Duration::from_secs(5)));
+    let permit = limit.acquire().unwrap();
+
+    let waiting = {
+        let limit = limit.clone();
+        thread::spawn(move || limit.acquire().map(|_| ()))
+    };
+    while limit.queued() == 0 {
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(limit.active(), 1);
+
+    drop(permit);
+    assert!(waiting.join().unwrap().is_ok());
+    assert_eq!((limit.active(), limit.queued()), (0, 0));
+}
+
+#[test]
+fn connection_limit_refuses_queued_connection_after_timeout() {
+    let limit = ConnectionLimit::new(1, WhenFull::Queue(Duration::from_millis(30)));
+    let _permit = limit.acquire().unwrap();
+
+    assert_eq!(
+        limit.acquire().err(),
+        Some(TOO_MANY_CONNECTIONS_MESSAGE.to_string())
+    );
+    assert_eq!(limit.queued(), 0);
+}
@@ -0,0 +1,4 @@
+mod cache;
+mod limit;
+mod pool;
+mod session;
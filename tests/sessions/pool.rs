@@ -0,0 +1,56 @@
+use std::{
+    sync::{Arc, RwLock},
+    thread,
+    time::Duration,
+};
+
+use ferrum_engine::{
+    persistence::DatabaseRegistry,
+    sessions::pool::{SESSION_EXPIRED_MESSAGE, SessionPool},
+};
+
+#[test]
+fn sweep_idle_closes_idle_sessions() {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    let mut pool = SessionPool::new(&registry, Duration::from_millis(50));
+    let idle = pool.connect();
+    let busy = pool.connect();
+
+    thread::sleep(Duration::from_millis(80));
+    pool.get(busy).unwrap();
+    let expired = pool.sweep_idle();
+
+    assert_eq!(expired, vec![idle]);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(
+        pool.get(idle).err(),
+        Some(SESSION_EXPIRED_MESSAGE.to_string())
+    );
+    assert!(pool.get(busy).is_ok());
+}
+
+#[test]
+fn sweep_idle_rolls_back_open_transactions() {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    let mut pool = SessionPool::new(&registry, Duration::from_millis(10));
+    let id = pool.connect();
+    let database = {
+        let session = pool.get(id).unwrap();
+        let mut session = session.write().unwrap();
+        let database = session.create_database("shop", false).unwrap();
+        session.use_database("shop").unwrap();
+        session.begin_transaction().unwrap();
+        database
+            .write()
+            .unwrap()
+            .create_table("items".to_string(), vec!["id num pk".to_string()])
+            .unwrap();
+        database
+    };
+
+    thread::sleep(Duration::from_millis(30));
+    pool.sweep_idle();
+
+    assert!(pool.is_empty());
+    assert!(!database.read().unwrap().contains_table("items"));
+}
@@ -0,0 +1,154 @@
+use std::sync::{Arc, RwLock};
+
+use ferrum_engine::{
+    persistence::DatabaseRegistry,
+    sessions::{auth, event_log::Event, session::Session},
+};
+
+fn _prepare_session() -> Session {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    Session::client(&registry)
+}
+
+#[test]
+fn session_reset_clears_state() {
+    let mut session = _prepare_session();
+    session.create_database("test_db", false).unwrap();
+    session.use_database("test_db").unwrap();
+    session.add_to_command_history("USE test_db");
+    session.set_variable("prompt", "fe");
+
+    session.reset();
+
+    assert!(session.get_active_database().is_none());
+    assert!(session.get_last_command(1).is_none());
+    assert!(session.get_variable("prompt").is_none());
+    assert_eq!(session.get_available_databases(), vec!["test_db"]);
+}
+
+#[test]
+fn session_transaction_requires_database_and_single_begin() {
+    let mut session = _prepare_session();
+    assert!(session.begin_transaction().is_err());
+
+    session.create_database("test_db", false).unwrap();
+    session.use_database("test_db").unwrap();
+
+    session.begin_transaction().unwrap();
+    assert!(session.in_transaction());
+    assert!(session.begin_transaction().is_err());
+
+    session.commit_transaction().unwrap();
+    assert!(session.commit_transaction().is_err());
+}
+
+#[test]
+fn session_config_changes_take_effect() {
+    let mut session = _prepare_session();
+    assert_eq!(session.config().prompt, "ferrum");
+    assert!(session.config().timing);
+
+    session.set_config("prompt", "fe").unwrap();
+    session.set_config("timing", "off").unwrap();
+    assert_eq!(session.config().prompt, "fe");
+    assert!(!session.config().timing);
+
+    assert!(session.set_config("timing", "sometimes").is_err());
+    assert!(session.set_config("prompt", "").is_err());
+    assert!(session.set_config("theme", "dark").is_err());
+}
+
+#[test]
+fn session_reset_selects_default_database() {
+    let mut session = _prepare_session();
+    session.create_database("test_db", false).unwrap();
+    session.set_config("default_database", "test_db").unwrap();
+
+    session.reset();
+
+    let database = session.get_active_database().unwrap();
+    assert_eq!(database.read().unwrap().name(), "test_db");
+}
+
+#[test]
+fn session_authenticate_checks_password_hash() {
+    let mut session = _prepare_session();
+    assert!(!session.has_users());
+    assert!(session.authenticate("alice", "secret").is_err());
+
+    session.create_user("alice", "secret").unwrap();
+    assert!(session.has_users());
+
+    assert!(session.authenticate("alice", "wrong").is_err());
+    assert!(session.authenticate("bob", "secret").is_err());
+    assert!(session.current_user().is_none());
+
+    session.authenticate("alice", "secret").unwrap();
+    assert_eq!(session.current_user(), Some("alice"));
+}
+
+#[test]
+fn session_can_not_reach_system_database() {
+    let mut session = _prepare_session();
+    session.create_user("alice", "secret").unwrap();
+    session.create_database("shop", false).unwrap();
+
+    assert!(session.use_database(auth::SYSTEM_DATABASE).is_err());
+    assert!(session.drop_database(auth::SYSTEM_DATABASE).is_err());
+    assert!(
+        session
+            .create_database(auth::SYSTEM_DATABASE, true)
+            .is_err()
+    );
+    assert_eq!(session.get_available_databases(), vec!["shop"]);
+
+    session.authenticate("alice", "secret").unwrap();
+}
+
+#[test]
+fn users_table_stores_salted_hashes() {
+    let registry = Arc::new(RwLock::new(DatabaseRegistry::new()));
+    let mut session = Session::client(&registry);
+    session.create_user("alice", "secret").unwrap();
+    session.create_user("bob", "secret").unwrap();
+    assert!(session.create_user("alice", "other").is_err());
+
+    let registry = registry.read().unwrap();
+    let database = registry.get_database(auth::SYSTEM_DATABASE).unwrap();
+    let table = database
+        .read()
+        .unwrap()
+        .get_table(auth::USERS_TABLE)
+        .unwrap();
+    let rows = table.read().unwrap().reader().scan();
+
+    let hashes: Vec<String> = rows
+        .iter()
+        .map(|row| row.get_str(2).unwrap().into_owned())
+        .collect();
+    assert_ne!(hashes[0], hashes[1]);
+    assert!(hashes.iter().all(|hash| !hash.contains("secret")));
+}
+
+#[test]
+fn session_log_event_appends_lines() {
+    let mut session = _prepare_session();
+    let path = std::env::temp_dir().join("ferrum_session_log_event_appends_lines.log");
+    let _ = std::fs::remove_file(&path);
+
+    session.log_event(Event::Connection { user: None }).unwrap();
+    session.set_event_log(&path);
+    session
+        .log_event(Event::Connection {
+            user: Some("ana".to_string()),
+        })
+        .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].contains(&format!(
+        "\"session_start\":\"{}\",\"event\":\"connection\",\"user\":\"ana\"",
+        session.start_time_string()
+    )));
+}